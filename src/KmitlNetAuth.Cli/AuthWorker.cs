@@ -1,3 +1,4 @@
+using KmitlNetAuth.Core;
 using KmitlNetAuth.Core.Services;
 using Microsoft.Extensions.Hosting;
 using Microsoft.Extensions.Logging;
@@ -7,11 +8,15 @@ namespace KmitlNetAuth.Cli;
 public sealed class AuthWorker : BackgroundService
 {
     private readonly IAuthService _authService;
+    private readonly Config _config;
+    private readonly IHostApplicationLifetime _lifetime;
     private readonly ILogger<AuthWorker> _logger;
 
-    public AuthWorker(IAuthService authService, ILogger<AuthWorker> logger)
+    public AuthWorker(IAuthService authService, Config config, IHostApplicationLifetime lifetime, ILogger<AuthWorker> logger)
     {
         _authService = authService;
+        _config = config;
+        _lifetime = lifetime;
         _logger = logger;
     }
 
@@ -19,18 +24,35 @@ public sealed class AuthWorker : BackgroundService
     {
         _logger.LogInformation("KMITL NetAuth service worker started.");
 
+        // max_runtime cancels the same way a stop signal does, so logout_on_exit and the status file still run
+        using var runtimeCts = CancellationTokenSource.CreateLinkedTokenSource(stoppingToken);
+        if (_config.MaxRuntime > 0)
+        {
+            runtimeCts.CancelAfter(TimeSpan.FromSeconds(_config.MaxRuntime));
+            _logger.LogInformation("Will exit after max_runtime of {MaxRuntime}s.", _config.MaxRuntime);
+        }
+
         try
         {
-            await _authService.RunAsync(stoppingToken);
+            await _authService.RunSupervisedAsync(_logger, runtimeCts.Token);
         }
         catch (OperationCanceledException)
         {
-            _logger.LogInformation("KMITL NetAuth service worker stopping.");
         }
         catch (Exception e)
         {
             _logger.LogError(e, "KMITL NetAuth service worker encountered an error.");
             throw;
         }
+
+        if (!stoppingToken.IsCancellationRequested && runtimeCts.IsCancellationRequested)
+        {
+            _logger.LogInformation("Reached max_runtime of {MaxRuntime}s, shutting down.", _config.MaxRuntime);
+            _lifetime.StopApplication();
+        }
+        else
+        {
+            _logger.LogInformation("KMITL NetAuth service worker stopping.");
+        }
     }
 }
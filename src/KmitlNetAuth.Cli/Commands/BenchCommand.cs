@@ -0,0 +1,96 @@
+using System.Diagnostics;
+using KmitlNetAuth.Core;
+using KmitlNetAuth.Core.Platform;
+using KmitlNetAuth.Core.Services;
+using Microsoft.Extensions.Logging.Abstractions;
+using Spectre.Console;
+
+namespace KmitlNetAuth.Cli.Commands;
+
+public static class BenchCommand
+{
+    // Back-to-back cycles would look like a flood to the portal
+    private static readonly TimeSpan CycleGap = TimeSpan.FromSeconds(1);
+
+    /// <summary>
+    /// Runs <paramref name="cycles"/> cycles the way the service does (connectivity check, heartbeat while
+    /// online, login when offline or the heartbeat fails), prints each one as a tick line and then the
+    /// min/avg/max/p95 time of every step, for putting numbers on a slow portal.
+    /// </summary>
+    public static async Task<int> ExecuteAsync(string? configPath, int cycles, CancellationToken ct)
+    {
+        var config = Config.Load(ConfigPaths.Resolve(configPath));
+        var store = config.UseCredentialStore ? CredentialStoreFactory.Create() : null;
+        if (string.IsNullOrEmpty(config.Username))
+        {
+            AnsiConsole.MarkupLine("[red]No username configured.[/] Run 'kmitlnetauth setup' first.");
+            return 1;
+        }
+
+        using var http = PortalHttpClient.Create(config);
+        var client = new AuthClient(http.Client, config, new NetworkInfo(), store,
+            new NullNotificationService(), NullLogger<AuthClient>.Instance);
+
+        var canLogin = !config.MonitorOnly && config.GetPasswordSource(store) != CredentialSource.None;
+        var heartbeatOnly = config.ConnectivityCheck.Equals("heartbeat_only", StringComparison.OrdinalIgnoreCase);
+        var checks = new List<TimeSpan>();
+        var heartbeats = new List<TimeSpan>();
+        var logins = new List<TimeSpan>();
+
+        AnsiConsole.MarkupLine($"Running {cycles} cycle(s) for [bold]{Markup.Escape(config.Username)}[/]" +
+            (canLogin ? "" : " [grey](no login: monitor-only or no password)[/]"));
+        try
+        {
+            for (var cycle = 1; cycle <= cycles; cycle++)
+            {
+                if (cycle > 1)
+                    await Task.Delay(CycleGap, ct);
+
+                var online = await TimeAsync(() => client.CheckConnectivityAsync(ct), checks);
+                var heartbeat = TickStep.Skipped;
+                if (online && !heartbeatOnly)
+                    heartbeat = await TimeAsync(() => client.HeartbeatAsync(ct), heartbeats) ? TickStep.Ok : TickStep.Failed;
+
+                var login = TickStep.Skipped;
+                if (canLogin && (!online || heartbeat == TickStep.Failed))
+                    login = await TimeAsync(() => client.LoginAsync(ct), logins) ? TickStep.Ok : TickStep.Failed;
+
+                Console.WriteLine(new TickOutcome(cycle, online, heartbeat, login, checks[^1]));
+            }
+        }
+        catch (OperationCanceledException)
+        {
+            // Ctrl+C still reports the cycles that ran
+        }
+
+        Console.WriteLine();
+        var table = new Table().AddColumns("Step", "Samples", "Min", "Avg", "Max", "p95");
+        AddRow(table, "check", checks);
+        AddRow(table, "heartbeat", heartbeats);
+        AddRow(table, "login", logins);
+        AnsiConsole.Write(table);
+        return 0;
+    }
+
+    private static async Task<bool> TimeAsync(Func<Task<bool>> step, List<TimeSpan> samples)
+    {
+        var started = Stopwatch.GetTimestamp();
+        var result = await step();
+        samples.Add(Stopwatch.GetElapsedTime(started));
+        return result;
+    }
+
+    private static void AddRow(Table table, string step, List<TimeSpan> samples)
+    {
+        if (LatencySummary.FromSamples(samples) is not { } summary)
+        {
+            table.AddRow(Markup.Escape(step), "0", "-", "-", "-", "-");
+            return;
+        }
+
+        table.AddRow(Markup.Escape(step), summary.Samples.ToString(), Format(summary.Min), Format(summary.Average),
+            Format(summary.Max), Format(summary.P95));
+    }
+
+    private static string Format(TimeSpan latency) => $"{latency.TotalMilliseconds:F0}ms";
+}
@@ -1,5 +1,6 @@
 using System.Diagnostics;
 using KmitlNetAuth.Core;
+using KmitlNetAuth.Core.Exceptions;
 using Spectre.Console;
 
 namespace KmitlNetAuth.Cli.Commands;
@@ -34,4 +35,194 @@ public static class ConfigCommand
 
         return Task.CompletedTask;
     }
+
+    /// <summary>
+    /// Prints the fully resolved config (files, environment, defaults) with the source of each value.
+    /// Plain stdout rather than markup so the output can be redirected into a config file.
+    /// </summary>
+    public static Task<int> PrintEffectiveAsync(string? configPath)
+    {
+        var resolvedPath = ConfigPaths.Resolve(configPath);
+        Console.Write(Config.DescribeEffective(resolvedPath, CredentialStoreFactory.Create()));
+        return Task.FromResult(0);
+    }
+
+    /// <summary>
+    /// Parses and validates the config without starting the service, so a deploy script can check
+    /// a file before restarting. Reads no credentials and sends nothing over the network.
+    /// </summary>
+    public static Task<int> CheckAsync(string? configPath)
+    {
+        var resolvedPath = ConfigPaths.Resolve(configPath);
+
+        Config config;
+        try
+        {
+            config = Config.Load(resolvedPath, throwOnParseError: true);
+        }
+        catch (ConfigException e)
+        {
+            Console.Error.WriteLine($"Invalid config: {e.Message}");
+            return Task.FromResult(1);
+        }
+
+        var errors = config.Validate();
+        if (errors.Count > 0)
+        {
+            Console.Error.WriteLine($"Invalid config: {resolvedPath}");
+            foreach (var error in errors)
+                Console.Error.WriteLine($"  - {error}");
+            return Task.FromResult(1);
+        }
+
+        Console.WriteLine(ConfigPaths.IsEnvironmentOnly(resolvedPath)
+            ? $"OK: environment only (no config file at {resolvedPath})"
+            : $"OK: {resolvedPath}");
+        return Task.FromResult(0);
+    }
+
+    /// <summary>
+    /// Lists every environment variable the config reads, whether it is set and its value (secrets
+    /// redacted), for finding out which ones a container actually passes through.
+    /// </summary>
+    public static Task<int> ListEnvironmentAsync()
+    {
+        var table = new Table().Border(TableBorder.Rounded);
+        table.AddColumn("Variable");
+        table.AddColumn("Overrides");
+        table.AddColumn("Value");
+
+        foreach (var (variable, value) in Config.DescribeEnvironment())
+        {
+            table.AddRow(
+                value != null ? $"[bold]{variable.Name}[/]" : $"[grey]{variable.Name}[/]",
+                Markup.Escape(variable.Overrides),
+                value != null ? Markup.Escape(value) : "[grey]not set[/]");
+        }
+
+        AnsiConsole.Write(table);
+        return Task.FromResult(0);
+    }
+
+    /// <summary>
+    /// Writes the active config to <paramref name="exportPath"/> with every password field and the control API token stripped.
+    /// </summary>
+    public static Task<int> ExportAsync(string? configPath, string exportPath)
+    {
+        var resolvedPath = ConfigPaths.Resolve(configPath);
+        var config = Config.Load(resolvedPath);
+
+        config.Password = null;
+        config.EncryptedPassword = null;
+        config.Passphrase = null;
+        config.ApiToken = null;
+
+        try
+        {
+            config.Save(exportPath);
+        }
+        catch (Exception e)
+        {
+            AnsiConsole.MarkupLine($"[red]Export failed:[/] {Markup.Escape(e.Message)}");
+            return Task.FromResult(1);
+        }
+
+        AnsiConsole.MarkupLine($"[green]Config exported to {Markup.Escape(exportPath)} (password and API token not included)[/]");
+        return Task.FromResult(0);
+    }
+
+    /// <summary>
+    /// Merges the keys present in <paramref name="importPath"/> into the config file, validates the result
+    /// and saves it. Only that file is read and written, so neither the system config nor environment
+    /// overrides end up in it. Nothing is written if validation fails.
+    /// </summary>
+    public static Task<int> ImportAsync(string? configPath, string importPath)
+    {
+        var resolvedPath = ConfigPaths.Resolve(configPath);
+
+        if (!File.Exists(importPath))
+        {
+            AnsiConsole.MarkupLine($"[red]File not found:[/] {Markup.Escape(importPath)}");
+            return Task.FromResult(1);
+        }
+
+        Config config;
+        try
+        {
+            config = Config.LoadFile(resolvedPath);
+            config.MergeFrom(importPath);
+        }
+        catch (ConfigException e)
+        {
+            AnsiConsole.MarkupLine($"[red]Import failed:[/] {Markup.Escape(e.Message)}");
+            return Task.FromResult(1);
+        }
+
+        var errors = config.Validate();
+        if (errors.Count > 0)
+        {
+            AnsiConsole.MarkupLine("[red]Imported config is invalid:[/]");
+            foreach (var error in errors)
+                AnsiConsole.MarkupLine($"  - {Markup.Escape(error)}");
+            return Task.FromResult(1);
+        }
+
+        config.Save(resolvedPath, CredentialStoreFactory.Create());
+
+        AnsiConsole.MarkupLine($"[green]Imported {Markup.Escape(importPath)} into {Markup.Escape(resolvedPath)}[/]");
+        return Task.FromResult(0);
+    }
+
+    /// <summary>Prints one config value, plain so scripts can capture it.</summary>
+    public static Task<int> GetAsync(string? configPath, string key)
+    {
+        var config = Config.Load(ConfigPaths.Resolve(configPath));
+
+        try
+        {
+            Console.WriteLine(config.GetValue(key));
+        }
+        catch (ConfigException e)
+        {
+            AnsiConsole.MarkupLine($"[red]{Markup.Escape(e.Message)}[/]");
+            return Task.FromResult(1);
+        }
+
+        return Task.FromResult(0);
+    }
+
+    /// <summary>
+    /// Sets one value in the config file, validates the result and saves it. Like <see cref="ImportAsync"/>
+    /// it reads and writes only that file. Nothing is written if validation fails.
+    /// </summary>
+    public static Task<int> SetAsync(string? configPath, string key, string value)
+    {
+        var resolvedPath = ConfigPaths.Resolve(configPath);
+
+        Config config;
+        try
+        {
+            config = Config.LoadFile(resolvedPath);
+            config.SetValue(key, value);
+        }
+        catch (ConfigException e)
+        {
+            AnsiConsole.MarkupLine($"[red]{Markup.Escape(e.Message)}[/]");
+            return Task.FromResult(1);
+        }
+
+        var errors = config.Validate();
+        if (errors.Count > 0)
+        {
+            AnsiConsole.MarkupLine("[red]Not saved, the new value is invalid:[/]");
+            foreach (var error in errors)
+                AnsiConsole.MarkupLine($"  - {Markup.Escape(error)}");
+            return Task.FromResult(1);
+        }
+
+        config.Save(resolvedPath, CredentialStoreFactory.Create());
+
+        AnsiConsole.MarkupLine($"[green]{Markup.Escape(key)} = {Markup.Escape(config.GetValue(key))}[/] saved to {Markup.Escape(resolvedPath)}");
+        return Task.FromResult(0);
+    }
 }
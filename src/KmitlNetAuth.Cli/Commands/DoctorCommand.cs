@@ -0,0 +1,227 @@
+using System.Net;
+using System.Net.Security;
+using System.Net.Sockets;
+using KmitlNetAuth.Core;
+using KmitlNetAuth.Core.Exceptions;
+using KmitlNetAuth.Core.Platform;
+using Spectre.Console;
+
+namespace KmitlNetAuth.Cli.Commands;
+
+/// <summary>
+/// Setup troubleshooting: runs independent checks and prints a pass/fail report
+/// with a remediation hint for every failure.
+/// </summary>
+public static class DoctorCommand
+{
+    private sealed record Check(string Name, bool Passed, string Detail, string? Hint = null);
+
+    public static async Task<int> ExecuteAsync(string? configPath)
+    {
+        var resolvedPath = ConfigPaths.Resolve(configPath);
+        var checks = new List<Check>();
+
+        CheckConfigFile(resolvedPath, checks);
+
+        // A file that doesn't load is reported above; the remaining checks run on the defaults and environment
+        Config config;
+        try
+        {
+            config = Config.Load(resolvedPath);
+        }
+        catch (ConfigException)
+        {
+            config = Config.LoadLayered([]);
+        }
+        var store = config.UseCredentialStore ? CredentialStoreFactory.Create() : null;
+
+        checks.Add(await CheckCredentialStoreAsync(config, store));
+        checks.Add(CheckCredentials(config, store));
+        checks.Add(CheckMacAddress(config));
+        checks.AddRange(await CheckPortalAsync(config));
+
+        var table = new Table()
+            .Border(TableBorder.Rounded)
+            .Title("[bold]KMITL NetAuth Doctor[/]");
+
+        table.AddColumn("Check");
+        table.AddColumn("Result");
+        table.AddColumn("Details");
+
+        foreach (var check in checks)
+        {
+            table.AddRow(
+                check.Name,
+                check.Passed ? "[green]PASS[/]" : "[red]FAIL[/]",
+                Markup.Escape(check.Detail));
+        }
+
+        AnsiConsole.Write(table);
+
+        if (checks.All(c => c.Passed))
+        {
+            AnsiConsole.MarkupLine("[green]All checks passed.[/]");
+            return 0;
+        }
+
+        AnsiConsole.MarkupLine("[bold]How to fix:[/]");
+        foreach (var check in checks.Where(c => !c.Passed && c.Hint != null))
+            AnsiConsole.MarkupLine($"  [yellow]{Markup.Escape(check.Name)}:[/] {Markup.Escape(check.Hint!)}");
+
+        return 1;
+    }
+
+    private static void CheckConfigFile(string path, List<Check> checks)
+    {
+        if (!File.Exists(path))
+        {
+            checks.Add(new Check("Config file", false, $"{path} not found",
+                "Run 'kmitlnetauth setup', or pass --config / set KMITL_CONFIG."));
+            return;
+        }
+
+        try
+        {
+            new Config().MergeFrom(path);
+        }
+        catch (ConfigException e)
+        {
+            checks.Add(new Check("Config file", false, e.Message, "Fix the TOML syntax at the reported location."));
+            return;
+        }
+
+        checks.Add(new Check("Config file", true, path));
+
+        var errors = Config.Load(path).Validate();
+        checks.Add(errors.Count == 0
+            ? new Check("Config values", true, "Valid")
+            : new Check("Config values", false, string.Join("; ", errors), "Correct the listed keys in the config file."));
+    }
+
+    private static async Task<Check> CheckCredentialStoreAsync(Config config, ICredentialStore? store)
+    {
+        const string name = "Credential store";
+
+        if (store == null)
+            return new Check(name, true, "Disabled (use_credential_store = false)");
+
+        try
+        {
+            // An unresponsive secret service hangs rather than throws, so bound the probe
+            await store.GetPasswordAsync(string.IsNullOrEmpty(config.Username) ? "doctor" : config.Username)
+                .WaitAsync(TimeSpan.FromSeconds(5));
+            return new Check(name, true, store.GetType().Name);
+        }
+        catch (TimeoutException)
+        {
+            return new Check(name, false, $"{store.GetType().Name} did not respond within 5s",
+                "Run with --no-keyring and supply the password via KMITL_PASSWORD or password_enc.");
+        }
+        catch (Exception e)
+        {
+            return new Check(name, false, $"{store.GetType().Name}: {e.Message}",
+                "Check permissions on the credential file, or run with --no-keyring.");
+        }
+    }
+
+    private static Check CheckCredentials(Config config, ICredentialStore? store)
+    {
+        const string name = "Credentials";
+
+        if (string.IsNullOrEmpty(config.Username))
+            return new Check(name, false, "Username not set", "Run 'kmitlnetauth setup' or set KMITL_USERNAME.");
+
+        if (config.GetPasswordSource(store) is not CredentialSource.None and var source)
+            return new Check(name, true, $"Username '{config.Username}', password from {source.Describe()}");
+
+        var hint = !string.IsNullOrEmpty(config.EncryptedPassword)
+            ? "password_enc is set: provide the passphrase via KMITL_PASSPHRASE."
+            : "Run 'kmitlnetauth setup' or set KMITL_PASSWORD.";
+        return new Check(name, false, $"Username '{config.Username}', no password found", hint);
+    }
+
+    private static Check CheckMacAddress(Config config)
+    {
+        const string name = "MAC address";
+
+        if (NetworkInfo.NormalizeMacAddress(config.MacAddress) is { } overridden)
+            return new Check(name, true, $"{overridden} (from config)");
+
+        var mac = new NetworkInfo().GetMacAddress(config.Interface);
+        if (mac == NetworkInfo.FallbackMacAddress)
+        {
+            var where = config.Interface != null ? $"interface '{config.Interface}'" : "any active interface";
+            return new Check(name, false, $"Could not read a MAC from {where}",
+                "Set [auth] mac_address, or [network] interface to the adapter connected to KMITL.");
+        }
+
+        // Not a failure by itself, but the usual cause of "rejected" on newer laptops
+        if (NetworkInfo.IsLocallyAdministered(mac))
+            return new Check(name, true,
+                $"{mac} (looks randomized; if logins are rejected, disable MAC randomization or pin [auth] mac_address)");
+
+        return new Check(name, true, mac);
+    }
+
+    private static async Task<List<Check>> CheckPortalAsync(Config config)
+    {
+        var checks = new List<Check>();
+
+        if (!Uri.TryCreate(config.PortalUrl, UriKind.Absolute, out var portal))
+        {
+            checks.Add(new Check("Portal DNS", false, $"Invalid portal_url '{config.PortalUrl}'",
+                "Fix [auth] portal_url."));
+            return checks;
+        }
+
+        using var cts = new CancellationTokenSource(TimeSpan.FromSeconds(config.Timeout));
+
+        try
+        {
+            var addresses = await Dns.GetHostAddressesAsync(portal.Host, cts.Token);
+            checks.Add(new Check("Portal DNS", true, $"{portal.Host} -> {string.Join(", ", addresses.Select(a => a.ToString()))}"));
+        }
+        catch (Exception e)
+        {
+            checks.Add(new Check("Portal DNS", false, $"{portal.Host}: {e.Message}",
+                "Make sure you are on the KMITL network (Wi-Fi or LAN) and DNS is provided by DHCP."));
+            return checks;
+        }
+
+        if (portal.Scheme != Uri.UriSchemeHttps)
+            return checks;
+
+        try
+        {
+            using var tcp = new TcpClient();
+            await tcp.ConnectAsync(portal.Host, portal.Port, cts.Token);
+
+            var certErrors = SslPolicyErrors.None;
+            await using var ssl = new SslStream(tcp.GetStream());
+            await ssl.AuthenticateAsClientAsync(new SslClientAuthenticationOptions
+            {
+                TargetHost = portal.Host,
+                EnabledSslProtocols = config.EnabledSslProtocols,
+                RemoteCertificateValidationCallback = (_, _, _, errors) =>
+                {
+                    certErrors = errors;
+                    return errors == SslPolicyErrors.None || config.AcceptInvalidCerts;
+                },
+            }, cts.Token);
+
+            var detail = certErrors == SslPolicyErrors.None
+                ? $"{ssl.SslProtocol}, certificate valid"
+                : $"{ssl.SslProtocol}, certificate errors ignored: {certErrors}";
+            checks.Add(new Check("Portal TLS", true, detail));
+        }
+        catch (Exception e)
+        {
+            checks.Add(new Check("Portal TLS", false, $"{portal.Host}:{portal.Port}: {e.Message}",
+                config.AcceptInvalidCerts
+                    ? "The portal host resolves but does not accept connections; check firewall or VPN."
+                    : "Set [network] accept_invalid_certs = true if the portal uses a self-signed certificate."));
+        }
+
+        return checks;
+    }
+}
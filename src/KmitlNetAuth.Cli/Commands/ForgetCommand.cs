@@ -0,0 +1,38 @@
+using KmitlNetAuth.Core;
+using KmitlNetAuth.Core.Exceptions;
+using Spectre.Console;
+
+namespace KmitlNetAuth.Cli.Commands;
+
+public static class ForgetCommand
+{
+    /// <summary>
+    /// Removes the saved password (credential store and <c>password_enc</c>) for the configured user.
+    /// </summary>
+    public static Task<int> ExecuteAsync(string? configPath)
+    {
+        var resolvedPath = ConfigPaths.Resolve(configPath);
+        var config = Config.Load(resolvedPath);
+        if (string.IsNullOrEmpty(config.Username))
+        {
+            AnsiConsole.MarkupLine("[yellow]No username configured, nothing to forget.[/]");
+            return Task.FromResult(0);
+        }
+
+        try
+        {
+            config.ForgetPassword(resolvedPath, CredentialStoreFactory.Create());
+        }
+        catch (Exception e) when (e is IOException or UnauthorizedAccessException or ConfigException)
+        {
+            AnsiConsole.MarkupLine($"[red]Could not update {Markup.Escape(resolvedPath)}:[/] {Markup.Escape(e.Message)}");
+            return Task.FromResult(1);
+        }
+
+        AnsiConsole.MarkupLine($"[green]Forgot the saved password for {Markup.Escape(config.Username)}.[/] " +
+            "Run 'kmitlnetauth setup' to enter new credentials.");
+        if (Environment.GetEnvironmentVariable("KMITL_PASSWORD") != null)
+            AnsiConsole.MarkupLine("[yellow]KMITL_PASSWORD is still set in this environment and will keep being used.[/]");
+        return Task.FromResult(0);
+    }
+}
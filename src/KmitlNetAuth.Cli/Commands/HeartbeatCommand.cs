@@ -0,0 +1,43 @@
+using KmitlNetAuth.Core;
+using KmitlNetAuth.Core.Platform;
+using Microsoft.Extensions.Logging.Abstractions;
+using Spectre.Console;
+
+namespace KmitlNetAuth.Cli.Commands;
+
+public static class HeartbeatCommand
+{
+    /// <summary>
+    /// Sends one heartbeat and prints the result with the raw portal response, for checking the
+    /// keepalive endpoint on its own. No login is attempted, so no password is needed.
+    /// </summary>
+    public static async Task<int> ExecuteAsync(string? configPath)
+    {
+        var config = Config.Load(ConfigPaths.Resolve(configPath));
+        if (string.IsNullOrEmpty(config.Username))
+        {
+            AnsiConsole.MarkupLine("[red]No username configured.[/] Run 'kmitlnetauth setup' first.");
+            return 1;
+        }
+
+        using var http = PortalHttpClient.Create(config);
+        var client = new AuthClient(http.Client, config, new NetworkInfo(), null,
+            new NullNotificationService(), NullLogger<AuthClient>.Instance);
+
+        AnsiConsole.MarkupLine($"Sending heartbeat for [bold]{Markup.Escape(config.Username)}[/] to {Markup.Escape(config.HeartbeatUrl)}");
+        var ok = await client.HeartbeatAsync();
+
+        AnsiConsole.MarkupLine(ok ? "[green]Heartbeat: OK[/]" : "[red]Heartbeat: FAILED[/]");
+        if (client.LastResponse is { } response)
+        {
+            Console.WriteLine();
+            Console.WriteLine(response);
+        }
+        else
+        {
+            AnsiConsole.MarkupLine("[yellow]No response received (connection error or timeout).[/]");
+        }
+
+        return ok ? 0 : 1;
+    }
+}
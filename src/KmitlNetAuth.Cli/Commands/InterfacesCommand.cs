@@ -0,0 +1,59 @@
+using KmitlNetAuth.Core;
+using KmitlNetAuth.Core.Platform;
+using Spectre.Console;
+
+namespace KmitlNetAuth.Cli.Commands;
+
+public static class InterfacesCommand
+{
+    /// <summary>
+    /// Lists the network adapters with their MAC addresses and marks the one whose MAC is sent to the
+    /// portal, to help pick <c>[network] interface</c> or <c>[auth] mac_address</c> when the guess is wrong.
+    /// </summary>
+    public static Task<int> ExecuteAsync(string? configPath)
+    {
+        var config = Config.Load(ConfigPaths.Resolve(configPath));
+        var interfaces = NetworkInfo.ListInterfaces(config.Interface);
+
+        if (interfaces.Count == 0)
+        {
+            AnsiConsole.MarkupLine("[red]No network interfaces with a MAC address found.[/]");
+            return Task.FromResult(1);
+        }
+
+        var table = new Table()
+            .Border(TableBorder.Rounded)
+            .Title("[bold]Network Interfaces[/]");
+
+        table.AddColumn("");
+        table.AddColumn("Name");
+        table.AddColumn("MAC");
+        table.AddColumn("IPv4");
+        table.AddColumn("Status");
+
+        foreach (var nic in interfaces)
+        {
+            var status = !nic.IsUp ? "[grey]Down[/]" : nic.HasDefaultRoute ? "[green]Up, default route[/]" : "Up";
+            var mac = NetworkInfo.IsLocallyAdministered(nic.Mac) ? $"{nic.Mac} [yellow](randomized?)[/]" : nic.Mac;
+            table.AddRow(
+                nic.Selected ? "[green]*[/]" : "",
+                $"{Markup.Escape(nic.Name)}\n[grey]{Markup.Escape(nic.Description)}[/]",
+                mac,
+                nic.Ipv4Addresses.Count > 0 ? string.Join("\n", nic.Ipv4Addresses) : "[grey]-[/]",
+                status);
+        }
+
+        AnsiConsole.Write(table);
+
+        if (NetworkInfo.NormalizeMacAddress(config.MacAddress) is { } overridden)
+            AnsiConsole.MarkupLine($"Sending [bold]{overridden}[/] from [[auth]] mac_address; the interfaces above are not used.");
+        else if (interfaces.FirstOrDefault(n => n.Selected) is { } selected)
+            AnsiConsole.MarkupLine($"Sending [bold]{selected.Mac}[/] from [bold]{Markup.Escape(selected.Name)}[/] (marked *).");
+        else if (config.Interface != null)
+            AnsiConsole.MarkupLine($"[red][[network]] interface '{Markup.Escape(config.Interface)}' not found;[/] sending {NetworkInfo.FallbackMacAddress}.");
+        else
+            AnsiConsole.MarkupLine($"[red]No interface is up;[/] sending {NetworkInfo.FallbackMacAddress}.");
+
+        return Task.FromResult(0);
+    }
+}
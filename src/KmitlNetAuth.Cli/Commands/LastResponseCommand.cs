@@ -0,0 +1,27 @@
+using KmitlNetAuth.Core;
+using Spectre.Console;
+
+namespace KmitlNetAuth.Cli.Commands;
+
+public static class LastResponseCommand
+{
+    /// <summary>
+    /// Prints the last login or heartbeat response the service saved. It is only saved while running
+    /// at Verbose/Trace log level, since it's a debugging aid.
+    /// </summary>
+    public static Task<int> ExecuteAsync(string? configPath)
+    {
+        var config = Config.Load(ConfigPaths.Resolve(configPath));
+        var path = Path.Combine(config.GetLogDirectory(), AuthClient.LastResponseFileName);
+
+        if (!File.Exists(path))
+        {
+            AnsiConsole.MarkupLine($"[yellow]No saved portal response at {Markup.Escape(path)}.[/]");
+            AnsiConsole.MarkupLine("Run the service with [bold]--trace[/] (or [[logging]] level = \"verbose\") and try again.");
+            return Task.FromResult(1);
+        }
+
+        Console.WriteLine(File.ReadAllText(path));
+        return Task.FromResult(0);
+    }
+}
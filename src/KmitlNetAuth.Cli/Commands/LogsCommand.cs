@@ -0,0 +1,115 @@
+using System.Text;
+using KmitlNetAuth.Core;
+using Spectre.Console;
+
+namespace KmitlNetAuth.Cli.Commands;
+
+public static class LogsCommand
+{
+    private static readonly TimeSpan FollowPollInterval = TimeSpan.FromMilliseconds(500);
+
+    /// <summary>
+    /// Prints the end of the newest daily log file the service wrote to the default log directory, and with
+    /// <paramref name="follow"/> keeps printing new lines, moving on to the next file when the log rolls over
+    /// at midnight. A log written with an explicit <c>--log-file</c> isn't searched for.
+    /// </summary>
+    public static async Task<int> ExecuteAsync(int lines, bool follow, bool errors, CancellationToken ct)
+    {
+        var directory = ConfigPaths.GetLogDirectory();
+        var prefix = errors ? RunCommand.ErrorLogFilePrefix : RunCommand.LogFilePrefix;
+        var path = FindNewest(directory, prefix);
+        if (path == null)
+        {
+            AnsiConsole.MarkupLine($"[yellow]No {(errors ? "error " : "")}log files in {Markup.Escape(directory)}.[/]");
+            if (errors)
+                AnsiConsole.MarkupLine("The error log is only written with [[logging]] error_file_level set.");
+            return 1;
+        }
+
+        if (!follow)
+            AnsiConsole.MarkupLine($"[grey]{Markup.Escape(path)}[/]");
+        var position = PrintTail(path, lines);
+        if (!follow)
+            return 0;
+
+        try
+        {
+            while (true)
+            {
+                await Task.Delay(FollowPollInterval, ct);
+
+                var newest = FindNewest(directory, prefix);
+                if (newest != null && newest != path)
+                {
+                    // Rolled over: finish the old file, then read the new one from the start
+                    position = PrintFrom(path, position);
+                    path = newest;
+                    position = 0;
+                }
+
+                position = PrintFrom(path, position);
+            }
+        }
+        catch (OperationCanceledException)
+        {
+            return 0;
+        }
+    }
+
+    // The daily files sort by date in their names; the errors file shares the prefix, so it's excluded by name
+    private static string? FindNewest(string directory, string prefix)
+    {
+        if (!Directory.Exists(directory))
+            return null;
+
+        return Directory.EnumerateFiles(directory, prefix + "*.log")
+            .Where(file => prefix == RunCommand.ErrorLogFilePrefix
+                || !Path.GetFileName(file).StartsWith(RunCommand.ErrorLogFilePrefix, StringComparison.Ordinal))
+            .OrderByDescending(file => Path.GetFileName(file), StringComparer.Ordinal)
+            .FirstOrDefault();
+    }
+
+    // Returns the length read, where following picks up
+    private static long PrintTail(string path, int lines)
+    {
+        using var stream = OpenShared(path);
+        using var reader = new StreamReader(stream, Encoding.UTF8);
+        var tail = new Queue<string>();
+        while (reader.ReadLine() is { } line)
+        {
+            tail.Enqueue(line);
+            if (tail.Count > lines)
+                tail.Dequeue();
+        }
+
+        foreach (var line in tail)
+            Console.WriteLine(line);
+        return stream.Length;
+    }
+
+    private static long PrintFrom(string path, long position)
+    {
+        try
+        {
+            using var stream = OpenShared(path);
+            // Truncated or replaced: start over
+            if (stream.Length < position)
+                position = 0;
+            if (stream.Length == position)
+                return position;
+
+            stream.Seek(position, SeekOrigin.Begin);
+            using var reader = new StreamReader(stream, Encoding.UTF8);
+            Console.Write(reader.ReadToEnd());
+            return stream.Length;
+        }
+        catch (FileNotFoundException)
+        {
+            return position;
+        }
+    }
+
+    // The running service keeps the file open for writing
+    private static FileStream OpenShared(string path) =>
+        new(path, FileMode.Open, FileAccess.Read, FileShare.ReadWrite | FileShare.Delete);
+}
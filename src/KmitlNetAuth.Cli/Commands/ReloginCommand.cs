@@ -0,0 +1,46 @@
+using KmitlNetAuth.Core;
+using KmitlNetAuth.Core.Platform;
+using KmitlNetAuth.Core.Services;
+using Microsoft.Extensions.Logging.Abstractions;
+using Spectre.Console;
+
+namespace KmitlNetAuth.Cli.Commands;
+
+public static class ReloginCommand
+{
+    /// <summary>
+    /// Logs out of the portal, waits a moment and logs back in, reporting each step. Clears a session
+    /// the portal still holds but no longer honours, which a plain login is refused for as a duplicate.
+    /// </summary>
+    public static async Task<int> ExecuteAsync(string? configPath)
+    {
+        var config = Config.Load(ConfigPaths.Resolve(configPath));
+        var store = config.UseCredentialStore ? CredentialStoreFactory.Create() : null;
+        if (string.IsNullOrEmpty(config.Username) || config.GetPasswordSource(store) == CredentialSource.None)
+        {
+            AnsiConsole.MarkupLine("[red]No credentials configured.[/] Run 'kmitlnetauth setup' first.");
+            return 1;
+        }
+
+        using var http = PortalHttpClient.Create(config);
+        var client = new AuthClient(http.Client, config, new NetworkInfo(), store,
+            new NullNotificationService(), NullLogger<AuthClient>.Instance);
+        // Through the service so the login is confirmed by a connectivity check, like the run loop's
+        var service = new AuthService(client, config, new NullNotificationService(), NullLogger<AuthService>.Instance);
+
+        AnsiConsole.MarkupLine($"Re-logging in [bold]{Markup.Escape(config.Username)}[/]...");
+        var result = await service.ReloginAsync();
+
+        AnsiConsole.MarkupLine(result.LoggedOut switch
+        {
+            true => "  Logout: [green]OK[/]",
+            false => "  Logout: [yellow]FAILED[/] (logging in anyway)",
+            null => "  Logout: [grey]skipped[/] (no logout_url configured)",
+        });
+        AnsiConsole.MarkupLine(result.Login.Success
+            ? "  Login:  [green]OK[/]"
+            : $"  Login:  [red]FAILED[/] ({result.Login.Failure}: {Markup.Escape(result.Login.Message ?? "no details")})");
+
+        return result.Login.Success ? 0 : 1;
+    }
+}
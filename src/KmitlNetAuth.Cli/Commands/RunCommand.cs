@@ -1,35 +1,76 @@
 using KmitlNetAuth.Core;
 using KmitlNetAuth.Core.DependencyInjection;
 using KmitlNetAuth.Core.Platform;
+using KmitlNetAuth.Core.Services;
 using Microsoft.Extensions.DependencyInjection;
 using Microsoft.Extensions.Hosting;
 using Serilog;
+using Serilog.Core;
+using Serilog.Formatting.Json;
+using Serilog.Sinks.SystemConsole.Themes;
 using Spectre.Console;
 
 namespace KmitlNetAuth.Cli.Commands;
 
 public static class RunCommand
 {
-    public static async Task ExecuteAsync(string? configPath, bool daemon)
+    // Serilog's default templates plus "#<cycle> " from the auth service's log scope. A custom numeric
+    // format keeps the prefix off lines logged outside a cycle, where the property is missing.
+    private const string ConsoleTemplate = "[{Timestamp:HH:mm:ss} {Level:u3}] {Cycle:'#'0' '}{Message:lj}{NewLine}{Exception}";
+    private const string FileTemplate =
+        "{Timestamp:yyyy-MM-dd HH:mm:ss.fff zzz} [{Level:u3}] {Cycle:'#'0' '}{Message:lj}{NewLine}{Exception}";
+
+    // Serilog appends the date to these; the logs command finds the files by them
+    internal const string LogFilePrefix = "kmitlnetauth-";
+    internal const string ErrorLogFilePrefix = "kmitlnetauth-errors-";
+
+    /// <param name="logFile">Write the log to this file instead of the rolling files in the log directory.</param>
+    /// <param name="noLogFile">Log to the console only, for supervisors and containers that capture stdout.</param>
+    /// <param name="loginOnStart">Log in once immediately, then run the normal loop (overrides <c>login_on_start</c>).</param>
+    /// <param name="logLevel">Log level from -v/-vv/-q; wins over the config file and KMITL_LOG_LEVEL without being saved.</param>
+    public static async Task ExecuteAsync(string? configPath, bool daemon, bool watch = false, bool noKeyring = false,
+        string? logFile = null, bool noLogFile = false, bool loginOnStart = false, string? logLevel = null)
     {
         var resolvedPath = ConfigPaths.Resolve(configPath);
         var config = Config.Load(resolvedPath);
-
-        if (string.IsNullOrEmpty(config.Username))
+        if (watch)
+            config.MonitorOnly = true;
+        if (noKeyring)
+            config.UseCredentialStore = false;
+        if (loginOnStart)
+            config.LoginOnStart = true;
+
+        if (string.IsNullOrEmpty(config.Username) && !config.MonitorOnly)
         {
             if (daemon || !Environment.UserInteractive)
             {
-                Console.Error.WriteLine("Error: Username not set in config. Run 'kmitlnetauth setup' first.");
+                Console.Error.WriteLine("Error: Username not set in config. Run 'kmitlnetauth setup' first, " +
+                    "or set KMITL_USERNAME and KMITL_PASSWORD to run without a config file.");
                 Environment.Exit(1);
             }
 
             // Interactive - run setup wizard
-            var tempStore = CreateCredentialStore();
+            var tempStore = CredentialStoreFactory.Create();
             config = SetupWizard.Run(resolvedPath, tempStore);
         }
 
+        // No file at all: everything comes from KMITL_* variables, so nothing is read from or written to
+        // the keyring (unless KMITL_USE_CREDENTIAL_STORE asks for it) and nothing offers to save a file
+        var environmentOnly = ConfigPaths.IsEnvironmentOnly(resolvedPath);
+        if (environmentOnly && Environment.GetEnvironmentVariable("KMITL_USE_CREDENTIAL_STORE") == null)
+            config.UseCredentialStore = false;
+
+        // Password is passphrase-encrypted in config and nothing else can unlock it: ask for the passphrase
+        if (!string.IsNullOrEmpty(config.EncryptedPassword) && !daemon && Environment.UserInteractive
+            && string.IsNullOrEmpty(config.GetPassword(CredentialStoreFactory.Create())))
+        {
+            config.Passphrase = AnsiConsole.Prompt(
+                new TextPrompt<string>("Passphrase for encrypted password:")
+                    .Secret());
+        }
+
         // Warn if using DHCP and no static IP is configured (interactive mode only)
-        if (string.IsNullOrEmpty(config.IpAddress) && !daemon && Environment.UserInteractive)
+        if (string.IsNullOrEmpty(config.IpAddress) && !daemon && Environment.UserInteractive && !environmentOnly)
         {
             var (isDhcp, currentIp) = DhcpDetector.GetNetworkStatus();
             if (isDhcp && !string.IsNullOrEmpty(currentIp))
@@ -37,31 +78,42 @@ public static class RunCommand
                 AnsiConsole.MarkupLine($"[yellow]Warning: Network interface is using DHCP (current IP: {currentIp})[/]");
                 if (AnsiConsole.Confirm($"Save [bold]{currentIp}[/] as static IP?", false))
                 {
-                    var tempStore = CreateCredentialStore();
+                    var tempStore = CredentialStoreFactory.Create();
                     config.IpAddress = currentIp;
-                    config.Save(resolvedPath, tempStore);
+                    // The file's own settings only, not the merged system layer and environment values
+                    var file = Config.LoadFile(resolvedPath);
+                    file.IpAddress = currentIp;
+                    file.Save(resolvedPath, tempStore);
                 }
             }
         }
 
-        var logDir = ConfigPaths.GetLogDirectory();
-        Directory.CreateDirectory(logDir);
+        // A switch rather than a fixed level, so POST /log-level on the control API can change it live
+        var levelSwitch = new LoggingLevelSwitch(ParseLogLevel(logLevel ?? config.LogLevel));
+        var loggerConfig = new LoggerConfiguration().MinimumLevel.ControlledBy(levelSwitch);
 
-        var logLevel = ParseLogLevel(config.LogLevel);
+        // JSON lines for log aggregators (Loki, ELK); console and file always share one format
+        var formatter = config.LogFormat.Equals("json", StringComparison.OrdinalIgnoreCase)
+            ? new JsonFormatter(renderMessage: true)
+            : null;
 
-        Log.Logger = new LoggerConfiguration()
-            .MinimumLevel.Is(logLevel)
-            .WriteTo.Console()
-            .WriteTo.File(
-                Path.Combine(logDir, "kmitlnetauth-.log"),
-                rollingInterval: RollingInterval.Day,
-                retainedFileCountLimit: 30)
-            .CreateLogger();
+        if (formatter != null)
+            loggerConfig.WriteTo.Console(formatter);
+        else if (CreateConsoleTheme(config) is { } theme)
+            loggerConfig.WriteTo.Console(outputTemplate: ConsoleTemplate, theme: theme);
+        else
+            loggerConfig.WriteTo.Console(outputTemplate: ConsoleTemplate);
+
+        if (!noLogFile)
+            AddFileSink(loggerConfig, formatter, logFile, config.ErrorFileLevel);
+
+        Log.Logger = loggerConfig.CreateLogger();
 
         try
         {
             var builder = Host.CreateApplicationBuilder();
             builder.Services.AddSerilog();
+            builder.Services.AddSingleton<ILogLevelSwitch>(new SerilogLevelSwitch(levelSwitch));
             builder.Services.AddKmitlNetAuth(config);
             builder.Services.AddHostedService<AuthWorker>();
 
@@ -73,7 +125,10 @@ public static class RunCommand
             var host = builder.Build();
 
             Log.Information("Starting KMITL NetAuth Service ({Mode})", daemon ? "Daemon" : "Foreground");
-            Log.Information("Using config file: {ConfigPath}", resolvedPath);
+            if (environmentOnly)
+                Log.Information("No config file at {ConfigPath}; using KMITL_* environment variables and defaults", resolvedPath);
+            else
+                Log.Information("Using config file: {ConfigPath}", resolvedPath);
 
             await host.RunAsync();
         }
@@ -83,21 +138,64 @@ public static class RunCommand
         }
     }
 
-    private static KmitlNetAuth.Core.Platform.ICredentialStore CreateCredentialStore()
+    // An explicit --log-file is written as-is; the default location rolls daily and keeps 30 files.
+    // error_file_level adds a second file of that level and above: "<name>.errors.log" or "kmitlnetauth-errors-<date>.log".
+    private static void AddFileSink(LoggerConfiguration loggerConfig, JsonFormatter? formatter, string? logFile,
+        string? errorFileLevel)
     {
-        if (OperatingSystem.IsWindows())
-            return CreateWindowsStore();
+        string logPath, errorPath;
+        RollingInterval rollingInterval;
+        if (logFile != null)
+        {
+            logPath = Path.GetFullPath(logFile);
+            errorPath = Path.ChangeExtension(logPath, ".errors" + Path.GetExtension(logPath));
+            rollingInterval = RollingInterval.Infinite;
+        }
+        else
+        {
+            logPath = Path.Combine(ConfigPaths.GetLogDirectory(), LogFilePrefix + ".log");
+            errorPath = Path.Combine(ConfigPaths.GetLogDirectory(), ErrorLogFilePrefix + ".log");
+            rollingInterval = RollingInterval.Day;
+        }
+
+        if (Path.GetDirectoryName(logPath) is { Length: > 0 } dir)
+            Directory.CreateDirectory(dir);
 
-        return new KmitlNetAuth.Core.Platform.Linux.FileCredentialStore();
+        AddFile(loggerConfig, formatter, logPath, rollingInterval, Serilog.Events.LogEventLevel.Verbose);
+        if (errorFileLevel != null)
+            AddFile(loggerConfig, formatter, errorPath, rollingInterval, ParseLogLevel(errorFileLevel));
+    }
+
+    private static void AddFile(LoggerConfiguration loggerConfig, JsonFormatter? formatter, string path,
+        RollingInterval rollingInterval, Serilog.Events.LogEventLevel minimumLevel)
+    {
+        if (formatter != null)
+            loggerConfig.WriteTo.File(formatter, path, restrictedToMinimumLevel: minimumLevel,
+                rollingInterval: rollingInterval, retainedFileCountLimit: 30);
+        else
+            loggerConfig.WriteTo.File(path, restrictedToMinimumLevel: minimumLevel, outputTemplate: FileTemplate,
+                rollingInterval: rollingInterval, retainedFileCountLimit: 30);
     }
 
-    [System.Runtime.Versioning.SupportedOSPlatform("windows")]
-    private static KmitlNetAuth.Core.Platform.ICredentialStore CreateWindowsStore()
+    // Only built when [theme] sets a log colour; the theme then colours just the level token
+    private static AnsiConsoleTheme? CreateConsoleTheme(Config config)
     {
-        return new KmitlNetAuth.Core.Platform.Windows.DpapiCredentialStore();
+        if (config.ThemeLogWarning == null && config.ThemeLogError == null)
+            return null;
+
+        var styles = new Dictionary<ConsoleThemeStyle, string>();
+        if (ThemeColor.TryParse(config.ThemeLogWarning, out var warning))
+            styles[ConsoleThemeStyle.LevelWarning] = warning.ToAnsi();
+        if (ThemeColor.TryParse(config.ThemeLogError, out var error))
+        {
+            styles[ConsoleThemeStyle.LevelError] = error.ToAnsi();
+            styles[ConsoleThemeStyle.LevelFatal] = error.ToAnsi();
+        }
+
+        return new AnsiConsoleTheme(styles);
     }
 
-    private static Serilog.Events.LogEventLevel ParseLogLevel(string level) => level.ToLowerInvariant() switch
+    internal static Serilog.Events.LogEventLevel ParseLogLevel(string level) => level.ToLowerInvariant() switch
     {
         "verbose" or "trace" => Serilog.Events.LogEventLevel.Verbose,
         "debug" => Serilog.Events.LogEventLevel.Debug,
@@ -8,19 +8,7 @@ public static class SetupCommand
     {
         var resolvedPath = ConfigPaths.Resolve(configPath);
 
-        KmitlNetAuth.Core.Platform.ICredentialStore store;
-        if (OperatingSystem.IsWindows())
-            store = CreateWindowsStore();
-        else
-            store = new KmitlNetAuth.Core.Platform.Linux.FileCredentialStore();
-
-        SetupWizard.Run(resolvedPath, store);
+        SetupWizard.Run(resolvedPath, CredentialStoreFactory.Create());
         return Task.CompletedTask;
     }
-
-    [System.Runtime.Versioning.SupportedOSPlatform("windows")]
-    private static KmitlNetAuth.Core.Platform.ICredentialStore CreateWindowsStore()
-    {
-        return new KmitlNetAuth.Core.Platform.Windows.DpapiCredentialStore();
-    }
 }
@@ -1,15 +1,37 @@
+using System.Diagnostics;
+using System.Text.Json;
+using System.Text.Json.Nodes;
 using KmitlNetAuth.Core;
+using KmitlNetAuth.Core.Platform;
+using Microsoft.Extensions.Logging.Abstractions;
 using Spectre.Console;
 
 namespace KmitlNetAuth.Cli.Commands;
 
 public static class StatusCommand
 {
-    public static Task ExecuteAsync(string? configPath)
+    /// <summary><c>status --json</c> exit code: online and the portal accepts our heartbeat.</summary>
+    public const int ExitAuthenticated = 0;
+
+    /// <summary><c>status --json</c> exit code: online, but the portal doesn't accept our heartbeat.</summary>
+    public const int ExitNotAuthenticated = 2;
+
+    /// <summary><c>status --json</c> exit code: the internet check failed.</summary>
+    public const int ExitOffline = 3;
+
+    public static async Task<int> ExecuteAsync(string? configPath, bool json = false)
     {
         var resolvedPath = ConfigPaths.Resolve(configPath);
         var config = Config.Load(resolvedPath);
 
+        if (json)
+        {
+            using var http = PortalHttpClient.Create(config);
+            var (output, exitCode) = await ProbeAsync(config, http.Client);
+            Console.WriteLine(output);
+            return exitCode;
+        }
+
         var table = new Table()
             .Border(TableBorder.Rounded)
             .Title("[bold]KMITL NetAuth Status[/]");
@@ -19,14 +41,46 @@ public static class StatusCommand
 
         table.AddRow("Config Path", resolvedPath);
         table.AddRow("Username", string.IsNullOrEmpty(config.Username) ? "[red]Not set[/]" : config.Username);
+
+        var store = config.UseCredentialStore ? CredentialStoreFactory.Create() : null;
+        var source = config.GetPasswordSource(store);
+        table.AddRow("Credentials", source == CredentialSource.None ? "[red]None[/]" : Markup.Escape(source.Describe()));
         table.AddRow("IP Address", config.IpAddress ?? "[grey]Auto[/]");
-        table.AddRow("Interval", $"{config.Interval}s");
+        table.AddRow("Interval", config.IsIntervalUnusuallyLong
+            ? $"[yellow]{config.Interval}s (unusually long, over {config.IntervalWarningThreshold}s)[/]"
+            : $"{config.Interval}s");
         table.AddRow("Max Attempts", config.MaxAttempt.ToString());
         table.AddRow("Auto Login", config.AutoLogin ? "[green]Enabled[/]" : "[red]Disabled[/]");
         table.AddRow("Log Level", config.LogLevel);
 
         AnsiConsole.Write(table);
+        return 0;
+    }
+
+    /// <summary>
+    /// Checks connectivity once for scripts: <c>online</c> is the internet check,
+    /// <c>authenticated</c> whether the portal accepts our heartbeat and <c>latency_ms</c> how long
+    /// both took together. Returns the JSON and one of the <c>Exit*</c> codes.
+    /// </summary>
+    public static async Task<(string Json, int ExitCode)> ProbeAsync(Config config, HttpClient httpClient,
+        CancellationToken ct = default)
+    {
+        var client = new AuthClient(httpClient, config, new NetworkInfo(), null,
+            new NullNotificationService(), NullLogger<AuthClient>.Instance);
+
+        var started = Stopwatch.GetTimestamp();
+        var online = await client.CheckInternetAsync(ct);
+        var authenticated = await client.HeartbeatAsync(ct);
+        var latency = (long)Stopwatch.GetElapsedTime(started).TotalMilliseconds;
 
-        return Task.CompletedTask;
+        var status = new JsonObject
+        {
+            ["online"] = online,
+            ["authenticated"] = authenticated,
+            ["username"] = config.Username,
+            ["latency_ms"] = latency,
+        };
+        var exitCode = !online ? ExitOffline : authenticated ? ExitAuthenticated : ExitNotAuthenticated;
+        return (status.ToJsonString(new JsonSerializerOptions { WriteIndented = true }), exitCode);
     }
 }
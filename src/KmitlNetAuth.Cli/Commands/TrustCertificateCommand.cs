@@ -0,0 +1,39 @@
+using KmitlNetAuth.Core;
+using KmitlNetAuth.Core.Platform;
+using Microsoft.Extensions.Logging.Abstractions;
+using Spectre.Console;
+
+namespace KmitlNetAuth.Cli.Commands;
+
+public static class TrustCertificateCommand
+{
+    /// <summary>
+    /// Shows each portal certificate that changed since it was first seen and, once confirmed, pins the new
+    /// fingerprint so the service stops warning about it. See <see cref="PortalCertificateWatcher"/>.
+    /// </summary>
+    public static Task<int> ExecuteAsync(string? configPath)
+    {
+        var config = Config.Load(ConfigPaths.Resolve(configPath));
+        var watcher = new PortalCertificateWatcher(config, new NullNotificationService(), NullLogger<PortalCertificateWatcher>.Instance);
+
+        var pending = watcher.GetPending();
+        if (pending.Count == 0)
+        {
+            AnsiConsole.MarkupLine("No changed portal certificates are waiting to be trusted.");
+            return Task.FromResult(0);
+        }
+
+        foreach (var (host, fingerprint) in pending)
+        {
+            AnsiConsole.MarkupLine($"[yellow]{Markup.Escape(host)}[/] now presents a certificate with SHA-256 fingerprint");
+            AnsiConsole.MarkupLine($"  [bold]{Markup.Escape(fingerprint)}[/]");
+            if (!AnsiConsole.Confirm("Trust it? Only do so if the portal renewed its certificate.", false))
+                continue;
+
+            watcher.AcceptPending(host);
+            AnsiConsole.MarkupLine($"[green]Trusted the new certificate of {Markup.Escape(host)}.[/]");
+        }
+
+        return Task.FromResult(0);
+    }
+}
@@ -0,0 +1,26 @@
+using System.Runtime.Versioning;
+using KmitlNetAuth.Core.Platform;
+using KmitlNetAuth.Core.Platform.Linux;
+
+namespace KmitlNetAuth.Cli;
+
+/// <summary>
+/// Creates the platform credential store for commands that run outside the DI host.
+/// </summary>
+public static class CredentialStoreFactory
+{
+    public static ICredentialStore Create()
+    {
+        if (OperatingSystem.IsWindows())
+            return CreateWindowsStore();
+
+        return new FileCredentialStore();
+    }
+
+    // Separate method to avoid loading Windows-specific types on Linux
+    [SupportedOSPlatform("windows")]
+    private static ICredentialStore CreateWindowsStore()
+    {
+        return new KmitlNetAuth.Core.Platform.Windows.DpapiCredentialStore();
+    }
+}
@@ -0,0 +1,42 @@
+using KmitlNetAuth.Core;
+using KmitlNetAuth.Core.DependencyInjection;
+using KmitlNetAuth.Core.Platform;
+using Microsoft.Extensions.DependencyInjection;
+
+namespace KmitlNetAuth.Cli;
+
+/// <summary>
+/// The portal <see cref="HttpClient"/> for commands that run outside the DI host, built from the same
+/// named client <see cref="CoreServiceCollectionExtensions.AddKmitlNetAuth"/> registers for the service,
+/// so one-shot commands get the same TLS settings, cookies, DNS cache and certificate pinning.
+/// </summary>
+public sealed class PortalHttpClient : IDisposable
+{
+    private readonly ServiceProvider _provider;
+
+    public HttpClient Client { get; }
+
+    private PortalHttpClient(ServiceProvider provider)
+    {
+        _provider = provider;
+        Client = provider.GetRequiredService<IHttpClientFactory>()
+            .CreateClient(CoreServiceCollectionExtensions.HttpClientName);
+    }
+
+    public static PortalHttpClient Create(Config config)
+    {
+        var services = new ServiceCollection();
+        services.AddLogging();
+        // A one-shot command shouldn't pop desktop notifications (e.g. for a changed certificate)
+        services.AddKeyedSingleton<INotificationService, NullNotificationService>(
+            CoreServiceCollectionExtensions.DesktopNotificationKey);
+        services.AddKmitlNetAuth(config);
+        return new PortalHttpClient(services.BuildServiceProvider());
+    }
+
+    public void Dispose()
+    {
+        Client.Dispose();
+        _provider.Dispose();
+    }
+}
@@ -1,5 +1,6 @@
 using System.CommandLine;
 using KmitlNetAuth.Cli.Commands;
+using KmitlNetAuth.Core.Exceptions;
 
 var configOption = new Option<string?>("--config", "-c")
 {
@@ -11,15 +12,97 @@ var daemonOption = new Option<bool>("--daemon", "-d")
     Description = "Run as daemon (background mode)",
 };
 
+var watchOption = new Option<bool>("--watch", "-w")
+{
+    Description = "Monitor connectivity only, never log in",
+};
+
+var noKeyringOption = new Option<bool>("--no-keyring")
+{
+    Description = "Never use the OS credential store (password from KMITL_PASSWORD or password_enc only)",
+};
+
+var logFileOption = new Option<string?>("--log-file")
+{
+    Description = "Write the log to this file instead of the default log directory",
+};
+
+var noLogFileOption = new Option<bool>("--no-log-file")
+{
+    Description = "Log to stdout only (for systemd, Docker and other supervisors that capture output)",
+};
+
+var onceThenWatchOption = new Option<bool>("--once-then-watch")
+{
+    Description = "Log in immediately on start, then check and heartbeat as usual",
+};
+
+var verboseOption = new Option<bool>("--verbose", "-v")
+{
+    Description = "Log at Debug level, overriding the config",
+};
+
+var traceOption = new Option<bool>("--trace", "-vv", "-vvv")
+{
+    Description = "Log at Verbose level (every request and response), overriding the config",
+};
+
+var quietOption = new Option<bool>("--quiet", "-q")
+{
+    Description = "Log warnings and errors only, overriding the config",
+};
+
+var printConfigOption = new Option<bool>("--print-config")
+{
+    Description = "Print the effective config (password redacted) with the source of each value, then exit",
+};
+
+var configCheckOption = new Option<bool>("--config-check")
+{
+    Description = "Validate the config without running, reading credentials or touching the network; exit 1 if invalid",
+};
+
 var rootCommand = new RootCommand("KMITL NetAuth - Auto authentication service for KMITL network");
 rootCommand.Options.Add(configOption);
 rootCommand.Options.Add(daemonOption);
+rootCommand.Options.Add(watchOption);
+rootCommand.Options.Add(noKeyringOption);
+rootCommand.Options.Add(logFileOption);
+rootCommand.Options.Add(noLogFileOption);
+rootCommand.Options.Add(onceThenWatchOption);
+rootCommand.Options.Add(verboseOption);
+rootCommand.Options.Add(traceOption);
+rootCommand.Options.Add(quietOption);
+rootCommand.Options.Add(printConfigOption);
+rootCommand.Options.Add(configCheckOption);
+rootCommand.Validators.Add(result =>
+{
+    if (result.GetValue(logFileOption) != null && result.GetValue(noLogFileOption))
+        result.AddError("--log-file and --no-log-file cannot be used together");
+    if (result.GetValue(quietOption) && (result.GetValue(verboseOption) || result.GetValue(traceOption)))
+        result.AddError("--quiet cannot be combined with --verbose or --trace");
+});
 
 rootCommand.SetAction(async (parseResult, ct) =>
 {
     var configPath = parseResult.GetValue(configOption);
     var daemon = parseResult.GetValue(daemonOption);
-    await RunCommand.ExecuteAsync(configPath, daemon);
+    var watch = parseResult.GetValue(watchOption);
+    var noKeyring = parseResult.GetValue(noKeyringOption);
+    if (parseResult.GetValue(printConfigOption))
+        return await ConfigCommand.PrintEffectiveAsync(configPath);
+    if (parseResult.GetValue(configCheckOption))
+        return await ConfigCommand.CheckAsync(configPath);
+
+    var logFile = parseResult.GetValue(logFileOption);
+    var noLogFile = parseResult.GetValue(noLogFileOption);
+    var onceThenWatch = parseResult.GetValue(onceThenWatchOption);
+    var logLevel = parseResult.GetValue(traceOption) ? "Verbose"
+        : parseResult.GetValue(verboseOption) ? "Debug"
+        : parseResult.GetValue(quietOption) ? "Warning"
+        : null;
+    await RunCommand.ExecuteAsync(configPath, daemon, watch, noKeyring, logFile, noLogFile, onceThenWatch, logLevel);
+    return 0;
 });
 
 var setupCommand = new Command("setup") { Description = "Interactive setup wizard" };
@@ -30,12 +113,141 @@ setupCommand.SetAction(async (parseResult, _) =>
     await SetupCommand.ExecuteAsync(configPath);
 });
 
+var jsonOption = new Option<bool>("--json")
+{
+    Description = "Check connectivity and print a JSON object (online, authenticated, username, latency_ms); exits 0 when authenticated, 2 when online but not authenticated, 3 when offline",
+};
+
 var statusCommand = new Command("status") { Description = "Show current configuration and status" };
 statusCommand.Options.Add(configOption);
+statusCommand.Options.Add(jsonOption);
 statusCommand.SetAction(async (parseResult, _) =>
 {
     var configPath = parseResult.GetValue(configOption);
-    await StatusCommand.ExecuteAsync(configPath);
+    var json = parseResult.GetValue(jsonOption);
+    return await StatusCommand.ExecuteAsync(configPath, json);
+});
+
+var doctorCommand = new Command("doctor") { Description = "Check config, credentials, network and portal reachability" };
+doctorCommand.Options.Add(configOption);
+doctorCommand.SetAction(async (parseResult, _) =>
+{
+    var configPath = parseResult.GetValue(configOption);
+    return await DoctorCommand.ExecuteAsync(configPath);
+});
+
+var forgetCommand = new Command("forget") { Description = "Delete the saved password for the configured user" };
+forgetCommand.Options.Add(configOption);
+forgetCommand.SetAction(async (parseResult, _) =>
+{
+    var configPath = parseResult.GetValue(configOption);
+    return await ForgetCommand.ExecuteAsync(configPath);
+});
+
+var trustCertificateCommand = new Command("trust-certificate")
+{
+    Description = "Accept a portal certificate that changed, after checking its fingerprint",
+};
+trustCertificateCommand.Options.Add(configOption);
+trustCertificateCommand.SetAction(async (parseResult, _) =>
+{
+    var configPath = parseResult.GetValue(configOption);
+    return await TrustCertificateCommand.ExecuteAsync(configPath);
+});
+
+var interfacesCommand = new Command("interfaces") { Description = "List network interfaces and the MAC address sent to the portal" };
+interfacesCommand.Options.Add(configOption);
+interfacesCommand.SetAction(async (parseResult, _) =>
+{
+    var configPath = parseResult.GetValue(configOption);
+    return await InterfacesCommand.ExecuteAsync(configPath);
+});
+
+var heartbeatCommand = new Command("heartbeat")
+{
+    Description = "Send one heartbeat and print the result with the raw portal response",
+};
+heartbeatCommand.Options.Add(configOption);
+heartbeatCommand.SetAction(async (parseResult, _) =>
+{
+    var configPath = parseResult.GetValue(configOption);
+    return await HeartbeatCommand.ExecuteAsync(configPath);
+});
+
+var reloginCommand = new Command("relogin")
+{
+    Description = "Log out of the portal and back in, to reset a stuck session",
+};
+reloginCommand.Options.Add(configOption);
+reloginCommand.SetAction(async (parseResult, _) =>
+{
+    var configPath = parseResult.GetValue(configOption);
+    return await ReloginCommand.ExecuteAsync(configPath);
+});
+
+var cyclesOption = new Option<int>("--cycles", "-n")
+{
+    Description = "Number of check/heartbeat/login cycles to time",
+    DefaultValueFactory = _ => 10,
+};
+
+var benchCommand = new Command("bench")
+{
+    Description = "Run a number of auth cycles and report min/avg/max/p95 time of each step",
+};
+benchCommand.Options.Add(configOption);
+benchCommand.Options.Add(cyclesOption);
+benchCommand.Validators.Add(result =>
+{
+    if (result.GetValue(cyclesOption) < 1)
+        result.AddError("--cycles must be at least 1");
+});
+benchCommand.SetAction(async (parseResult, ct) =>
+{
+    var configPath = parseResult.GetValue(configOption);
+    return await BenchCommand.ExecuteAsync(configPath, parseResult.GetValue(cyclesOption), ct);
+});
+
+var lastResponseCommand = new Command("last-response")
+{
+    Description = "Print the last raw portal response (saved while running with --trace)",
+};
+lastResponseCommand.Options.Add(configOption);
+lastResponseCommand.SetAction(async (parseResult, _) =>
+{
+    var configPath = parseResult.GetValue(configOption);
+    return await LastResponseCommand.ExecuteAsync(configPath);
+});
+
+var followOption = new Option<bool>("--follow", "-f")
+{
+    Description = "Keep printing new lines as they are logged, across the daily rollover",
+};
+
+var linesOption = new Option<int>("--lines", "-n")
+{
+    Description = "Number of lines to print from the end of the log",
+    DefaultValueFactory = _ => 100,
+};
+
+var errorsOption = new Option<bool>("--errors")
+{
+    Description = "Show the error log (written with [logging] error_file_level) instead of the full log",
+};
+
+var logsCommand = new Command("logs") { Description = "Print the end of the current log file, wherever this platform keeps it" };
+logsCommand.Options.Add(followOption);
+logsCommand.Options.Add(linesOption);
+logsCommand.Options.Add(errorsOption);
+logsCommand.Validators.Add(result =>
+{
+    if (result.GetValue(linesOption) < 0)
+        result.AddError("--lines must not be negative");
+});
+logsCommand.SetAction(async (parseResult, ct) =>
+{
+    return await LogsCommand.ExecuteAsync(parseResult.GetValue(linesOption), parseResult.GetValue(followOption),
+        parseResult.GetValue(errorsOption), ct);
 });
 
 var configCommand = new Command("config") { Description = "Show or open config file" };
@@ -46,9 +258,110 @@ configCommand.SetAction(async (parseResult, _) =>
     await ConfigCommand.ExecuteAsync(configPath);
 });
 
+var exportPathArgument = new Argument<string>("path")
+{
+    Description = "Destination file for the exported config",
+};
+
+var configExportCommand = new Command("export") { Description = "Export config to a file (password not included)" };
+configExportCommand.Options.Add(configOption);
+configExportCommand.Arguments.Add(exportPathArgument);
+configExportCommand.SetAction(async (parseResult, _) =>
+{
+    var configPath = parseResult.GetValue(configOption);
+    var exportPath = parseResult.GetValue(exportPathArgument)!;
+    return await ConfigCommand.ExportAsync(configPath, exportPath);
+});
+
+var importPathArgument = new Argument<string>("path")
+{
+    Description = "Config file to merge into the active config",
+};
+
+var configImportCommand = new Command("import") { Description = "Merge a config file into the active config" };
+configImportCommand.Options.Add(configOption);
+configImportCommand.Arguments.Add(importPathArgument);
+configImportCommand.SetAction(async (parseResult, _) =>
+{
+    var configPath = parseResult.GetValue(configOption);
+    var importPath = parseResult.GetValue(importPathArgument)!;
+    return await ConfigCommand.ImportAsync(configPath, importPath);
+});
+
+var keyArgument = new Argument<string>("key")
+{
+    Description = "Config key, such as service.interval (or just interval when unambiguous)",
+};
+
+var valueArgument = new Argument<string>("value")
+{
+    Description = "New value: true/false, a number or duration, or text",
+};
+
+var configGetCommand = new Command("get") { Description = "Print one config value" };
+configGetCommand.Options.Add(configOption);
+configGetCommand.Arguments.Add(keyArgument);
+configGetCommand.SetAction(async (parseResult, _) =>
+{
+    var configPath = parseResult.GetValue(configOption);
+    var key = parseResult.GetValue(keyArgument)!;
+    return await ConfigCommand.GetAsync(configPath, key);
+});
+
+var configSetCommand = new Command("set") { Description = "Change one config value and save it (validated first)" };
+configSetCommand.Options.Add(configOption);
+configSetCommand.Arguments.Add(keyArgument);
+configSetCommand.Arguments.Add(valueArgument);
+configSetCommand.SetAction(async (parseResult, _) =>
+{
+    var configPath = parseResult.GetValue(configOption);
+    var key = parseResult.GetValue(keyArgument)!;
+    var value = parseResult.GetValue(valueArgument)!;
+    return await ConfigCommand.SetAsync(configPath, key, value);
+});
+
+var configEnvCommand = new Command("env") { Description = "List the environment variables the config reads and their values" };
+configEnvCommand.SetAction(async (_, _) => await ConfigCommand.ListEnvironmentAsync());
+
+configCommand.Subcommands.Add(configExportCommand);
+configCommand.Subcommands.Add(configImportCommand);
+configCommand.Subcommands.Add(configGetCommand);
+configCommand.Subcommands.Add(configSetCommand);
+configCommand.Subcommands.Add(configEnvCommand);
+
 rootCommand.Subcommands.Add(setupCommand);
 rootCommand.Subcommands.Add(statusCommand);
 rootCommand.Subcommands.Add(configCommand);
+rootCommand.Subcommands.Add(doctorCommand);
+rootCommand.Subcommands.Add(forgetCommand);
+rootCommand.Subcommands.Add(trustCertificateCommand);
+rootCommand.Subcommands.Add(interfacesCommand);
+rootCommand.Subcommands.Add(heartbeatCommand);
+rootCommand.Subcommands.Add(reloginCommand);
+rootCommand.Subcommands.Add(benchCommand);
+rootCommand.Subcommands.Add(lastResponseCommand);
+rootCommand.Subcommands.Add(logsCommand);
 
 var result = rootCommand.Parse(args);
-return await result.InvokeAsync();
+
+try
+{
+    // Let config errors surface as a one-line message instead of a stack trace
+    return await result.InvokeAsync(new InvocationConfiguration { EnableDefaultExceptionHandler = false });
+}
+catch (ConfigException e)
+{
+    Console.Error.WriteLine($"Error: {e.Message}");
+    return 1;
+}
+catch (OperationCanceledException)
+{
+    // Ctrl+C during a command that doesn't handle it itself
+    return 130;
+}
+catch (Exception e)
+{
+    // The default handler is off, so anything else would otherwise end as an unhandled exception
+    Console.Error.WriteLine($"Error: {e.GetType().Name}: {e.Message}");
+    return 1;
+}
@@ -0,0 +1,20 @@
+using KmitlNetAuth.Cli.Commands;
+using KmitlNetAuth.Core.Services;
+using Serilog.Core;
+
+namespace KmitlNetAuth.Cli;
+
+/// <summary>Lets the control API change the level of the logger <see cref="RunCommand"/> built.</summary>
+public sealed class SerilogLevelSwitch : ILogLevelSwitch
+{
+    private readonly LoggingLevelSwitch _switch;
+
+    public SerilogLevelSwitch(LoggingLevelSwitch levelSwitch)
+    {
+        _switch = levelSwitch;
+    }
+
+    public string Level => _switch.MinimumLevel.ToString();
+
+    public void SetLevel(string level) => _switch.MinimumLevel = RunCommand.ParseLogLevel(level);
+}
@@ -1,3 +1,4 @@
+using System.Globalization;
 using KmitlNetAuth.Core;
 using KmitlNetAuth.Core.Platform;
 using Spectre.Console;
@@ -11,17 +12,18 @@ public static class SetupWizard
         AnsiConsole.Write(new Rule("[bold blue]KMITL NetAuth Setup[/]").RuleStyle("grey"));
         AnsiConsole.WriteLine();
 
-        var existingConfig = new Config();
+        // Edits the existing file in place, so settings the wizard doesn't ask about are kept
+        var config = new Config();
         if (File.Exists(configPath))
         {
-            existingConfig = Config.Load(configPath);
+            config = Config.LoadFile(configPath);
             AnsiConsole.MarkupLine("[yellow]Existing config found. Current values shown as defaults.[/]");
             AnsiConsole.WriteLine();
         }
 
         var username = AnsiConsole.Prompt(
             new TextPrompt<string>("Student ID:")
-                .DefaultValue(existingConfig.Username)
+                .DefaultValue(config.Username)
                 .AllowEmpty());
 
         var password = AnsiConsole.Prompt(
@@ -29,46 +31,58 @@ public static class SetupWizard
                 .Secret()
                 .AllowEmpty());
 
+        // On a shared machine the password can be used for this run only and never saved
+        var rememberPassword = string.IsNullOrEmpty(password) || AnsiConsole.Confirm("Remember password?", true);
+
         var ipAddress = AnsiConsole.Prompt(
             new TextPrompt<string>("IP Address [grey](optional, press Enter to skip)[/]:")
-                .DefaultValue(existingConfig.IpAddress ?? "")
+                .DefaultValue(config.IpAddress ?? "")
                 .AllowEmpty());
 
         var interval = AnsiConsole.Prompt(
             new TextPrompt<ulong>("Heartbeat Interval (seconds):")
-                .DefaultValue(existingConfig.Interval));
+                .DefaultValue(config.Interval));
 
-        var autoLogin = AnsiConsole.Confirm("Enable auto-login?", existingConfig.AutoLogin);
+        var autoLogin = AnsiConsole.Confirm("Enable auto-login?", config.AutoLogin);
 
-        var config = new Config
-        {
-            Username = username,
-            Password = string.IsNullOrEmpty(password) ? null : password,
-            IpAddress = string.IsNullOrEmpty(ipAddress) ? null : ipAddress,
-            Interval = interval,
-            MaxAttempt = existingConfig.MaxAttempt,
-            AutoLogin = autoLogin,
-            LogLevel = existingConfig.LogLevel,
-        };
+        config.SetValue("auth.username", username);
+        config.SetValue("auth.ip_address", ipAddress);
+        config.SetValue("service.interval", interval.ToString(CultureInfo.InvariantCulture));
+        config.SetValue("service.auto_login", autoLogin ? "true" : "false");
+        if (!string.IsNullOrEmpty(password))
+            config.Password = password;
 
-        config.Save(configPath, credentialStore);
+        // Save is the only place the password is written, to the credential store or password_enc
+        var savedTo = config.Save(configPath, credentialStore, rememberPassword: rememberPassword);
 
         AnsiConsole.WriteLine();
         AnsiConsole.MarkupLine($"[green]Configuration saved to {configPath}[/]");
 
-        if (!string.IsNullOrEmpty(password) && credentialStore != null)
+        if (!rememberPassword)
         {
-            try
-            {
-                credentialStore.SetPasswordAsync(username, password).GetAwaiter().GetResult();
-                AnsiConsole.MarkupLine("[green]Password stored securely.[/]");
-            }
-            catch
+            AnsiConsole.MarkupLine("[yellow]Password not saved; it is only used until this process exits.[/]");
+        }
+        else if (!string.IsNullOrEmpty(password) && savedTo == CredentialSource.None)
+        {
+            AnsiConsole.MarkupLine("[yellow]Credential store unavailable.[/]");
+
+            var passphrase = AnsiConsole.Prompt(
+                new TextPrompt<string>("Passphrase to encrypt the password in config [grey](press Enter to skip)[/]:")
+                    .Secret()
+                    .AllowEmpty());
+
+            if (!string.IsNullOrEmpty(passphrase))
             {
-                AnsiConsole.MarkupLine("[yellow]Password saved to config file (credential store unavailable).[/]");
+                config.Passphrase = passphrase;
+                savedTo = config.Save(configPath, credentialStore);
             }
         }
 
+        if (savedTo == CredentialSource.CredentialStore)
+            AnsiConsole.MarkupLine("[green]Password stored securely.[/]");
+        else if (savedTo == CredentialSource.EncryptedConfig)
+            AnsiConsole.MarkupLine("[green]Password encrypted in config file. Set KMITL_PASSPHRASE to unlock it in daemon mode.[/]");
+
         return config;
     }
 }
@@ -1,19 +1,71 @@
+using System.Collections.Concurrent;
+using System.Diagnostics;
+using System.Net;
+using System.Net.Http.Headers;
+using System.Net.NetworkInformation;
+using System.Net.Sockets;
 using KmitlNetAuth.Core.Exceptions;
 using KmitlNetAuth.Core.Platform;
 using Microsoft.Extensions.Logging;
 
 namespace KmitlNetAuth.Core;
 
+/// <summary>
+/// Talks to the KMITL portal. A single instance is registered as a singleton and shared by the
+/// background <see cref="Services.AuthService"/> loop and on-demand UI actions (Login Now, Debug).
+/// All methods are safe to call concurrently: the MAC is fixed at construction, the
+/// <see cref="HttpClient"/> and circuit breaker are thread-safe, and logins are serialized so a
+/// manual login never interleaves with one from the loop. Config is re-read on every call, so
+/// edits from the settings page apply to the next request.
+/// </summary>
 public sealed class AuthClient
 {
-    private const string Acip = "10.252.13.10";
+    /// <summary>File in the log directory holding the last portal response at Verbose/Trace log level.</summary>
+    public const string LastResponseFileName = "last-response.txt";
 
     private readonly HttpClient _httpClient;
     private readonly Config _config;
+    private readonly INetworkInfo _networkInfo;
     private readonly ICredentialStore? _credentialStore;
     private readonly INotificationService _notificationService;
     private readonly ILogger<AuthClient> _logger;
     private readonly string _macAddress;
+    private readonly CircuitBreaker _portalCircuit;
+    private readonly SemaphoreSlim _loginLock = new(1, 1);
+    private readonly PortalDiagnostics? _portalDiagnostics;
+    private string? _learnedAcip;
+    private bool _redirectTakesLogin;
+    private string? _sessionUserAgent;
+    private Task<string?>? _outageDiagnosis;
+    private readonly ConcurrentDictionary<string, DeviceLogin> _deviceLogins = new();
+
+    /// <summary>Login and heartbeat counters since this client was created.</summary>
+    public AuthStats Stats { get; } = new();
+
+    /// <summary>The MAC sent as <c>umac</c>: the config override, else the detected one.</summary>
+    public string MacAddress => _macAddress;
+
+    public CircuitState PortalCircuitState => _portalCircuit.State;
+
+    /// <summary>The access controller sent as <c>acip</c>: the one the portal redirect named, else the config value.</summary>
+    public string Acip => _config.LearnAcip && _learnedAcip != null ? _learnedAcip : _config.Acip;
+
+    /// <summary>
+    /// True if login fields go in a GET query string: login_method is <c>get_redirect</c>, or <c>auto</c>
+    /// and the captive portal redirect landed on the login URL.
+    /// </summary>
+    public bool UsesGetLogin => _config.LoginMethod.ToLowerInvariant() switch
+    {
+        "get_redirect" => true,
+        "auto" => _redirectTakesLogin,
+        _ => false,
+    };
+
+    /// <summary>The latest login result of each <see cref="Config.AdditionalMacs"/> device, by normalized MAC.</summary>
+    public IReadOnlyDictionary<string, DeviceLogin> DeviceLogins => _deviceLogins;
+
+    /// <summary>The most recent login or heartbeat response (redacted, truncated); null until one arrives.</summary>
+    public PortalResponse? LastResponse { get; private set; }
 
     public AuthClient(
         HttpClient httpClient,
@@ -21,71 +73,514 @@ public sealed class AuthClient
         INetworkInfo networkInfo,
         ICredentialStore? credentialStore,
         INotificationService notificationService,
-        ILogger<AuthClient> logger)
+        ILogger<AuthClient> logger,
+        PortalDiagnostics? portalDiagnostics = null)
     {
         _httpClient = httpClient;
         _config = config;
+        _networkInfo = networkInfo;
         _credentialStore = credentialStore;
         _notificationService = notificationService;
         _logger = logger;
-        _macAddress = networkInfo.GetMacAddress();
+        _portalDiagnostics = portalDiagnostics;
+        _portalCircuit = new CircuitBreaker(
+            config.CircuitBreakerThreshold, TimeSpan.FromSeconds(config.CircuitBreakerCooldown));
+        _macAddress = NetworkInfo.NormalizeMacAddress(config.MacAddress) ?? networkInfo.GetMacAddress(config.Interface);
+
+        if (_macAddress == NetworkInfo.FallbackMacAddress)
+        {
+            _logger.LogWarning(
+                "Could not read a MAC address{Interface}; sending {Fallback}, which the portal may reject. " +
+                "Set [auth] mac_address or [network] interface in the config.",
+                config.Interface != null ? $" from interface '{config.Interface}'" : "", NetworkInfo.FallbackMacAddress);
+        }
+        else if (config.MacAddress == null && NetworkInfo.IsLocallyAdministered(_macAddress))
+        {
+            _logger.LogWarning(
+                "MAC address {Mac} looks randomized (private per-network address). If logins are rejected, " +
+                "turn off MAC randomization for this network or pin [auth] mac_address to the MAC registered with the portal.",
+                _macAddress);
+        }
     }
 
-    public async Task<bool> LoginAsync(CancellationToken ct = default)
+    /// <summary>
+    /// Raised by the Retry action of a failed-login notification. <see cref="Services.AuthService"/> handles it
+    /// so the retry is confirmed like any other login; with no handler the client just logs in again.
+    /// </summary>
+    public event EventHandler? RetryRequested;
+
+    public async Task<bool> LoginAsync(CancellationToken ct = default) =>
+        (await AttemptLoginAsync(ct)).Success;
+
+    /// <summary>
+    /// Sends a login request and reports why it failed, so callers can tell a
+    /// transient error apart from one the user has to fix.
+    /// </summary>
+    public async Task<LoginResult> AttemptLoginAsync(CancellationToken ct = default)
+    {
+        await _loginLock.WaitAsync(ct);
+        try
+        {
+            using var step = BeginStep("login");
+            var started = Stopwatch.GetTimestamp();
+            var result = await AttemptLoginCoreAsync(ct);
+            _logger.LogDebug("Login finished in {ElapsedMs:F0} ms ({Outcome})",
+                Stopwatch.GetElapsedTime(started).TotalMilliseconds, result.Failure);
+            Stats.RecordLogin(result);
+            return result;
+        }
+        finally
+        {
+            _loginLock.Release();
+        }
+    }
+
+    private async Task<LoginResult> AttemptLoginCoreAsync(CancellationToken ct)
     {
         var username = _config.Username;
-        var password = _config.GetPassword(_credentialStore);
+        var password = _config.GetPassword(_credentialStore, _logger);
         var ipAddress = _config.IpAddress ?? "";
 
         if (string.IsNullOrEmpty(username) || string.IsNullOrEmpty(password))
         {
             _logger.LogWarning("Username or password empty. Skipping login.");
-            return false;
+            return LoginResult.Failed(LoginFailure.MissingCredentials, "Username or password is not set");
+        }
+
+        if (!_portalCircuit.TryAcquire())
+        {
+            _logger.LogDebug("Portal circuit open, skipping login for {Remaining:F0}s",
+                _portalCircuit.RemainingCooldown.TotalSeconds);
+            return LoginResult.Failed(LoginFailure.CircuitOpen, "Portal unreachable, waiting before retrying");
         }
 
         _logger.LogInformation("Logging in with username '{Username}'...", username);
 
-        var form = new FormUrlEncodedContent(new Dictionary<string, string>
+        var fields = BuildLoginFields(username, password, ipAddress, _macAddress);
+
+        try
+        {
+            var response = await SendLoginAsync(fields, ct);
+
+            // Any HTTP answer means the host is reachable, even if it rejects us
+            _portalCircuit.RecordSuccess();
+            _outageDiagnosis = null;
+
+            var text = await PortalResponseDecoder.ReadAsync(response.Content, ct);
+            _logger.LogDebug("Login response: {Response}", text);
+            RecordResponse("Login", response, text, password);
+
+            // Some deployments reject the blind POST until a token from the login page is echoed back
+            if (IsFormTokenRejection(response, text) && await FetchFormTokensAsync(fields, ct) is { Count: > 0 } tokens)
+            {
+                _logger.LogInformation("Portal requires a form token; retrying login with {Fields}", string.Join(", ", tokens.Keys));
+                foreach (var (name, value) in tokens)
+                    fields[name] = value;
+
+                response.Dispose();
+                response = await SendLoginAsync(fields, ct);
+                text = await PortalResponseDecoder.ReadAsync(response.Content, ct);
+                _logger.LogDebug("Login response: {Response}", text);
+                RecordResponse("Login", response, text, password);
+            }
+
+            if (response.IsSuccessStatusCode && MatchesSuccessPattern(text) is { } matched)
+            {
+                if (matched)
+                    return LoginSucceeded();
+
+                // A rejection the JSON spells out falls through, to keep its lockout and session-limit handling
+                if (!LoginResponseParser.TryGetRejection(text, out _))
+                {
+                    var summary = LoginResponseParser.Summarize(text);
+                    _logger.LogError("Login response did not match login_success_pattern: {Summary}", summary);
+                    _notificationService.Show(Message(MessageKey.LoginFailedTitle),
+                        Message(MessageKey.LoginFailedUnexpectedPage, summary), FailureNotification(retry: true));
+                    return LoginResult.Failed(LoginFailure.UnexpectedResponse, $"Response did not match login_success_pattern: {summary}");
+                }
+            }
+
+            if (response.IsSuccessStatusCode)
+            {
+                if (LoginResponseParser.TryGetRejection(text, out var reason))
+                {
+                    if (LoginResponseParser.IsLockout(text, reason, out var retryAfter))
+                        return AccountLocked(reason, retryAfter);
+                    if (LoginResponseParser.IsSessionLimit(reason))
+                        return SessionLimit(reason);
+
+                    _logger.LogError("Login rejected by portal: {Reason}", reason);
+                    _notificationService.Show(Message(MessageKey.LoginRejectedTitle), reason, FailureNotification(retry: false));
+                    return LoginResult.Failed(LoginFailure.Rejected, reason);
+                }
+
+                if (LoginResponseParser.IsHtml(text))
+                {
+                    // Full body is in the debug log above
+                    var summary = LoginResponseParser.Summarize(text);
+                    _logger.LogError("Portal returned an HTML page instead of a login result: {Summary}", summary);
+                    _notificationService.Show(Message(MessageKey.LoginFailedTitle),
+                        Message(MessageKey.LoginFailedUnexpectedPage, summary), FailureNotification(retry: true));
+                    return LoginResult.Failed(LoginFailure.UnexpectedResponse, $"Portal returned an HTML page: {summary}");
+                }
+
+                return LoginSucceeded();
+            }
+
+            if (response.StatusCode == HttpStatusCode.TooManyRequests)
+                return AccountLocked("Too many login attempts", response.Headers.RetryAfter?.Delta);
+
+            _logger.LogError("Login failed with status: {Status}", response.StatusCode);
+            _notificationService.Show(Message(MessageKey.LoginFailedTitle),
+                Message(MessageKey.LoginFailedStatus, response.StatusCode), FailureNotification(retry: true));
+
+            return response.StatusCode is HttpStatusCode.Unauthorized or HttpStatusCode.Forbidden
+                ? LoginResult.Failed(LoginFailure.Rejected, $"Portal refused credentials ({(int)response.StatusCode})")
+                : LoginResult.Failed(LoginFailure.HttpError, $"Status: {response.StatusCode}");
+        }
+        catch (Exception e) when (e is not OperationCanceledException || !ct.IsCancellationRequested)
+        {
+            // HttpClient reports its own timeout as a cancellation; only the caller's token means shutdown
+            var failure = ClassifyConnectionError(e);
+            if (failure == LoginFailure.Timeout)
+                _logger.LogWarning("Login timed out after {Timeout}s, will retry.", _config.Timeout);
+            else if (failure == LoginFailure.ConnectionRefused)
+                _logger.LogError("Portal {Url} refused the connection. Check the host and port in [auth] portal_url.",
+                    _config.PortalUrl);
+            else
+                _logger.LogError(e, "Login connection error");
+
+            if (_portalCircuit.RecordFailure())
+            {
+                _logger.LogWarning("Portal unreachable after {Count} connection errors; pausing logins for {Cooldown}s.",
+                    _config.CircuitBreakerThreshold, _config.CircuitBreakerCooldown);
+                _notificationService.Show(Message(MessageKey.PortalUnreachableTitle),
+                    Message(MessageKey.PortalUnreachableBody, _config.CircuitBreakerCooldown),
+                    FailureNotification(retry: false));
+            }
+
+            return failure == LoginFailure.Timeout
+                ? LoginResult.Failed(failure, $"No response from portal within {_config.Timeout}s")
+                : LoginResult.Failed(failure, e.Message);
+        }
+    }
+
+    // No notification here: AuthService shows one once a connectivity check confirms the login took
+    private LoginResult LoginSucceeded()
+    {
+        _logger.LogInformation("Login request sent successfully.");
+        // A new session gets a new heartbeat_user_agents pick
+        _sessionUserAgent = null;
+        return LoginResult.Succeeded();
+    }
+
+    // Null when no login_success_pattern is set and the built-in checks decide
+    private bool? MatchesSuccessPattern(string body) =>
+        _config.LoginSuccessPattern is { } pattern ? LoginResponseParser.MatchesSuccessPattern(body, pattern) : null;
+
+    private Dictionary<string, string> BuildLoginFields(string username, string password, string ipAddress, string macAddress)
+    {
+        var fields = new Dictionary<string, string>
         {
             ["userName"] = username,
             ["userPass"] = password,
             ["uaddress"] = ipAddress,
-            ["umac"] = _macAddress,
+            ["umac"] = macAddress,
             ["agreed"] = "1",
             ["acip"] = Acip,
-            ["authType"] = "1",
+            ["authType"] = _config.AuthType,
+        };
+        foreach (var (key, value) in _config.ExtraLoginParams)
+            fields[key] = value;
+        return fields;
+    }
+
+    /// <summary>
+    /// Logs in another device (<see cref="Config.AdditionalMacs"/>) under this account. Its IP isn't
+    /// known here, so <c>uaddress</c> is left empty for the portal to fill in. No notifications are shown;
+    /// the result is kept in <see cref="DeviceLogins"/>.
+    /// </summary>
+    public async Task<LoginResult> LoginDeviceAsync(string macAddress, CancellationToken ct = default)
+    {
+        var mac = NetworkInfo.NormalizeMacAddress(macAddress) ?? macAddress;
+        var result = await AttemptDeviceLoginAsync(mac, ct);
+        _deviceLogins[mac] = new DeviceLogin(mac, DateTimeOffset.Now, result);
+        if (result.Success)
+            _logger.LogDebug("Logged in device {Mac}", mac);
+        else
+            _logger.LogWarning("Login for device {Mac} failed: {Reason}", mac, result.Message ?? result.Failure.ToString());
+        return result;
+    }
+
+    private async Task<LoginResult> AttemptDeviceLoginAsync(string mac, CancellationToken ct)
+    {
+        var password = _config.GetPassword(_credentialStore, _logger);
+        if (string.IsNullOrEmpty(_config.Username) || string.IsNullOrEmpty(password))
+            return LoginResult.Failed(LoginFailure.MissingCredentials, "Username or password is not set");
+
+        await _loginLock.WaitAsync(ct);
+        try
+        {
+            using var step = BeginStep("login_device");
+            if (!_portalCircuit.TryAcquire())
+                return LoginResult.Failed(LoginFailure.CircuitOpen, "Portal unreachable, waiting before retrying");
+
+            using var response = await SendLoginAsync(BuildLoginFields(_config.Username, password, "", mac), ct);
+            _portalCircuit.RecordSuccess();
+            _outageDiagnosis = null;
+            var text = await PortalResponseDecoder.ReadAsync(response.Content, ct);
+            _logger.LogDebug("Device login response: {Response}", text);
+
+            if (!response.IsSuccessStatusCode)
+                return LoginResult.Failed(LoginFailure.HttpError, $"Status: {response.StatusCode}");
+            if (MatchesSuccessPattern(text) is { } matched)
+                return matched
+                    ? LoginResult.Succeeded()
+                    : LoginResult.Failed(LoginFailure.UnexpectedResponse, "Response did not match login_success_pattern");
+            if (LoginResponseParser.TryGetRejection(text, out var reason))
+                return LoginResult.Failed(LoginFailure.Rejected, reason);
+            return LoginResponseParser.IsHtml(text)
+                ? LoginResult.Failed(LoginFailure.UnexpectedResponse, "Portal returned an HTML page")
+                : LoginResult.Succeeded();
+        }
+        catch (Exception e) when (e is not OperationCanceledException || !ct.IsCancellationRequested)
+        {
+            _portalCircuit.RecordFailure();
+            return LoginResult.Failed(ClassifyConnectionError(e), e.Message);
+        }
+        finally
+        {
+            _loginLock.Release();
+        }
+    }
+
+    private async Task<HttpResponseMessage> SendLoginAsync(Dictionary<string, string> fields, CancellationToken ct)
+    {
+        if (!UsesGetLogin)
+            return await _httpClient.PostAsync(_config.PortalUrl, new FormUrlEncodedContent(fields), ct);
+
+        var query = await new FormUrlEncodedContent(fields).ReadAsStringAsync(ct);
+        var separator = _config.PortalUrl.Contains('?') ? '&' : '?';
+        return await _httpClient.GetAsync($"{_config.PortalUrl}{separator}{query}", ct);
+    }
+
+    private static LoginFailure ClassifyConnectionError(Exception e)
+    {
+        if (e is OperationCanceledException)
+            return LoginFailure.Timeout;
+
+        for (var inner = e; inner != null; inner = inner.InnerException)
+        {
+            if (inner is TimeoutException || inner is SocketException { SocketErrorCode: SocketError.TimedOut })
+                return LoginFailure.Timeout;
+            if (inner is SocketException { SocketErrorCode: SocketError.ConnectionRefused })
+                return LoginFailure.ConnectionRefused;
+        }
+
+        return LoginFailure.ConnectionError;
+    }
+
+    /// <summary>
+    /// For a connection error from <see cref="AttemptLoginAsync"/>, replaces the HTTP error with the failing
+    /// step (DNS, TCP or TLS) when <see cref="PortalDiagnostics"/> can tell. The portal is probed once per
+    /// outage and the answer reused until it responds again. Call this outside any login lock, as the probe
+    /// can take up to the timeout; a timeout itself isn't diagnosed, since the probe would just time out again.
+    /// </summary>
+    public async Task<LoginResult> DiagnoseFailureAsync(LoginResult result, CancellationToken ct = default)
+    {
+        if (result.Failure is not (LoginFailure.ConnectionError or LoginFailure.ConnectionRefused)
+            || _portalDiagnostics == null || !Uri.TryCreate(_config.PortalUrl, UriKind.Absolute, out var portal))
+            return result;
+
+        var diagnosis = _outageDiagnosis ??= DiagnosePortalAsync(portal, ct);
+        string? summary;
+        try
+        {
+            summary = await diagnosis;
+        }
+        catch (OperationCanceledException)
+        {
+            // Cancelled with the caller; the next failure probes again
+            Interlocked.CompareExchange(ref _outageDiagnosis, null, diagnosis);
+            throw;
+        }
+
+        if (summary == null)
+            return result;
+
+        Stats.RefineLastError(summary);
+        return LoginResult.Failed(result.Failure, summary, result.RetryAfter);
+    }
+
+    // Null when the portal looks reachable, so the login request's own error stands
+    private async Task<string?> DiagnosePortalAsync(Uri portal, CancellationToken ct)
+    {
+        var diagnosis = await _portalDiagnostics!.DiagnoseAsync(
+            portal, TimeSpan.FromSeconds(_config.Timeout), _config.AcceptInvalidCerts, ct);
+        if (diagnosis.Reachable)
+        {
+            _logger.LogWarning("Portal looks reachable ({Diagnosis}); the login request itself failed.", diagnosis.Summary);
+            return null;
+        }
+
+        _logger.LogWarning("Portal connection diagnosis: {Diagnosis}", diagnosis.Summary);
+        return diagnosis.Summary;
+    }
+
+    private string Message(MessageKey key, params object[] args) => Messages.Get(_config.Language, key, args);
+
+    // Retry is left off where logging in again right away can't help (circuit open) or makes it worse (lockout)
+    private NotificationOptions FailureNotification(bool retry) =>
+        new(NotificationUrgency.Critical, "network.error", retry ? RequestRetry : null);
+
+    private void RequestRetry()
+    {
+        if (RetryRequested is { } handler)
+            handler(this, EventArgs.Empty);
+        else
+            _ = LoginAsync();
+    }
+
+    /// <summary>Tells the user another device holds the account's session; logging in again won't help until it logs out.</summary>
+    private LoginResult SessionLimit(string reason)
+    {
+        _logger.LogError("Login refused, session limit reached: {Reason}", reason);
+        _notificationService.Show(Message(MessageKey.SessionLimitTitle),
+            Message(MessageKey.SessionLimitBody, reason.TrimEnd('.')), FailureNotification(retry: false));
+        return LoginResult.Failed(LoginFailure.SessionLimit, reason);
+    }
+
+    private LoginResult AccountLocked(string reason, TimeSpan? retryAfter)
+    {
+        var wait = retryAfter is { } delay
+            ? Message(MessageKey.AccountLockedRetryIn, Math.Ceiling(delay.TotalMinutes))
+            : Message(MessageKey.AccountLockedWait);
+        _logger.LogError("Account locked by portal: {Reason}. Retry after: {RetryAfter}", reason, retryAfter?.ToString() ?? "unknown");
+        _notificationService.Show(Message(MessageKey.AccountLockedTitle), $"{reason.TrimEnd('.')}. {wait}", FailureNotification(retry: false));
+        return LoginResult.Failed(LoginFailure.AccountLocked, reason, retryAfter);
+    }
+
+    private static bool IsFormTokenRejection(HttpResponseMessage response, string text)
+    {
+        if (response.IsSuccessStatusCode)
+            return LoginResponseParser.TryGetRejection(text, out var reason) && LoginPageParser.MentionsFormToken(reason);
+
+        // 419 is the de-facto "CSRF token mismatch" status
+        return (response.StatusCode is HttpStatusCode.Forbidden or HttpStatusCode.BadRequest or (HttpStatusCode)419)
+            && LoginPageParser.MentionsFormToken(text);
+    }
+
+    /// <summary>GETs the login page and returns its hidden fields that the login form doesn't already set.</summary>
+    private async Task<Dictionary<string, string>> FetchFormTokensAsync(
+        IReadOnlyDictionary<string, string> fields, CancellationToken ct)
+    {
+        try
+        {
+            using var response = await _httpClient.GetAsync(_config.PortalUrl, ct);
+            response.EnsureSuccessStatusCode();
+            var html = await PortalResponseDecoder.ReadAsync(response.Content, ct);
+            return LoginPageParser.GetHiddenFields(html)
+                .Where(f => !fields.ContainsKey(f.Key))
+                .ToDictionary(f => f.Key, f => f.Value);
+        }
+        catch (HttpRequestException e)
+        {
+            _logger.LogWarning("Could not fetch the login page for a form token: {Error}", e.Message);
+            return new Dictionary<string, string>();
+        }
+    }
+
+    /// <summary>Ends the portal session so it stops counting against the concurrent-session limit.</summary>
+    public async Task<bool> LogoutAsync(CancellationToken ct = default)
+    {
+        if (string.IsNullOrEmpty(_config.LogoutUrl))
+        {
+            _logger.LogWarning("Logout skipped: no logout_url is configured.");
+            return false;
+        }
+
+        var form = new FormUrlEncodedContent(new Dictionary<string, string>
+        {
+            ["userName"] = _config.Username,
+            ["uaddress"] = _config.IpAddress ?? "",
+            ["umac"] = _macAddress,
+            ["acip"] = Acip,
         });
 
         try
         {
-            var response = await _httpClient.PostAsync(_config.PortalUrl, form, ct);
+            var response = await _httpClient.PostAsync(_config.LogoutUrl, form, ct);
 
             if (response.IsSuccessStatusCode)
             {
-                var text = await response.Content.ReadAsStringAsync(ct);
-                _logger.LogDebug("Login response: {Response}", text);
-                _logger.LogInformation("Login request sent successfully.");
-                _notificationService.Show("Login Successful", $"Logged in as {username}");
+                _logger.LogInformation("Logout request sent.");
                 return true;
             }
 
-            _logger.LogError("Login failed with status: {Status}", response.StatusCode);
-            _notificationService.Show("Login Failed", $"Status: {response.StatusCode}");
+            _logger.LogWarning("Logout failed with status: {Status}", response.StatusCode);
             return false;
         }
-        catch (Exception e) when (e is not OperationCanceledException)
+        catch (Exception e) when (e is not OperationCanceledException || !ct.IsCancellationRequested)
         {
-            _logger.LogError(e, "Login connection error");
+            _logger.LogWarning("Logout connection error: {Error}", e.Message);
             return false;
         }
     }
 
     public async Task<bool> HeartbeatAsync(CancellationToken ct = default)
+    {
+        using var step = BeginStep("heartbeat");
+        var started = Stopwatch.GetTimestamp();
+        var ok = await SendHeartbeatAsync(ct);
+        _logger.LogDebug("Heartbeat finished in {ElapsedMs:F0} ms ({Outcome})",
+            Stopwatch.GetElapsedTime(started).TotalMilliseconds, ok ? "ok" : "failed");
+        Stats.RecordHeartbeat(ok);
+        return ok;
+    }
+
+    /// <summary>
+    /// Keeps <paramref name="body"/> as <see cref="LastResponse"/>. At Verbose/Trace log level it is also
+    /// written to <c>last-response.txt</c> in the log directory, for <c>kmitlnetauth last-response</c>.
+    /// </summary>
+    private void RecordResponse(string request, HttpResponseMessage response, string body, string? password = null)
+    {
+        var captured = PortalResponse.Capture(
+            request, (int)response.StatusCode, response.Content.Headers.ContentType?.MediaType, body, password);
+        LastResponse = captured;
+
+        if (!_logger.IsEnabled(LogLevel.Trace))
+            return;
+
+        try
+        {
+            var directory = _config.GetLogDirectory();
+            Directory.CreateDirectory(directory);
+            File.WriteAllText(Path.Combine(directory, LastResponseFileName), captured.ToString());
+        }
+        catch (Exception e) when (e is IOException or UnauthorizedAccessException)
+        {
+            _logger.LogDebug("Could not write {File}: {Error}", LastResponseFileName, e.Message);
+        }
+    }
+
+    // Structured sinks get a Step property on every line logged while it runs, next to the service's Cycle
+    private IDisposable? BeginStep(string step) =>
+        _logger.BeginScope(new Dictionary<string, object> { ["Step"] = step });
+
+    private string SessionUserAgent()
+    {
+        var agents = _config.HeartbeatUserAgents;
+        if (agents.Count == 0)
+            return _config.HeartbeatUserAgent;
+        return _sessionUserAgent ??= agents[Random.Shared.Next(agents.Count)];
+    }
+
+    private async Task<bool> SendHeartbeatAsync(CancellationToken ct)
     {
         var form = new FormUrlEncodedContent(new Dictionary<string, string>
         {
             ["username"] = _config.Username,
-            ["os"] = _config.HeartbeatUserAgent,
+            ["os"] = SessionUserAgent(),
             ["speed"] = "1.29",
             ["newauth"] = "1",
         });
@@ -93,6 +588,7 @@ public sealed class AuthClient
         try
         {
             var response = await _httpClient.PostAsync(_config.HeartbeatUrl, form, ct);
+            RecordResponse("Heartbeat", response, await PortalResponseDecoder.ReadAsync(response.Content, ct));
 
             if (response.IsSuccessStatusCode)
             {
@@ -103,24 +599,144 @@ public sealed class AuthClient
             _logger.LogWarning("Heartbeat failed with status: {Status}", response.StatusCode);
             return false;
         }
-        catch (Exception e) when (e is not OperationCanceledException)
+        catch (Exception e) when (e is not OperationCanceledException || !ct.IsCancellationRequested)
         {
             _logger.LogWarning("Heartbeat connection error: {Error}", e.Message);
             return false;
         }
     }
 
+    /// <summary>
+    /// Whether the network is usable, judged by <see cref="Config.ConnectivityCheck"/>. With
+    /// <c>heartbeat_only</c> this sends the heartbeat itself.
+    /// </summary>
+    public async Task<bool> CheckConnectivityAsync(CancellationToken ct = default) =>
+        _config.ConnectivityCheck.ToLowerInvariant() switch
+        {
+            "heartbeat_only" => await HeartbeatAsync(ct),
+            "gateway_ping" => await PingGatewayAsync(ct),
+            _ => await CheckInternetAsync(ct),
+        };
+
+    private async Task<bool> PingGatewayAsync(CancellationToken ct)
+    {
+        var gateway = _networkInfo.GetDefaultGateway(_config.Interface);
+        if (gateway == null)
+        {
+            _logger.LogWarning("No default gateway found to ping.");
+            return false;
+        }
+
+        try
+        {
+            using var ping = new Ping();
+            var reply = await ping.SendPingAsync(gateway, TimeSpan.FromSeconds(_config.Timeout), cancellationToken: ct);
+            return reply.Status == IPStatus.Success;
+        }
+        catch (PingException e)
+        {
+            _logger.LogWarning("Gateway ping to {Gateway} failed: {Error}", gateway, e.Message);
+            return false;
+        }
+    }
+
+    /// <summary>
+    /// Tries <see cref="Config.InternetCheckUrl"/>, then each fallback probe in order; online if any succeeds.
+    /// A success served from a cache is inconclusive: the next probe decides, and a check that only ever
+    /// gets cached answers counts as offline.
+    /// </summary>
     public async Task<bool> CheckInternetAsync(CancellationToken ct = default)
+    {
+        using var step = BeginStep("check_internet");
+        var cachedOnly = false;
+        foreach (var url in _config.InternetCheckFallbackUrls.Prepend(_config.InternetCheckUrl))
+        {
+            var started = Stopwatch.GetTimestamp();
+            var ok = await ProbeAsync(url, ct);
+            _logger.LogDebug("Connectivity probe {Url} {Outcome} in {ElapsedMs:F0} ms",
+                url, ok switch { true => "succeeded", false => "failed", null => "was answered from a cache" },
+                Stopwatch.GetElapsedTime(started).TotalMilliseconds);
+            if (ok == true)
+                return true;
+            cachedOnly |= ok == null;
+        }
+
+        if (cachedOnly)
+            _logger.LogDebug("Only cached answers to the connectivity check; not trusting them");
+        return false;
+    }
+
+    // Null for a success that came from a cache, which a cache can serve long after the path to the internet is gone
+    private async Task<bool?> ProbeAsync(string url, CancellationToken ct)
     {
         try
         {
-            var response = await _httpClient.GetAsync(_config.InternetCheckUrl, ct);
-            var text = await response.Content.ReadAsStringAsync(ct);
-            return text.Trim() == "success";
+            using var request = CreateProbeRequest(url);
+            var response = await _httpClient.SendAsync(request, ct);
+            var cached = _config.BypassProbeCache && LooksCached(response);
+
+            if (response.StatusCode == HttpStatusCode.NoContent)
+                return cached ? null : true;
+
+            var text = await PortalResponseDecoder.ReadAsync(response.Content, ct);
+            var online = response.IsSuccessStatusCode &&
+                (text.Trim() == "success" || text.Contains("<TITLE>Success</TITLE>", StringComparison.OrdinalIgnoreCase));
+            if (!online)
+                LearnFromRedirect(response, url);
+            return online && cached ? null : online;
         }
         catch
         {
             return false;
         }
     }
+
+    private HttpRequestMessage CreateProbeRequest(string url)
+    {
+        if (!_config.BypassProbeCache)
+            return new HttpRequestMessage(HttpMethod.Get, url);
+
+        var separator = url.Contains('?') ? '&' : '?';
+        var request = new HttpRequestMessage(HttpMethod.Get, $"{url}{separator}_={Guid.NewGuid():N}");
+        request.Headers.CacheControl = new CacheControlHeaderValue { NoCache = true };
+        request.Headers.Pragma.Add(new NameValueHeaderValue("no-cache"));
+        return request;
+    }
+
+    // A unique URL can't be a genuine cache hit, so these mean a cache that ignores query strings
+    private static bool LooksCached(HttpResponseMessage response)
+    {
+        if (response.Headers.Age is { } age && age > TimeSpan.Zero)
+            return true;
+
+        foreach (var header in (string[])["X-Cache", "X-Cache-Status", "CF-Cache-Status"])
+        {
+            if (response.Headers.TryGetValues(header, out var values) &&
+                values.Any(value => value.Contains("HIT", StringComparison.OrdinalIgnoreCase)))
+                return true;
+        }
+
+        return false;
+    }
+
+    // A probe caught by the captive portal is redirected to its login page, whose URL usually names
+    // the access controller for this network segment and, on some variants, is itself the login endpoint
+    private void LearnFromRedirect(HttpResponseMessage response, string url)
+    {
+        var requested = response.RequestMessage?.RequestUri ?? new Uri(url);
+        var redirect = response.Headers.Location is { } location ? new Uri(requested, location) : requested;
+
+        if (_config.LearnAcip && LoginPageParser.GetAcipFromRedirect(redirect) is { } acip && acip != _learnedAcip)
+        {
+            _logger.LogInformation("Captive portal redirect names access controller {Acip}; using it for login", acip);
+            _learnedAcip = acip;
+        }
+
+        if (_config.LoginMethod.Equals("auto", StringComparison.OrdinalIgnoreCase) && !_redirectTakesLogin &&
+            LoginPageParser.IsLoginRedirect(redirect, _config.PortalUrl))
+        {
+            _logger.LogInformation("Captive portal redirects to the login URL with a query string; logging in with GET");
+            _redirectTakesLogin = true;
+        }
+    }
 }
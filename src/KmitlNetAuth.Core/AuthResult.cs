@@ -6,10 +6,68 @@ public enum AuthStatus
     Offline,
     Connecting,
     Paused,
+
+    /// <summary>
+    /// The user must fix something (e.g. rejected credentials) before auth can succeed.
+    /// See <see cref="AuthStatusChangedEventArgs.Reason"/>.
+    /// </summary>
+    NeedsAttention,
 }
 
 public sealed class AuthStatusChangedEventArgs : EventArgs
 {
     public AuthStatus OldStatus { get; init; }
     public AuthStatus NewStatus { get; init; }
+    public string? Reason { get; init; }
+}
+
+public enum LoginFailure
+{
+    None,
+    MissingCredentials,
+    Rejected,
+    HttpError,
+    ConnectionError,
+
+    /// <summary>No answer within <see cref="Config.Timeout"/>; usually a slow or congested network, worth retrying.</summary>
+    Timeout,
+
+    /// <summary>The host actively refused the connection; usually a wrong portal host or port.</summary>
+    ConnectionRefused,
+
+    /// <summary>Skipped: too many consecutive connection errors, see <see cref="CircuitBreaker"/>.</summary>
+    CircuitOpen,
+
+    /// <summary>The portal answered with something other than a login result, e.g. an HTML error page.</summary>
+    UnexpectedResponse,
+
+    /// <summary>Too many failed logins; see <see cref="LoginResult.RetryAfter"/> for when to try again.</summary>
+    AccountLocked,
+
+    /// <summary>Another session (usually another device) holds the account's only slot.</summary>
+    SessionLimit,
 }
+
+public sealed class LoginResult
+{
+    public bool Success => Failure == LoginFailure.None;
+    public LoginFailure Failure { get; init; }
+    public string? Message { get; init; }
+
+    /// <summary>How long the portal asked us to wait, when it said so.</summary>
+    public TimeSpan? RetryAfter { get; init; }
+
+    public static LoginResult Succeeded() => new();
+
+    public static LoginResult Failed(LoginFailure failure, string? message = null, TimeSpan? retryAfter = null) =>
+        new() { Failure = failure, Message = message, RetryAfter = retryAfter };
+}
+
+/// <summary>
+/// What <see cref="Services.IAuthService.ReloginAsync"/> did: whether the portal accepted the logout (null when
+/// no <see cref="Config.LogoutUrl"/> is set and it was skipped), and the login after it.
+/// </summary>
+public sealed record ReloginResult(bool? LoggedOut, LoginResult Login);
+
+/// <summary>The last login of one <see cref="Config.AdditionalMacs"/> device.</summary>
+public sealed record DeviceLogin(string MacAddress, DateTimeOffset At, LoginResult Result);
@@ -0,0 +1,109 @@
+namespace KmitlNetAuth.Core;
+
+/// <summary>
+/// Running counters for one process, updated by <see cref="AuthClient"/> and the auth service.
+/// Safe to read from any thread; values are a best-effort snapshot, not a consistent set.
+/// </summary>
+public sealed class AuthStats
+{
+    private long _logins;
+    private long _loginFailures;
+    private long _heartbeats;
+    private long _heartbeatFailures;
+    private long _disconnects;
+    private long _connectedTicks;
+
+    public DateTimeOffset StartedAt { get; } = DateTimeOffset.Now;
+    public long Logins => Interlocked.Read(ref _logins);
+    public long LoginFailures => Interlocked.Read(ref _loginFailures);
+    public long Heartbeats => Interlocked.Read(ref _heartbeats);
+    public long HeartbeatFailures => Interlocked.Read(ref _heartbeatFailures);
+    public long Disconnects => Interlocked.Read(ref _disconnects);
+    public DateTimeOffset? LastLoginAt { get; private set; }
+    public DateTimeOffset? LastHeartbeatAt { get; private set; }
+    public DateTimeOffset? LastErrorAt { get; private set; }
+    public string? LastError { get; private set; }
+
+    /// <summary>When the current connected stretch began; null while offline.</summary>
+    public DateTimeOffset? OnlineSince { get; private set; }
+
+    /// <summary>How long the connection has been up without a failed check; zero while offline.</summary>
+    public TimeSpan ConnectedFor => OnlineSince is { } since ? DateTimeOffset.Now - since : TimeSpan.Zero;
+
+    /// <summary>Total time connected since <see cref="StartedAt"/>, including the current stretch.</summary>
+    public TimeSpan TotalConnected => TimeSpan.FromTicks(Interlocked.Read(ref _connectedTicks)) + ConnectedFor;
+
+    /// <summary>Share of heartbeats that succeeded, 0 to 1; null before the first one.</summary>
+    public double? HeartbeatSuccessRate => (Heartbeats + HeartbeatFailures) is > 0 and var total
+        ? (double)Heartbeats / total
+        : null;
+
+    /// <summary>The counters as a report for the end of the run.</summary>
+    public SessionSummary Summarize(bool loggedOut) =>
+        new(DateTimeOffset.Now - StartedAt, Logins, Heartbeats, HeartbeatSuccessRate, loggedOut);
+
+    internal void RecordLogin(LoginResult result)
+    {
+        if (result.Success)
+        {
+            Interlocked.Increment(ref _logins);
+            LastLoginAt = DateTimeOffset.Now;
+        }
+        else if (result.Failure != LoginFailure.CircuitOpen)
+        {
+            // A skipped attempt isn't a new failure
+            Interlocked.Increment(ref _loginFailures);
+            RecordError(result.Message ?? result.Failure.ToString());
+        }
+    }
+
+    /// <summary>A login the portal accepted but that didn't bring the connection up.</summary>
+    internal void RecordUnconfirmedLogin()
+    {
+        Interlocked.Increment(ref _loginFailures);
+        RecordError("Login reported success but still offline");
+    }
+
+    /// <summary>Replaces the last error with what a diagnosis found out about it.</summary>
+    internal void RefineLastError(string message) => RecordError(message);
+
+    internal void RecordHeartbeat(bool ok)
+    {
+        if (ok)
+        {
+            Interlocked.Increment(ref _heartbeats);
+            LastHeartbeatAt = DateTimeOffset.Now;
+            return;
+        }
+
+        Interlocked.Increment(ref _heartbeatFailures);
+        RecordError("Heartbeat failed");
+    }
+
+    internal void RecordDisconnect() => Interlocked.Increment(ref _disconnects);
+
+    /// <summary>The result of a connectivity check: starts a connected stretch, or ends and banks the current one.</summary>
+    internal void RecordConnectivity(bool online)
+    {
+        if (online)
+        {
+            OnlineSince ??= DateTimeOffset.Now;
+            return;
+        }
+
+        if (OnlineSince is { } since)
+        {
+            Interlocked.Add(ref _connectedTicks, (DateTimeOffset.Now - since).Ticks);
+            OnlineSince = null;
+        }
+    }
+
+    private void RecordError(string message)
+    {
+        LastError = message;
+        LastErrorAt = DateTimeOffset.Now;
+    }
+}
+
+/// <summary>What a run did, logged (and written to the status file) when the service stops.</summary>
+public sealed record SessionSummary(TimeSpan Uptime, long Logins, long Heartbeats, double? HeartbeatSuccessRate, bool LoggedOut);
@@ -0,0 +1,118 @@
+namespace KmitlNetAuth.Core;
+
+public enum CircuitState
+{
+    Closed,
+    Open,
+    HalfOpen,
+}
+
+/// <summary>
+/// Consecutive-failure circuit breaker. After <c>failureThreshold</c> failures in a row the
+/// circuit opens and <see cref="TryAcquire"/> refuses calls for the cooldown; then a single
+/// half-open probe decides whether it closes again or re-opens for another cooldown.
+/// A threshold of 0 disables the breaker.
+/// </summary>
+public sealed class CircuitBreaker
+{
+    private readonly int _failureThreshold;
+    private readonly TimeSpan _cooldown;
+    private readonly TimeProvider _timeProvider;
+    private readonly object _lock = new();
+
+    private int _consecutiveFailures;
+    private DateTimeOffset _openedAt;
+    private bool _open;
+    private bool _probeInFlight;
+
+    public CircuitBreaker(int failureThreshold, TimeSpan cooldown, TimeProvider? timeProvider = null)
+    {
+        _failureThreshold = failureThreshold;
+        _cooldown = cooldown;
+        _timeProvider = timeProvider ?? TimeProvider.System;
+    }
+
+    public CircuitState State
+    {
+        get
+        {
+            lock (_lock)
+                return GetState();
+        }
+    }
+
+    /// <summary>Returns true if a call may go ahead. In half-open state only one probe is let through.</summary>
+    public bool TryAcquire()
+    {
+        lock (_lock)
+        {
+            switch (GetState())
+            {
+                case CircuitState.Closed:
+                    return true;
+                case CircuitState.HalfOpen when !_probeInFlight:
+                    _probeInFlight = true;
+                    return true;
+                default:
+                    return false;
+            }
+        }
+    }
+
+    public void RecordSuccess()
+    {
+        lock (_lock)
+        {
+            _consecutiveFailures = 0;
+            _open = false;
+            _probeInFlight = false;
+        }
+    }
+
+    /// <summary>Records a failure. Returns true if this failure opened the circuit from closed.</summary>
+    public bool RecordFailure()
+    {
+        lock (_lock)
+        {
+            if (_failureThreshold <= 0)
+                return false;
+
+            if (_probeInFlight)
+            {
+                // Failed half-open probe: back to open for another full cooldown
+                _probeInFlight = false;
+                _openedAt = _timeProvider.GetUtcNow();
+                return false;
+            }
+
+            _consecutiveFailures++;
+            if (_open || _consecutiveFailures < _failureThreshold)
+                return false;
+
+            _open = true;
+            _openedAt = _timeProvider.GetUtcNow();
+            return true;
+        }
+    }
+
+    /// <summary>Time left before the next half-open probe; zero unless open.</summary>
+    public TimeSpan RemainingCooldown
+    {
+        get
+        {
+            lock (_lock)
+            {
+                if (GetState() != CircuitState.Open)
+                    return TimeSpan.Zero;
+                return _openedAt + _cooldown - _timeProvider.GetUtcNow();
+            }
+        }
+    }
+
+    private CircuitState GetState()
+    {
+        if (!_open)
+            return CircuitState.Closed;
+        return _timeProvider.GetUtcNow() - _openedAt >= _cooldown ? CircuitState.HalfOpen : CircuitState.Open;
+    }
+}
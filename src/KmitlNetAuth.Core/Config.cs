@@ -1,3 +1,7 @@
+using System.Globalization;
+using System.Net;
+using System.Security.Authentication;
+using KmitlNetAuth.Core.Exceptions;
 using KmitlNetAuth.Core.Platform;
 using Microsoft.Extensions.Logging;
 using Tomlyn;
@@ -10,29 +14,200 @@ public sealed class Config
     // [auth]
     public string Username { get; set; } = "";
     public string? Password { get; set; }
+
+    // Password as read from the config files, to tell a stale plaintext copy from one set since (env, code, wizard)
+    private string? _filePassword;
+
+    // "section.key"s the file read by LoadFile set, plus those edited since; Save writes only these and
+    // values that differ from the defaults. Null for a merged config, which Save writes in full.
+    private HashSet<string>? _fileKeys;
+    public string? EncryptedPassword { get; set; }
+
+    /// <summary>
+    /// False once the user chose not to remember the password (<see cref="Save"/> with
+    /// <c>rememberPassword</c> false): it stays in memory for this session, and every later save of this
+    /// config keeps it out of the file and the credential store too. Not written to the file.
+    /// </summary>
+    public bool RememberPassword { get; set; } = true;
     public string? IpAddress { get; set; }
+    public string? MacAddress { get; set; }
+
+    /// <summary>
+    /// MACs of other devices (a headless box, an IoT gadget) to keep logged in under the same account.
+    /// Each is logged in once per cycle while online; the heartbeat is per account, so it isn't repeated.
+    /// </summary>
+    public List<string> AdditionalMacs { get; set; } = [];
+
+    /// <summary>When false, the OS credential store is never touched; only env / <c>password_enc</c> are used.</summary>
+    public bool UseCredentialStore { get; set; } = true;
     public string PortalUrl { get; set; } = "https://portal.kmitl.ac.th:19008/portalauth/login";
     public string HeartbeatUrl { get; set; } = "https://nani.csc.kmitl.ac.th/network-api/data/";
     public string InternetCheckUrl { get; set; } = "http://detectportal.firefox.com/success.txt";
 
+    /// <summary>
+    /// Probes tried in order when <see cref="InternetCheckUrl"/> fails, so one blocked domain doesn't read as
+    /// offline. A probe succeeds on HTTP 204 or a "success" body (Firefox and Apple style).
+    /// </summary>
+    public List<string> InternetCheckFallbackUrls { get; set; } =
+    [
+        "http://captive.apple.com/hotspot-detect.html",
+        "http://connectivitycheck.gstatic.com/generate_204",
+    ];
+
+    /// <summary>
+    /// Portal logout endpoint. There is no default: the KMITL portal's logout request hasn't been verified,
+    /// so logout (and everything that uses it) is skipped until one is configured.
+    /// </summary>
+    public string LogoutUrl { get; set; } = "";
+
+    /// <summary>
+    /// <c>authType</c> sent with the login form. The student login page sends "1"; portal variants that
+    /// send something else show it in the form their login page submits.
+    /// </summary>
+    public string AuthType { get; set; } = "1";
+
+    /// <summary>Access controller address sent as <c>acip</c>; network segments with their own controller need theirs.</summary>
+    public string Acip { get; set; } = "10.252.13.10";
+
+    /// <summary>Use the controller address the captive portal redirect names instead of <see cref="Acip"/>, when it names one.</summary>
+    public bool LearnAcip { get; set; } = true;
+
+    /// <summary>
+    /// How the login fields reach the portal: <c>post_form</c> (a form POST to portal_url),
+    /// <c>get_redirect</c> (a GET to portal_url with the fields in the query string) or <c>auto</c>
+    /// (GET when the captive portal redirect lands on portal_url with a query string, else POST).
+    /// </summary>
+    public string LoginMethod { get; set; } = "post_form";
+
+    /// <summary>
+    /// A regular expression (a plain keyword works too), matched case-insensitively against a 2xx login
+    /// response body. When set, a match means success and no match means failure, in place of the
+    /// built-in JSON and HTML checks, for when the portal changes its response before this app does.
+    /// </summary>
+    public string? LoginSuccessPattern { get; set; }
+
+    /// <summary>
+    /// Extra login form fields, merged over the built-in ones (<c>agreed</c>, <c>acip</c>, <c>authType</c>,
+    /// <c>uaddress</c>, <c>umac</c>), for portal variants that expect more or different fields.
+    /// </summary>
+    public Dictionary<string, string> ExtraLoginParams { get; set; } = new();
+
     // [network]
     public int Timeout { get; set; } = 10;
     public bool AcceptInvalidCerts { get; set; } = true;
+
+    /// <summary>Oldest TLS version accepted from the portal and the other endpoints: <c>1.2</c> or <c>1.3</c>.</summary>
+    public string MinTlsVersion { get; set; } = "1.2";
+
+    /// <summary>The protocols <see cref="MinTlsVersion"/> allows, for handlers and <c>SslStream</c>s.</summary>
+    public SslProtocols EnabledSslProtocols =>
+        MinTlsVersion.Trim() == "1.3" ? SslProtocols.Tls13 : SslProtocols.Tls12 | SslProtocols.Tls13;
     public string HeartbeatUserAgent { get; set; } = "Chrome v116.0.5845.141 on Windows 10 64-bit";
 
+    /// <summary>
+    /// <c>os</c> strings to send with the heartbeat instead of <see cref="HeartbeatUserAgent"/>, one picked
+    /// at random for each portal session, so the value isn't a fixed fingerprint. Empty (the default) keeps
+    /// the single string. Only the heartbeat payload changes; the HTTP User-Agent header doesn't.
+    /// </summary>
+    public List<string> HeartbeatUserAgents { get; set; } = [];
+    public string? Interface { get; set; }
+
+    /// <summary>
+    /// How "online" is decided: <c>external_url</c> (GET internet_check_url), <c>heartbeat_only</c>
+    /// (the portal heartbeat alone) or <c>gateway_ping</c> (ping the default gateway).
+    /// </summary>
+    public string ConnectivityCheck { get; set; } = "external_url";
+
+    /// <summary>Consecutive portal connection errors before logins pause (0 disables).</summary>
+    public int CircuitBreakerThreshold { get; set; } = 5;
+    public int CircuitBreakerCooldown { get; set; } = 300;
+
+    /// <summary>Check (and log in) right away when the OS reports an address or link change, instead of at the next tick.</summary>
+    public bool RecheckOnNetworkChange { get; set; }
+
+    /// <summary>Resolve the portal hosts once at startup and connect to the cached addresses, re-resolving only on failure.</summary>
+    public bool CacheDns { get; set; }
+
+    /// <summary>
+    /// Send connectivity probes with <c>Cache-Control: no-cache</c> and a unique query parameter, and don't
+    /// trust an answer a cache says it served. Turn off for a check URL that rejects unknown parameters.
+    /// </summary>
+    public bool BypassProbeCache { get; set; } = true;
+
+    /// <summary>
+    /// Remember the portal's certificate fingerprint and warn when it changes, even with
+    /// <see cref="AcceptInvalidCerts"/> on. See <see cref="PortalCertificateWatcher"/>.
+    /// </summary>
+    public bool WatchCertificate { get; set; } = true;
+
     // [service]
     public ulong Interval { get; set; } = 300;
     public uint MaxAttempt { get; set; } = 20;
     public int BackoffInterval { get; set; } = 60;
+
+    /// <summary>Consecutive failed heartbeats (while online) before a full re-login; one hiccup isn't enough.</summary>
+    public int HeartbeatFailureThreshold { get; set; } = 2;
+
+    /// <summary>Seconds to wait after a login before its first heartbeat, while the portal activates the session.</summary>
+    public int PostLoginGrace { get; set; } = 3;
+
+    /// <summary>
+    /// An interval longer than this many seconds is warned about at startup and flagged on the dashboard,
+    /// since an hours-long interval typed by mistake looks like a hung service. 0 disables the warning.
+    /// </summary>
+    public int IntervalWarningThreshold { get; set; } = 1800;
+
+    /// <summary>True if <see cref="Interval"/> is over <see cref="IntervalWarningThreshold"/>.</summary>
+    public bool IsIntervalUnusuallyLong => IntervalWarningThreshold > 0 && Interval > (ulong)IntervalWarningThreshold;
     public bool AutoLogin { get; set; } = true;
 
+    /// <summary>Only monitor connectivity (check + heartbeat + notifications); never send a login.</summary>
+    public bool MonitorOnly { get; set; }
+    /// <summary>
+    /// Best-effort portal logout on graceful shutdown, freeing the session for other devices. Needs
+    /// <see cref="LogoutUrl"/>.
+    /// </summary>
+    public bool LogoutOnExit { get; set; }
+
+    /// <summary>Log in once right at startup, before the first connectivity check, then run the normal loop.</summary>
+    public bool LoginOnStart { get; set; }
+
+    /// <summary>Interval is multiplied by this on battery below <see cref="BatteryThresholdPercent"/>. 1 disables.</summary>
+    public int BatteryIntervalMultiplier { get; set; } = 1;
+    public int BatteryThresholdPercent { get; set; } = 50;
+
+    /// <summary>Where the JSON status file goes; null uses <see cref="ConfigPaths.GetStatusFilePath"/>.</summary>
+    public string? StatusFile { get; set; }
+
+    /// <summary>Seconds between status file writes; 0 disables the file.</summary>
+    public int StatusFileInterval { get; set; } = 30;
+
+    /// <summary>Seconds after which the daemon shuts down gracefully, for a scheduler to restart it; 0 runs forever.</summary>
+    public int MaxRuntime { get; set; }
+
     // [logging]
     public string LogLevel { get; set; } = "Information";
     public string? LogDirectory { get; set; }
     public int LogRetentionDays { get; set; } = 30;
+    public string LogFormat { get; set; } = "text";
+
+    /// <summary>
+    /// Also write events at this level and above to a file of their own next to the main log, for
+    /// alerting on errors without reading the whole log. Null (the default) writes no extra file.
+    /// </summary>
+    public string? ErrorFileLevel { get; set; }
 
     // [notifications]
+    /// <summary>
+    /// Desktop popups (notify-send, toasts). Off, the tray icon, tooltip and balloons still follow the
+    /// status; only the popups stop. Checked per notification, so changing it needs no restart.
+    /// </summary>
     public bool NotificationsEnabled { get; set; } = true;
+    /// <summary>Also POST notifications here (Discord, Slack or ntfy webhook). Null disables it.</summary>
+    public string? WebhookUrl { get; set; }
+
+    /// <summary>Language of notifications and tray menu labels: <c>en</c> or <c>th</c> (see <see cref="Messages"/>).</summary>
+    public string Language { get; set; } = "en";
 
     // [update]
     public bool AutoUpdateCheck { get; set; } = true;
@@ -41,10 +216,249 @@ public sealed class Config
     // [tray]
     public bool StartMinimized { get; set; } = true;
 
-    public static Config Load(string path, ICredentialStore? credentialStore = null, ILogger? logger = null)
+    /// <summary>
+    /// Open the setup wizard on launch while no username is configured. A username from
+    /// <c>KMITL_USERNAME</c> already counts as configured; turn this off when credentials are provisioned
+    /// some other way and the tray should just start and report what's missing.
+    /// </summary>
+    public bool FirstRunWizard { get; set; } = true;
+
+    // [theme] - colours as "#RRGGBB" or a name (see ThemeColor); the defaults are the original palette
+    public string ThemeOnline { get; set; } = "#2ECC71";
+    public string ThemeOffline { get; set; } = "#E74C3C";
+    public string ThemeConnecting { get; set; } = "#F39C12";
+    public string ThemePaused { get; set; } = "#95A5A6";
+    public string ThemeNeedsAttention { get; set; } = "#FF1F1F";
+
+    /// <summary>Console colour of warning log lines; null keeps the console's default theme.</summary>
+    public string? ThemeLogWarning { get; set; }
+    /// <summary>Console colour of error and fatal log lines; null keeps the console's default theme.</summary>
+    public string? ThemeLogError { get; set; }
+
+    /// <summary>The configured colour for <paramref name="status"/>.</summary>
+    public string GetStatusColor(AuthStatus status) => status switch
+    {
+        AuthStatus.Online => ThemeOnline,
+        AuthStatus.Offline => ThemeOffline,
+        AuthStatus.Connecting => ThemeConnecting,
+        AuthStatus.Paused => ThemePaused,
+        _ => ThemeNeedsAttention,
+    };
+
+    // [api]
+    /// <summary>Port of the HTTP control API (see <see cref="Services.ControlApiServer"/>); 0 disables it.</summary>
+    public int ApiPort { get; set; }
+    /// <summary>Bearer token every control API request must send. Required when <see cref="ApiPort"/> is set.</summary>
+    public string? ApiToken { get; set; }
+    /// <summary>Address the control API listens on; only change it from localhost on a trusted network.</summary>
+    public string ApiBind { get; set; } = "127.0.0.1";
+
+    /// <summary>
+    /// Passphrase for <see cref="EncryptedPassword"/>. Runtime only, never written to disk.
+    /// Falls back to the <c>KMITL_PASSPHRASE</c> environment variable when unset.
+    /// </summary>
+    public string? Passphrase { get; set; }
+
+    // Paths a local mock portal must serve, relative to its base URL
+    public const string MockLoginPath = "/portalauth/login";
+    public const string MockHeartbeatPath = "/network-api/data/";
+    public const string MockInternetCheckPath = "/success.txt";
+    public const string MockLogoutPath = "/portalauth/logout";
+
+    /// <summary>
+    /// A config with every endpoint pointed at a local mock portal (e.g. WireMock on
+    /// <c>http://localhost:8080</c>) and the credential store disabled, for integration tests.
+    /// </summary>
+    public static Config ForMockPortal(string baseUrl, string username = "mockuser", string password = "mockpass")
+    {
+        var config = new Config
+        {
+            Username = username,
+            Password = password,
+            UseCredentialStore = false,
+            MacAddress = "00:11:22:33:44:55",
+        };
+        UseMockPortal(config, baseUrl);
+        return config;
+    }
+
+    private static void UseMockPortal(Config config, string baseUrl)
+    {
+        var root = baseUrl.TrimEnd('/');
+        config.PortalUrl = root + MockLoginPath;
+        config.HeartbeatUrl = root + MockHeartbeatPath;
+        config.InternetCheckUrl = root + MockInternetCheckPath;
+        config.InternetCheckFallbackUrls = [];
+        config.LogoutUrl = root + MockLogoutPath;
+    }
+
+    /// <param name="throwOnParseError">
+    /// Throw a <see cref="ConfigException"/> for a file that exists but can't be parsed, instead of
+    /// logging and skipping it. Callers that may save the config afterwards need this, or they'd
+    /// overwrite the user's settings with defaults.
+    /// </param>
+    public static Config Load(string path, ICredentialStore? credentialStore = null, ILogger? logger = null,
+        bool throwOnParseError = false) =>
+        LoadLayered(ConfigPaths.GetLayers(path), credentialStore, logger, throwOnParseError);
+
+    /// <summary>
+    /// Loads <paramref name="paths"/> in order, each file overriding only the keys it sets,
+    /// then applies environment overrides. Lets a system file carry site defaults (URLs,
+    /// interval) while the user file overrides just what it needs.
+    /// </summary>
+    public static Config LoadLayered(IReadOnlyList<string> paths, ICredentialStore? credentialStore = null, ILogger? logger = null,
+        bool throwOnParseError = false)
+    {
+        var config = new Config();
+
+        foreach (var path in paths)
+            LoadLayer(config, path, logger, throwOnParseError);
+
+        ApplyEnvironmentOverrides(config);
+        MigrateCredentials(config, credentialStore, logger);
+
+        return config;
+    }
+
+    /// <summary>
+    /// Loads just the file at <paramref name="path"/>: no system layer, environment overrides or credential
+    /// migration, and a file that doesn't parse throws. For editing the file (<c>config set</c>, the tray
+    /// settings) without writing values that came from elsewhere into it. <see cref="Save"/> then writes
+    /// back only the keys the file set and values changed since, so the system layer still applies to the rest.
+    /// </summary>
+    public static Config LoadFile(string path)
+    {
+        var config = new Config();
+        LoadLayer(config, path, null, throwOnParseError: true);
+        config._fileKeys = [.. ReadFileKeys(path)];
+        return config;
+    }
+
+    /// <summary>
+    /// The effective config for <paramref name="path"/> as TOML, each value annotated with where it came
+    /// from: the config file that set it, the environment, or the default. Secrets are redacted.
+    /// </summary>
+    public static string DescribeEffective(string path, ICredentialStore? credentialStore = null)
     {
         var config = new Config();
+        var sources = new Dictionary<string, string>();
+
+        foreach (var layer in ConfigPaths.GetLayers(path))
+        {
+            var before = ReadValues(config);
+            LoadLayer(config, layer, null);
+            var source = File.Exists(layer) ? layer : Path.ChangeExtension(layer, ".yaml");
+            foreach (var key in ChangedKeys(before, ReadValues(config)).Concat(ReadFileKeys(layer)))
+                sources[key] = source;
+        }
+
+        var beforeEnv = ReadValues(config);
+        var passwordBeforeEnv = config.Password;
+        ApplyEnvironmentOverrides(config);
+        foreach (var key in ChangedKeys(beforeEnv, ReadValues(config)))
+            sources[key] = "environment";
+
+        string? passwordSource = null;
+        if (!string.IsNullOrEmpty(config.Password))
+            passwordSource = config.Password != passwordBeforeEnv
+                ? "environment (KMITL_PASSWORD)"
+                : sources.GetValueOrDefault("auth.password", "config file");
+        else if (config.EncryptedPassword != null)
+            passwordSource = "password_enc (needs KMITL_PASSPHRASE)";
+        else if (config.UseCredentialStore && credentialStore != null && !string.IsNullOrEmpty(config.Username))
+        {
+            try
+            {
+                if (credentialStore.GetPasswordAsync(config.Username).GetAwaiter().GetResult() != null)
+                    passwordSource = "credential store";
+            }
+            catch
+            {
+                // Treated as not stored
+            }
+        }
+
+        var output = new System.Text.StringBuilder();
+        var section = "";
+        foreach (var line in SerializeToToml(config).Split('\n'))
+        {
+            var trimmed = line.TrimEnd();
+            if (trimmed.StartsWith('['))
+                section = trimmed.Trim('[', ']');
+
+            if (SplitKeyValue(trimmed) is not { } kv)
+            {
+                output.AppendLine(trimmed);
+                continue;
+            }
+
+            // A redacted secret is commented out, so pasting the output back as a config can't set it to "<redacted>"
+            var source = sources.GetValueOrDefault($"{section}.{kv.Key}", "default");
+            var secret = (section, kv.Key) is ("auth", "password_enc") or ("api", "token");
+            output.AppendLine(secret && kv.Value != "\"\""
+                ? $"# {kv.Key} = <redacted>  # {source}"
+                : $"{kv.Key} = {kv.Value}  # {source}");
+
+            if (section == "auth" && kv.Key == "username")
+                output.AppendLine(passwordSource != null
+                    ? $"# password = <redacted>  # {passwordSource}"
+                    : "# password: not set");
+        }
 
+        return output.ToString();
+    }
+
+    // "section.key" -> serialized value, for comparing a config before and after a layer is applied
+    private static Dictionary<string, string> ReadValues(Config config)
+    {
+        var values = new Dictionary<string, string>();
+        var section = "";
+        foreach (var line in SerializeToToml(config).Split('\n'))
+        {
+            var trimmed = line.Trim();
+            if (trimmed.StartsWith('['))
+                section = trimmed.Trim('[', ']');
+            else if (SplitKeyValue(trimmed) is { } kv)
+                values[$"{section}.{kv.Key}"] = kv.Value;
+        }
+
+        return values;
+    }
+
+    private static KeyValuePair<string, string>? SplitKeyValue(string line)
+    {
+        var eq = line.IndexOf(" = ", StringComparison.Ordinal);
+        return eq > 0 && !line.StartsWith('#') ? new(line[..eq], line[(eq + 3)..]) : null;
+    }
+
+    private static IEnumerable<string> ChangedKeys(Dictionary<string, string> before, Dictionary<string, string> after) =>
+        after.Where(kv => before.GetValueOrDefault(kv.Key) != kv.Value).Select(kv => kv.Key);
+
+    // Keys a TOML file sets explicitly, so a value equal to the default is still attributed to the file
+    private static IEnumerable<string> ReadFileKeys(string path)
+    {
+        if (!File.Exists(path))
+            return [];
+
+        try
+        {
+            var table = TomlSerializer.Deserialize<TomlTable>(File.ReadAllText(path));
+            if (table == null)
+                return [];
+
+            return table
+                .Where(section => section.Value is TomlTable)
+                .SelectMany(section => ((TomlTable)section.Value).Keys.Select(key => $"{section.Key}.{key}"))
+                .ToList();
+        }
+        catch (Exception)
+        {
+            return [];
+        }
+    }
+
+    private static void LoadLayer(Config config, string path, ILogger? logger, bool throwOnParseError = false)
+    {
         // Try TOML first, then YAML for backward compatibility
         if (File.Exists(path))
         {
@@ -55,10 +469,20 @@ public sealed class Config
                 {
                     LoadFromToml(config, content);
                 }
+                catch (ConfigException e)
+                {
+                    // A value we can't use (e.g. interval = "5 minutes") always stops the load: ignoring the whole
+                    // file over it would start the service on defaults instead of the user's settings
+                    logger?.LogError("Invalid config {Path}: {Error}", path, e.Message);
+                    throw new ConfigException($"{path}: {e.Message}", e);
+                }
+                catch (Exception e) when (!throwOnParseError)
+                {
+                    logger?.LogWarning("Failed to parse config {Path} (ignoring it): {Error}", path, e.Message);
+                }
                 catch (Exception e)
                 {
-                    logger?.LogWarning("Failed to parse config (using defaults): {Error}", e.Message);
-                    config = new Config();
+                    throw new ConfigException($"Failed to parse config '{path}': {e.Message}", e);
                 }
             }
         }
@@ -72,27 +496,60 @@ public sealed class Config
                 MigrateFromYaml(config, yamlPath, logger);
             }
         }
-
-        ApplyEnvironmentOverrides(config);
-        MigrateCredentials(config, credentialStore, logger);
-
-        return config;
     }
 
-    public void Save(string path, ICredentialStore? credentialStore = null, ILogger? logger = null)
+    /// <summary>
+    /// Writes the config to <paramref name="path"/>, moving the password into the credential store or
+    /// <c>password_enc</c> when possible. With <paramref name="rememberPassword"/> false (or
+    /// <see cref="RememberPassword"/> already false) the password stays in memory for this session only:
+    /// a password stored for the user earlier is deleted from the store, and neither <c>password</c> nor
+    /// <c>password_enc</c> is written.
+    /// </summary>
+    /// <returns>
+    /// Where the password was saved: <see cref="CredentialSource.CredentialStore"/>,
+    /// <see cref="CredentialSource.EncryptedConfig"/>, or <see cref="CredentialSource.None"/> when there was
+    /// none, it isn't remembered, or neither the store nor a passphrase was available.
+    /// </returns>
+    public CredentialSource Save(string path, ICredentialStore? credentialStore = null, ILogger? logger = null, bool rememberPassword = true)
     {
         var dir = Path.GetDirectoryName(path);
         if (!string.IsNullOrEmpty(dir))
             Directory.CreateDirectory(dir);
 
+        if (!rememberPassword)
+            RememberPassword = false;
+
         var configToSave = Clone();
+        if (!RememberPassword)
+        {
+            // Otherwise the next start would quietly log in with the password the user asked to forget
+            if (credentialStore != null && UseCredentialStore && !string.IsNullOrEmpty(Username))
+            {
+                try
+                {
+                    credentialStore.DeletePasswordAsync(Username).GetAwaiter().GetResult();
+                }
+                catch (Exception e)
+                {
+                    logger?.LogWarning("Could not delete password from credential store: {Error}", e.Message);
+                }
+            }
+
+            configToSave.Password = null;
+            configToSave.EncryptedPassword = null;
+            WriteAtomically(path, SerializeForSave(configToSave, path), logger);
+            return CredentialSource.None;
+        }
 
-        if (!string.IsNullOrEmpty(Password) && !string.IsNullOrEmpty(Username) && credentialStore != null)
+        var storedInCredentialStore = false;
+
+        if (!string.IsNullOrEmpty(Password) && !string.IsNullOrEmpty(Username) && credentialStore != null && UseCredentialStore)
         {
             try
             {
                 credentialStore.SetPasswordAsync(Username, Password).GetAwaiter().GetResult();
                 configToSave.Password = null;
+                storedInCredentialStore = true;
             }
             catch (Exception e)
             {
@@ -100,29 +557,421 @@ public sealed class Config
             }
         }
 
-        var toml = SerializeToToml(configToSave);
-        File.WriteAllText(path, toml);
+        // Credential store unavailable: fall back to passphrase encryption if one is configured
+        var savedTo = storedInCredentialStore ? CredentialSource.CredentialStore : CredentialSource.None;
+        if (!storedInCredentialStore && !string.IsNullOrEmpty(Password) && ResolvePassphrase() is { } passphrase)
+        {
+            EncryptedPassword = PasswordCipher.Encrypt(Password, passphrase);
+            configToSave.EncryptedPassword = EncryptedPassword;
+            configToSave.Password = null;
+            savedTo = CredentialSource.EncryptedConfig;
+        }
+
+        WriteAtomically(path, SerializeForSave(configToSave, path), logger);
+        return savedTo;
+    }
+
+    // Reading the output back before it replaces the file means a value the serializer mishandles fails
+    // the save, instead of leaving a config the next start can't parse
+    private static string SerializeForSave(Config config, string path)
+    {
+        var toml = SerializeToToml(config);
+        if (config._fileKeys is { } fileKeys)
+            toml = OmitUnsetDefaults(toml, fileKeys);
+        try
+        {
+            LoadFromToml(new Config(), toml);
+        }
+        catch (Exception e)
+        {
+            throw new ConfigException($"Not saving '{path}': the written config would not parse: {e.Message}", e);
+        }
+
+        return toml;
+    }
+
+    // Leaves out the keys the file didn't set that still hold their default, so a value from the system
+    // layer isn't shadowed by a default written into the user file
+    private static string OmitUnsetDefaults(string toml, HashSet<string> fileKeys)
+    {
+        var defaults = ReadValues(new Config());
+        var section = "";
+        var kept = new List<string>();
+        foreach (var line in toml.Split('\n'))
+        {
+            if (line.StartsWith('['))
+                section = line.Trim().Trim('[', ']');
+            else if (SplitKeyValue(line) is { } kv && !fileKeys.Contains($"{section}.{kv.Key}")
+                && defaults.GetValueOrDefault($"{section}.{kv.Key}") == kv.Value)
+                continue;
+
+            kept.Add(line);
+        }
+
+        return string.Join('\n', kept);
+    }
+
+    /// <summary>Suffix of the copy of the previous config that <see cref="Save"/> keeps next to it.</summary>
+    public const string BackupSuffix = ".bak";
+
+    // The tray and the daemon can save at the same time, and either can be killed mid-write. Each writer
+    // gets its own temp file that is renamed over the target, so readers see the old file or a complete
+    // new one and the last rename wins. The previous version is kept as <path>.bak.
+    private static void WriteAtomically(string path, string content, ILogger? logger)
+    {
+        var tempPath = $"{path}.{Guid.NewGuid():N}.tmp";
+        try
+        {
+            using (var stream = new FileStream(tempPath, FileMode.CreateNew, FileAccess.Write, FileShare.None))
+            using (var writer = new StreamWriter(stream))
+            {
+                writer.Write(content);
+                writer.Flush();
+                stream.Flush(flushToDisk: true);
+            }
+
+            try
+            {
+                if (File.Exists(path))
+                    File.Copy(path, path + BackupSuffix, overwrite: true);
+            }
+            catch (Exception e) when (e is IOException or UnauthorizedAccessException)
+            {
+                // Another save is refreshing the backup; the config itself matters more
+                logger?.LogDebug("Could not back up {Path}: {Error}", path, e.Message);
+            }
+
+            MoveWithRetry(tempPath, path);
+        }
+        finally
+        {
+            if (File.Exists(tempPath))
+                File.Delete(tempPath);
+        }
+    }
+
+    // Windows refuses to replace a file another process has open for an instant; rename on Unix never does
+    private static void MoveWithRetry(string source, string destination)
+    {
+        for (var attempt = 1; ; attempt++)
+        {
+            try
+            {
+                File.Move(source, destination, overwrite: true);
+                return;
+            }
+            catch (Exception e) when (e is IOException or UnauthorizedAccessException && attempt < 5)
+            {
+                Thread.Sleep(20 * attempt);
+            }
+        }
+    }
+
+    /// <summary>
+    /// Deletes the stored password for <see cref="Username"/> from the credential store, clears every
+    /// in-memory and <c>password_enc</c> copy, and removes the password from the file at
+    /// <paramref name="path"/>, leaving the rest of the file as it was. The username is kept.
+    /// </summary>
+    public void ForgetPassword(string path, ICredentialStore? credentialStore, ILogger? logger = null)
+    {
+        if (credentialStore != null && !string.IsNullOrEmpty(Username))
+        {
+            try
+            {
+                credentialStore.DeletePasswordAsync(Username).GetAwaiter().GetResult();
+            }
+            catch (Exception e)
+            {
+                logger?.LogWarning("Could not delete password from credential store: {Error}", e.Message);
+            }
+        }
+
+        Password = null;
+        EncryptedPassword = null;
+        Passphrase = null;
+
+        if (!File.Exists(path))
+            return;
+
+        // Only the file is rewritten; this config may also carry system-layer and environment values
+        var file = LoadFile(path);
+        file.Password = null;
+        file.EncryptedPassword = null;
+        file.Save(path, null, logger);
     }
 
-    public string GetPassword(ICredentialStore? credentialStore)
+    /// <param name="logger">Warned when a plaintext password in the config file conflicts with the credential store.</param>
+    public string GetPassword(ICredentialStore? credentialStore, ILogger? logger = null) =>
+        ResolvePassword(credentialStore, logger).Password;
+
+    /// <summary>
+    /// Which source <see cref="GetPassword"/> would use, without exposing the password, for
+    /// troubleshooting "it's using the wrong password".
+    /// </summary>
+    public CredentialSource GetPasswordSource(ICredentialStore? credentialStore) => ResolvePassword(credentialStore).Source;
+
+    private (string Password, CredentialSource Source) ResolvePassword(ICredentialStore? credentialStore, ILogger? logger = null)
     {
         if (!string.IsNullOrEmpty(Password))
-            return Password;
+        {
+            if (Password == Environment.GetEnvironmentVariable("KMITL_PASSWORD"))
+                return (Password, CredentialSource.Environment);
+
+            // A plaintext password left in the file goes stale when the store is updated after a password
+            // change (setup, Settings). The store is the managed copy, so it wins over what the file still says.
+            if (Password == _filePassword && GetStoredPassword(credentialStore) is { } stored && stored != Password)
+            {
+                logger?.LogWarning(
+                    "The password in the config file differs from the one in the credential store for '{Username}'; " +
+                    "using the credential store. Remove [auth] password from the config file.", Username);
+                return (stored, CredentialSource.CredentialStore);
+            }
 
-        if (!string.IsNullOrEmpty(Username) && credentialStore != null)
+            return (Password, CredentialSource.Config);
+        }
+
+        if (GetStoredPassword(credentialStore) is { } pwd)
+            return (pwd, CredentialSource.CredentialStore);
+
+        if (!string.IsNullOrEmpty(EncryptedPassword) && ResolvePassphrase() is { } passphrase)
         {
             try
             {
-                var pwd = credentialStore.GetPasswordAsync(Username).GetAwaiter().GetResult();
-                if (pwd != null)
-                    return pwd;
+                return (PasswordCipher.Decrypt(EncryptedPassword, passphrase), CredentialSource.EncryptedConfig);
             }
             catch { }
         }
 
-        return "";
+        return ("", CredentialSource.None);
+    }
+
+    private string? GetStoredPassword(ICredentialStore? credentialStore)
+    {
+        if (string.IsNullOrEmpty(Username) || credentialStore == null || !UseCredentialStore)
+            return null;
+
+        try
+        {
+            return credentialStore.GetPasswordAsync(Username).GetAwaiter().GetResult();
+        }
+        catch
+        {
+            return null;
+        }
+    }
+
+    private string? ResolvePassphrase() =>
+        NullIfEmpty(Passphrase) ?? NullIfEmpty(Environment.GetEnvironmentVariable("KMITL_PASSPHRASE"));
+
+    /// <summary>
+    /// Overlays only the keys present in the TOML file at <paramref name="path"/> onto this config.
+    /// Environment overrides and credential migration are not applied.
+    /// </summary>
+    public void MergeFrom(string path)
+    {
+        var content = File.ReadAllText(path);
+        try
+        {
+            LoadFromToml(this, content);
+        }
+        catch (Exception e)
+        {
+            throw new ConfigException($"Failed to parse config '{path}': {e.Message}", e);
+        }
+
+        _fileKeys?.UnionWith(ReadFileKeys(path));
+    }
+
+    /// <summary>
+    /// Scalar keys, as <c>section.key</c>, that <see cref="GetValue"/> and <see cref="SetValue"/> accept.
+    /// Lists, tables and <c>password_enc</c> are left to the config file.
+    /// </summary>
+    public static IReadOnlyList<string> EditableKeys => ReadValues(new Config())
+        .Where(kv => kv.Key != "auth.password_enc" && !kv.Value.StartsWith('[') && !kv.Value.StartsWith('{'))
+        .Select(kv => kv.Key)
+        .ToList();
+
+    /// <summary>The value of <paramref name="key"/> (<c>section.key</c>, or just <c>key</c> when unambiguous) as text.</summary>
+    public string GetValue(string key)
+    {
+        var value = ReadValues(this)[ResolveEditableKey(key)];
+        return value.StartsWith('"') ? (string)TomlSerializer.Deserialize<TomlTable>($"v = {value}")!["v"] : value;
+    }
+
+    /// <summary>
+    /// Sets <paramref name="key"/> from its text form: <c>true</c>/<c>false</c> for switches, a number (or a
+    /// duration like <c>5m</c>) for numbers, anything for strings. Run <see cref="Validate"/> before saving.
+    /// </summary>
+    public void SetValue(string key, string value)
+    {
+        var resolved = ResolveEditableKey(key);
+        var current = ReadValues(this)[resolved];
+        string literal;
+        if (current is "true" or "false")
+        {
+            if (!bool.TryParse(value, out var flag))
+                throw new ConfigException($"{resolved} must be true or false: '{value}'");
+            literal = flag ? "true" : "false";
+        }
+        else if (current.StartsWith('"'))
+        {
+            literal = Quote(value);
+        }
+        else if (long.TryParse(value, out var number))
+        {
+            literal = number.ToString(CultureInfo.InvariantCulture);
+        }
+        else if (DurationParser.TryParseSeconds(value, out var seconds))
+        {
+            literal = seconds.ToString(CultureInfo.InvariantCulture);
+        }
+        else
+        {
+            throw new ConfigException($"{resolved} must be a number: '{value}'");
+        }
+
+        var dot = resolved.IndexOf('.');
+        LoadFromToml(this, $"[{resolved[..dot]}]\n{resolved[(dot + 1)..]} = {literal}\n");
+        _fileKeys?.Add(resolved);
     }
 
+    private static string ResolveEditableKey(string key)
+    {
+        var keys = EditableKeys;
+        if (keys.Contains(key))
+            return key;
+
+        var matches = keys.Where(k => k[(k.IndexOf('.') + 1)..] == key).ToList();
+        return matches.Count switch
+        {
+            1 => matches[0],
+            0 => throw new ConfigException($"Unknown config key '{key}'. Valid keys: {string.Join(", ", keys)}"),
+            _ => throw new ConfigException($"Config key '{key}' is ambiguous; use one of {string.Join(", ", matches)}"),
+        };
+    }
+
+    /// <summary>
+    /// Checks field values for semantic errors. Returns an empty list when the config is valid.
+    /// </summary>
+    public IReadOnlyList<string> Validate()
+    {
+        var errors = new List<string>();
+
+        if (!IsHttpUrl(PortalUrl))
+            errors.Add($"[auth] portal_url is not a valid http(s) URL: '{PortalUrl}'");
+        if (!IsHttpUrl(HeartbeatUrl))
+            errors.Add($"[auth] heartbeat_url is not a valid http(s) URL: '{HeartbeatUrl}'");
+        if (!IsHttpUrl(InternetCheckUrl))
+            errors.Add($"[auth] internet_check_url is not a valid http(s) URL: '{InternetCheckUrl}'");
+        foreach (var url in InternetCheckFallbackUrls.Where(url => !IsHttpUrl(url)))
+            errors.Add($"[auth] internet_check_fallback_urls entry is not a valid http(s) URL: '{url}'");
+        if (LogoutUrl.Length > 0 && !IsHttpUrl(LogoutUrl))
+            errors.Add($"[auth] logout_url is not a valid http(s) URL: '{LogoutUrl}'");
+        if (IpAddress != null && !IPAddress.TryParse(IpAddress, out _))
+            errors.Add($"[auth] ip_address is not a valid IP address: '{IpAddress}'");
+        if (MacAddress != null && NetworkInfo.NormalizeMacAddress(MacAddress) == null)
+            errors.Add($"[auth] mac_address is not a valid MAC address: '{MacAddress}'");
+        if (string.IsNullOrWhiteSpace(AuthType))
+            errors.Add("[auth] auth_type must not be empty");
+        if (!IPAddress.TryParse(Acip, out _))
+            errors.Add($"[auth] acip is not a valid IP address: '{Acip}'");
+        if (!KnownLoginMethods.Contains(LoginMethod.ToLowerInvariant()))
+            errors.Add($"[auth] login_method must be 'post_form', 'get_redirect' or 'auto': '{LoginMethod}'");
+        if (LoginSuccessPattern != null && !LoginResponseParser.IsValidPattern(LoginSuccessPattern, out var patternError))
+            errors.Add($"[auth] login_success_pattern is not a valid regular expression: {patternError}");
+        foreach (var mac in AdditionalMacs.Where(mac => NetworkInfo.NormalizeMacAddress(mac) == null))
+            errors.Add($"[auth] additional_macs entry is not a valid MAC address: '{mac}'");
+        foreach (var key in ExtraLoginParams.Keys.Where(key => ReservedLoginParams.Contains(key)))
+            errors.Add($"[auth] extra_login_params must not set '{key}'; use username and the stored password");
+
+        if (Timeout <= 0)
+            errors.Add("[network] timeout must be greater than 0");
+        if (!KnownConnectivityChecks.Contains(ConnectivityCheck.ToLowerInvariant()))
+            errors.Add($"[network] connectivity_check must be 'external_url', 'heartbeat_only' or 'gateway_ping': '{ConnectivityCheck}'");
+        if (MinTlsVersion.Trim() is not ("1.2" or "1.3"))
+            errors.Add($"[network] min_tls_version must be '1.2' or '1.3': '{MinTlsVersion}'");
+        if (HeartbeatUserAgents.Any(string.IsNullOrWhiteSpace))
+            errors.Add("[network] heartbeat_user_agents must not contain empty entries");
+        if (CircuitBreakerThreshold < 0)
+            errors.Add("[network] circuit_breaker_threshold must not be negative");
+        if (CircuitBreakerCooldown <= 0)
+            errors.Add("[network] circuit_breaker_cooldown must be greater than 0");
+
+        if (Interval == 0)
+            errors.Add("[service] interval must be greater than 0");
+        if (MaxAttempt == 0)
+            errors.Add("[service] max_attempt must be greater than 0");
+        if (BackoffInterval < 0)
+            errors.Add("[service] backoff_interval must not be negative");
+        if (HeartbeatFailureThreshold < 1)
+            errors.Add("[service] heartbeat_failure_threshold must be at least 1");
+        if (PostLoginGrace < 0)
+            errors.Add("[service] post_login_grace must not be negative");
+        if (IntervalWarningThreshold < 0)
+            errors.Add("[service] interval_warning_threshold must not be negative");
+        if (BatteryIntervalMultiplier < 1)
+            errors.Add("[service] battery_interval_multiplier must be at least 1");
+        if (BatteryThresholdPercent is < 0 or > 100)
+            errors.Add("[service] battery_threshold_percent must be between 0 and 100");
+        if (StatusFileInterval < 0)
+            errors.Add("[service] status_file_interval must not be negative");
+        if (MaxRuntime < 0)
+            errors.Add("[service] max_runtime must not be negative");
+
+        if (!KnownLogLevels.Contains(LogLevel.ToLowerInvariant()))
+            errors.Add($"[logging] level is not recognized: '{LogLevel}'");
+        if (LogRetentionDays <= 0)
+            errors.Add("[logging] retention_days must be greater than 0");
+        if (!KnownLogFormats.Contains(LogFormat.ToLowerInvariant()))
+            errors.Add($"[logging] format must be 'text' or 'json': '{LogFormat}'");
+        if (ErrorFileLevel != null && !KnownLogLevels.Contains(ErrorFileLevel.ToLowerInvariant()))
+            errors.Add($"[logging] error_file_level is not recognized: '{ErrorFileLevel}'");
+
+        if (WebhookUrl != null && !IsHttpUrl(WebhookUrl))
+            errors.Add($"[notifications] webhook_url is not a valid http(s) URL: '{WebhookUrl}'");
+        if (!Messages.Languages.Contains(Language.ToLowerInvariant()))
+            errors.Add($"[notifications] language must be one of {string.Join(", ", Messages.Languages)}: '{Language}'");
+
+        if (UpdateCheckIntervalHours <= 0)
+            errors.Add("[update] check_interval_hours must be greater than 0");
+
+        foreach (var (key, value) in new[]
+        {
+            ("online", ThemeOnline), ("offline", ThemeOffline), ("connecting", ThemeConnecting), ("paused", ThemePaused),
+            ("needs_attention", ThemeNeedsAttention), ("log_warning", ThemeLogWarning), ("log_error", ThemeLogError),
+        })
+        {
+            if (value != null && !ThemeColor.TryParse(value, out _))
+                errors.Add($"[theme] {key} must be a #RRGGBB colour or one of {string.Join(", ", ThemeColor.Names)}: '{value}'");
+        }
+
+        if (ApiPort is < 0 or > 65535)
+            errors.Add($"[api] port must be between 0 and 65535: {ApiPort}");
+        if (ApiPort > 0 && string.IsNullOrEmpty(ApiToken))
+            errors.Add("[api] token must be set when [api] port is set");
+        if (string.IsNullOrWhiteSpace(ApiBind))
+            errors.Add("[api] bind must not be empty");
+
+        return errors;
+    }
+
+    private static readonly HashSet<string> KnownLogLevels =
+        ["verbose", "trace", "debug", "information", "info", "warning", "warn", "error", "fatal"];
+
+    /// <summary>Whether <paramref name="level"/> is a <c>[logging] level</c> value, in any case.</summary>
+    public static bool IsKnownLogLevel(string level) => KnownLogLevels.Contains(level.ToLowerInvariant());
+
+    private static readonly HashSet<string> KnownLogFormats = ["text", "json"];
+
+    private static readonly HashSet<string> KnownLoginMethods = ["post_form", "get_redirect", "auto"];
+
+    private static readonly HashSet<string> KnownConnectivityChecks = ["external_url", "heartbeat_only", "gateway_ping"];
+
+    private static readonly HashSet<string> ReservedLoginParams = new(StringComparer.OrdinalIgnoreCase) { "userName", "userPass" };
+
+    private static bool IsHttpUrl(string value) =>
+        Uri.TryCreate(value, UriKind.Absolute, out var uri) && (uri.Scheme == Uri.UriSchemeHttp || uri.Scheme == Uri.UriSchemeHttps);
+
     public string GetLogDirectory()
     {
         if (!string.IsNullOrEmpty(LogDirectory))
@@ -130,28 +979,79 @@ public sealed class Config
         return ConfigPaths.GetLogDirectory();
     }
 
-    private Config Clone() => new()
+    internal Config Clone() => new()
     {
         Username = Username,
         Password = Password,
+        _filePassword = _filePassword,
+        RememberPassword = RememberPassword,
+        _fileKeys = _fileKeys?.ToHashSet(),
+        EncryptedPassword = EncryptedPassword,
         IpAddress = IpAddress,
+        MacAddress = MacAddress,
+        UseCredentialStore = UseCredentialStore,
         PortalUrl = PortalUrl,
         HeartbeatUrl = HeartbeatUrl,
         InternetCheckUrl = InternetCheckUrl,
+        InternetCheckFallbackUrls = [.. InternetCheckFallbackUrls],
+        LogoutUrl = LogoutUrl,
+        AuthType = AuthType,
+        Acip = Acip,
+        LearnAcip = LearnAcip,
+        LoginMethod = LoginMethod,
+        LoginSuccessPattern = LoginSuccessPattern,
+        ExtraLoginParams = new Dictionary<string, string>(ExtraLoginParams),
+        AdditionalMacs = [.. AdditionalMacs],
         Timeout = Timeout,
         AcceptInvalidCerts = AcceptInvalidCerts,
+        MinTlsVersion = MinTlsVersion,
+        HeartbeatUserAgents = [.. HeartbeatUserAgents],
         HeartbeatUserAgent = HeartbeatUserAgent,
+        Interface = Interface,
+        ConnectivityCheck = ConnectivityCheck,
+        CircuitBreakerThreshold = CircuitBreakerThreshold,
+        CircuitBreakerCooldown = CircuitBreakerCooldown,
+        RecheckOnNetworkChange = RecheckOnNetworkChange,
+        CacheDns = CacheDns,
+        BypassProbeCache = BypassProbeCache,
+        WatchCertificate = WatchCertificate,
         Interval = Interval,
         MaxAttempt = MaxAttempt,
         BackoffInterval = BackoffInterval,
+        HeartbeatFailureThreshold = HeartbeatFailureThreshold,
+        PostLoginGrace = PostLoginGrace,
+        IntervalWarningThreshold = IntervalWarningThreshold,
         AutoLogin = AutoLogin,
+        MonitorOnly = MonitorOnly,
+        LogoutOnExit = LogoutOnExit,
+        LoginOnStart = LoginOnStart,
+        BatteryIntervalMultiplier = BatteryIntervalMultiplier,
+        BatteryThresholdPercent = BatteryThresholdPercent,
+        StatusFile = StatusFile,
+        StatusFileInterval = StatusFileInterval,
+        MaxRuntime = MaxRuntime,
         LogLevel = LogLevel,
         LogDirectory = LogDirectory,
         LogRetentionDays = LogRetentionDays,
+        LogFormat = LogFormat,
+        ErrorFileLevel = ErrorFileLevel,
         NotificationsEnabled = NotificationsEnabled,
+        WebhookUrl = WebhookUrl,
+        Language = Language,
         AutoUpdateCheck = AutoUpdateCheck,
         UpdateCheckIntervalHours = UpdateCheckIntervalHours,
         StartMinimized = StartMinimized,
+        FirstRunWizard = FirstRunWizard,
+        ThemeOnline = ThemeOnline,
+        ThemeOffline = ThemeOffline,
+        ThemeConnecting = ThemeConnecting,
+        ThemePaused = ThemePaused,
+        ThemeNeedsAttention = ThemeNeedsAttention,
+        ThemeLogWarning = ThemeLogWarning,
+        ThemeLogError = ThemeLogError,
+        ApiPort = ApiPort,
+        ApiToken = ApiToken,
+        ApiBind = ApiBind,
     };
 
     private static void LoadFromToml(Config config, string content)
@@ -163,25 +1063,59 @@ public sealed class Config
         {
             config.Username = GetString(auth, "username", config.Username);
             config.Password = GetString(auth, "password", config.Password);
+            config._filePassword = config.Password;
+            config.EncryptedPassword = NullIfEmpty(GetString(auth, "password_enc", config.EncryptedPassword));
             config.IpAddress = NullIfEmpty(GetString(auth, "ip_address", config.IpAddress));
+            config.MacAddress = NullIfEmpty(GetString(auth, "mac_address", config.MacAddress));
+            config.UseCredentialStore = GetBool(auth, "use_credential_store", config.UseCredentialStore);
             config.PortalUrl = GetString(auth, "portal_url", config.PortalUrl);
             config.HeartbeatUrl = GetString(auth, "heartbeat_url", config.HeartbeatUrl);
             config.InternetCheckUrl = GetString(auth, "internet_check_url", config.InternetCheckUrl);
+            config.InternetCheckFallbackUrls = GetStringList(auth, "internet_check_fallback_urls", config.InternetCheckFallbackUrls);
+            config.LogoutUrl = GetString(auth, "logout_url", config.LogoutUrl);
+            config.AuthType = GetString(auth, "auth_type", config.AuthType);
+            config.Acip = GetString(auth, "acip", config.Acip);
+            config.LearnAcip = GetBool(auth, "learn_acip", config.LearnAcip);
+            config.LoginMethod = GetString(auth, "login_method", config.LoginMethod);
+            config.LoginSuccessPattern = NullIfEmpty(GetString(auth, "login_success_pattern", config.LoginSuccessPattern));
+            config.ExtraLoginParams = GetStringTable(auth, "extra_login_params", config.ExtraLoginParams);
+            config.AdditionalMacs = GetStringList(auth, "additional_macs", config.AdditionalMacs);
         }
 
         if (GetSection(table, "network") is { } net)
         {
-            config.Timeout = GetInt(net, "timeout", config.Timeout);
+            config.Timeout = GetSeconds(net, "network", "timeout", config.Timeout);
             config.AcceptInvalidCerts = GetBool(net, "accept_invalid_certs", config.AcceptInvalidCerts);
+            config.MinTlsVersion = GetString(net, "min_tls_version", config.MinTlsVersion);
             config.HeartbeatUserAgent = GetString(net, "heartbeat_user_agent", config.HeartbeatUserAgent);
+            config.HeartbeatUserAgents = GetStringList(net, "heartbeat_user_agents", config.HeartbeatUserAgents);
+            config.Interface = NullIfEmpty(GetString(net, "interface", config.Interface));
+            config.ConnectivityCheck = GetString(net, "connectivity_check", config.ConnectivityCheck);
+            config.CircuitBreakerThreshold = GetInt(net, "circuit_breaker_threshold", config.CircuitBreakerThreshold);
+            config.CircuitBreakerCooldown = GetSeconds(net, "network", "circuit_breaker_cooldown", config.CircuitBreakerCooldown);
+            config.RecheckOnNetworkChange = GetBool(net, "recheck_on_network_change", config.RecheckOnNetworkChange);
+            config.CacheDns = GetBool(net, "cache_dns", config.CacheDns);
+            config.BypassProbeCache = GetBool(net, "bypass_probe_cache", config.BypassProbeCache);
+            config.WatchCertificate = GetBool(net, "watch_certificate", config.WatchCertificate);
         }
 
         if (GetSection(table, "service") is { } svc)
         {
-            config.Interval = (ulong)GetInt(svc, "interval", (int)config.Interval);
+            config.Interval = (ulong)GetSeconds(svc, "service", "interval", (int)config.Interval);
             config.MaxAttempt = (uint)GetInt(svc, "max_attempt", (int)config.MaxAttempt);
-            config.BackoffInterval = GetInt(svc, "backoff_interval", config.BackoffInterval);
+            config.BackoffInterval = GetSeconds(svc, "service", "backoff_interval", config.BackoffInterval);
+            config.HeartbeatFailureThreshold = GetInt(svc, "heartbeat_failure_threshold", config.HeartbeatFailureThreshold);
+            config.PostLoginGrace = GetSeconds(svc, "service", "post_login_grace", config.PostLoginGrace);
+            config.IntervalWarningThreshold = GetSeconds(svc, "service", "interval_warning_threshold", config.IntervalWarningThreshold);
             config.AutoLogin = GetBool(svc, "auto_login", config.AutoLogin);
+            config.MonitorOnly = GetBool(svc, "monitor_only", config.MonitorOnly);
+            config.LogoutOnExit = GetBool(svc, "logout_on_exit", config.LogoutOnExit);
+            config.LoginOnStart = GetBool(svc, "login_on_start", config.LoginOnStart);
+            config.BatteryIntervalMultiplier = GetInt(svc, "battery_interval_multiplier", config.BatteryIntervalMultiplier);
+            config.BatteryThresholdPercent = GetInt(svc, "battery_threshold_percent", config.BatteryThresholdPercent);
+            config.StatusFile = NullIfEmpty(GetString(svc, "status_file", config.StatusFile));
+            config.StatusFileInterval = GetSeconds(svc, "service", "status_file_interval", config.StatusFileInterval);
+            config.MaxRuntime = GetSeconds(svc, "service", "max_runtime", config.MaxRuntime);
         }
 
         if (GetSection(table, "logging") is { } log)
@@ -189,10 +1123,16 @@ public sealed class Config
             config.LogLevel = GetString(log, "level", config.LogLevel);
             config.LogDirectory = NullIfEmpty(GetString(log, "directory", config.LogDirectory));
             config.LogRetentionDays = GetInt(log, "retention_days", config.LogRetentionDays);
+            config.LogFormat = GetString(log, "format", config.LogFormat);
+            config.ErrorFileLevel = NullIfEmpty(GetString(log, "error_file_level", config.ErrorFileLevel));
         }
 
         if (GetSection(table, "notifications") is { } notif)
+        {
             config.NotificationsEnabled = GetBool(notif, "enabled", config.NotificationsEnabled);
+            config.WebhookUrl = NullIfEmpty(GetString(notif, "webhook_url", config.WebhookUrl));
+            config.Language = GetString(notif, "language", config.Language);
+        }
 
         if (GetSection(table, "update") is { } upd)
         {
@@ -201,11 +1141,32 @@ public sealed class Config
         }
 
         if (GetSection(table, "tray") is { } tray)
+        {
             config.StartMinimized = GetBool(tray, "start_minimized", config.StartMinimized);
+            config.FirstRunWizard = GetBool(tray, "first_run_wizard", config.FirstRunWizard);
+        }
+
+        if (GetSection(table, "theme") is { } theme)
+        {
+            config.ThemeOnline = GetString(theme, "online", config.ThemeOnline);
+            config.ThemeOffline = GetString(theme, "offline", config.ThemeOffline);
+            config.ThemeConnecting = GetString(theme, "connecting", config.ThemeConnecting);
+            config.ThemePaused = GetString(theme, "paused", config.ThemePaused);
+            config.ThemeNeedsAttention = GetString(theme, "needs_attention", config.ThemeNeedsAttention);
+            config.ThemeLogWarning = NullIfEmpty(GetString(theme, "log_warning", config.ThemeLogWarning));
+            config.ThemeLogError = NullIfEmpty(GetString(theme, "log_error", config.ThemeLogError));
+        }
+
+        if (GetSection(table, "api") is { } api)
+        {
+            config.ApiPort = GetInt(api, "port", config.ApiPort);
+            config.ApiToken = NullIfEmpty(GetString(api, "token", config.ApiToken));
+            config.ApiBind = GetString(api, "bind", config.ApiBind);
+        }
 
         // Flat keys for backward compatibility
         config.Username = GetString(table, "username", config.Username);
-        config.Interval = (ulong)GetInt(table, "interval", (int)config.Interval);
+        config.Interval = (ulong)GetSeconds(table, "", "interval", (int)config.Interval);
         config.AutoLogin = GetBool(table, "auto_login", config.AutoLogin);
         config.LogLevel = GetString(table, "log_level", config.LogLevel);
     }
@@ -219,39 +1180,133 @@ public sealed class Config
     private static int GetInt(TomlTable table, string key, int fallback) =>
         table.TryGetValue(key, out var v) && v is long l ? (int)l : fallback;
 
+    /// <summary>Reads seconds given either as an integer or a duration string like "5m".</summary>
+    private static int GetSeconds(TomlTable table, string section, string key, int fallback)
+    {
+        if (!table.TryGetValue(key, out var v))
+            return fallback;
+
+        return v switch
+        {
+            long l => (int)l,
+            string text when DurationParser.TryParseSeconds(text, out var seconds) => seconds,
+            _ => throw new ConfigException(
+                $"{(section.Length > 0 ? $"[{section}] " : "")}{key} is not a valid duration: '{v}' (use seconds or e.g. \"30s\", \"5m\", \"1h\")"),
+        };
+    }
+
     private static bool GetBool(TomlTable table, string key, bool fallback) =>
         table.TryGetValue(key, out var v) && v is bool b ? b : fallback;
 
+    private static List<string> GetStringList(TomlTable table, string key, List<string> fallback) =>
+        table.TryGetValue(key, out var v) && v is TomlArray array ? array.OfType<string>().ToList() : fallback;
+
+    private static Dictionary<string, string> GetStringTable(TomlTable table, string key, Dictionary<string, string> fallback) =>
+        table.TryGetValue(key, out var v) && v is TomlTable t
+            ? t.Where(kv => kv.Value != null).ToDictionary(kv => kv.Key, kv => kv.Value.ToString()!)
+            : fallback;
+
+    /// <summary>
+    /// Escapes <paramref name="value"/> for a TOML basic string: quotes, backslashes and every control
+    /// character, so any value stays on one line and parses back to itself.
+    /// </summary>
+    private static string EscapeTomlString(string value)
+    {
+        var escaped = new System.Text.StringBuilder(value.Length);
+        foreach (var c in value)
+        {
+            escaped.Append(c switch
+            {
+                '"' => "\\\"",
+                '\\' => "\\\\",
+                '\b' => "\\b",
+                '\t' => "\\t",
+                '\n' => "\\n",
+                '\f' => "\\f",
+                '\r' => "\\r",
+                < ' ' or '\u007f' => $"\\u{(int)c:X4}",
+                _ => c.ToString(),
+            });
+        }
+
+        return escaped.ToString();
+    }
+
+    private static string Quote(string? value) => $"\"{EscapeTomlString(value ?? "")}\"";
+
+    private static string FormatList(IEnumerable<string> values) => $"[{string.Join(", ", values.Select(Quote))}]";
+
+    // Single-line inline table, so the line-based helpers (DescribeEffective) still see one key = value
+    private static string FormatInlineTable(Dictionary<string, string> values) =>
+        values.Count == 0 ? "{}" : $"{{ {string.Join(", ", values.Select(kv => $"{Quote(kv.Key)} = {Quote(kv.Value)}"))} }}";
+
     private static string SerializeToToml(Config config)
     {
         return $"""
             # KMITL NetAuth Configuration
 
             [auth]
-            username = "{config.Username}"
-            ip_address = "{config.IpAddress ?? ""}"
-            portal_url = "{config.PortalUrl}"
-            heartbeat_url = "{config.HeartbeatUrl}"
-            internet_check_url = "{config.InternetCheckUrl}"
+            username = {Quote(config.Username)}
+            password_enc = {Quote(config.EncryptedPassword)}
+            ip_address = {Quote(config.IpAddress)}
+            mac_address = {Quote(config.MacAddress)}
+            use_credential_store = {config.UseCredentialStore.ToString().ToLowerInvariant()}
+            portal_url = {Quote(config.PortalUrl)}
+            heartbeat_url = {Quote(config.HeartbeatUrl)}
+            internet_check_url = {Quote(config.InternetCheckUrl)}
+            internet_check_fallback_urls = {FormatList(config.InternetCheckFallbackUrls)}
+            logout_url = {Quote(config.LogoutUrl)}
+            auth_type = {Quote(config.AuthType)}
+            acip = {Quote(config.Acip)}
+            learn_acip = {config.LearnAcip.ToString().ToLowerInvariant()}
+            login_method = {Quote(config.LoginMethod)}
+            login_success_pattern = {Quote(config.LoginSuccessPattern)}
+            extra_login_params = {FormatInlineTable(config.ExtraLoginParams)}
+            additional_macs = {FormatList(config.AdditionalMacs)}
 
             [network]
             timeout = {config.Timeout}
             accept_invalid_certs = {config.AcceptInvalidCerts.ToString().ToLowerInvariant()}
-            heartbeat_user_agent = "{config.HeartbeatUserAgent}"
+            min_tls_version = {Quote(config.MinTlsVersion)}
+            heartbeat_user_agent = {Quote(config.HeartbeatUserAgent)}
+            heartbeat_user_agents = {FormatList(config.HeartbeatUserAgents)}
+            interface = {Quote(config.Interface)}
+            connectivity_check = {Quote(config.ConnectivityCheck)}
+            circuit_breaker_threshold = {config.CircuitBreakerThreshold}
+            circuit_breaker_cooldown = {config.CircuitBreakerCooldown}
+            recheck_on_network_change = {config.RecheckOnNetworkChange.ToString().ToLowerInvariant()}
+            cache_dns = {config.CacheDns.ToString().ToLowerInvariant()}
+            bypass_probe_cache = {config.BypassProbeCache.ToString().ToLowerInvariant()}
+            watch_certificate = {config.WatchCertificate.ToString().ToLowerInvariant()}
 
             [service]
             interval = {config.Interval}
             max_attempt = {config.MaxAttempt}
             backoff_interval = {config.BackoffInterval}
+            heartbeat_failure_threshold = {config.HeartbeatFailureThreshold}
+            post_login_grace = {config.PostLoginGrace}
+            interval_warning_threshold = {config.IntervalWarningThreshold}
             auto_login = {config.AutoLogin.ToString().ToLowerInvariant()}
+            monitor_only = {config.MonitorOnly.ToString().ToLowerInvariant()}
+            logout_on_exit = {config.LogoutOnExit.ToString().ToLowerInvariant()}
+            login_on_start = {config.LoginOnStart.ToString().ToLowerInvariant()}
+            battery_interval_multiplier = {config.BatteryIntervalMultiplier}
+            battery_threshold_percent = {config.BatteryThresholdPercent}
+            status_file = {Quote(config.StatusFile)}
+            status_file_interval = {config.StatusFileInterval}
+            max_runtime = {config.MaxRuntime}
 
             [logging]
-            level = "{config.LogLevel}"
-            directory = "{config.LogDirectory ?? ""}"
+            level = {Quote(config.LogLevel)}
+            directory = {Quote(config.LogDirectory)}
             retention_days = {config.LogRetentionDays}
+            format = {Quote(config.LogFormat)}
+            error_file_level = {Quote(config.ErrorFileLevel)}
 
             [notifications]
             enabled = {config.NotificationsEnabled.ToString().ToLowerInvariant()}
+            webhook_url = {Quote(config.WebhookUrl)}
+            language = {Quote(config.Language)}
 
             [update]
             auto_check = {config.AutoUpdateCheck.ToString().ToLowerInvariant()}
@@ -259,6 +1314,21 @@ public sealed class Config
 
             [tray]
             start_minimized = {config.StartMinimized.ToString().ToLowerInvariant()}
+            first_run_wizard = {config.FirstRunWizard.ToString().ToLowerInvariant()}
+
+            [theme]
+            online = {Quote(config.ThemeOnline)}
+            offline = {Quote(config.ThemeOffline)}
+            connecting = {Quote(config.ThemeConnecting)}
+            paused = {Quote(config.ThemePaused)}
+            needs_attention = {Quote(config.ThemeNeedsAttention)}
+            log_warning = {Quote(config.ThemeLogWarning)}
+            log_error = {Quote(config.ThemeLogError)}
+
+            [api]
+            port = {config.ApiPort}
+            token = {Quote(config.ApiToken)}
+            bind = {Quote(config.ApiBind)}
             """;
     }
 
@@ -283,7 +1353,7 @@ public sealed class Config
                 switch (key)
                 {
                     case "username": config.Username = value; break;
-                    case "password": config.Password = value; break;
+                    case "password": config.Password = config._filePassword = value; break;
                     case "ip_address": config.IpAddress = NullIfEmpty(value); break;
                     case "interval" when ulong.TryParse(value, out var i): config.Interval = i; break;
                     case "max_attempt" when uint.TryParse(value, out var m): config.MaxAttempt = m; break;
@@ -300,6 +1370,73 @@ public sealed class Config
         }
     }
 
+    /// <summary>Every environment variable the config reads, in the order of the table in docs/INSTALL.md.</summary>
+    public static IReadOnlyList<EnvironmentVariable> EnvironmentVariables { get; } =
+    [
+        new("KMITL_USERNAME", "[auth] username"),
+        new("KMITL_PASSWORD", "password", Secret: true),
+        new("KMITL_IP", "[auth] ip_address"),
+        new("KMITL_MAC", "[auth] mac_address"),
+        new("KMITL_INTERFACE", "[network] interface"),
+        new("KMITL_INTERNET_CHECK_FALLBACK_URLS", "[auth] internet_check_fallback_urls"),
+        new("KMITL_AUTH_TYPE", "[auth] auth_type"),
+        new("KMITL_ACIP", "[auth] acip"),
+        new("KMITL_LEARN_ACIP", "[auth] learn_acip"),
+        new("KMITL_LOGIN_METHOD", "[auth] login_method"),
+        new("KMITL_LOGIN_SUCCESS_PATTERN", "[auth] login_success_pattern"),
+        new("KMITL_EXTRA_LOGIN_PARAMS", "[auth] extra_login_params"),
+        new("KMITL_ADDITIONAL_MACS", "[auth] additional_macs"),
+        new("KMITL_USE_CREDENTIAL_STORE", "[auth] use_credential_store"),
+        new("KMITL_PASSPHRASE", "unlocks [auth] password_enc", Secret: true),
+        new("KMITL_TIMEOUT", "[network] timeout"),
+        new("KMITL_CONNECTIVITY_CHECK", "[network] connectivity_check"),
+        new("KMITL_RECHECK_ON_NETWORK_CHANGE", "[network] recheck_on_network_change"),
+        new("KMITL_CACHE_DNS", "[network] cache_dns"),
+        new("KMITL_MIN_TLS_VERSION", "[network] min_tls_version"),
+        new("KMITL_HEARTBEAT_USER_AGENTS", "[network] heartbeat_user_agents"),
+        new("KMITL_BYPASS_PROBE_CACHE", "[network] bypass_probe_cache"),
+        new("KMITL_WATCH_CERTIFICATE", "[network] watch_certificate"),
+        new("KMITL_INTERVAL", "[service] interval"),
+        new("KMITL_MAX_ATTEMPT", "[service] max_attempt"),
+        new("KMITL_BACKOFF_INTERVAL", "[service] backoff_interval"),
+        new("KMITL_HEARTBEAT_FAILURE_THRESHOLD", "[service] heartbeat_failure_threshold"),
+        new("KMITL_POST_LOGIN_GRACE", "[service] post_login_grace"),
+        new("KMITL_INTERVAL_WARNING_THRESHOLD", "[service] interval_warning_threshold"),
+        new("KMITL_AUTO_LOGIN", "[service] auto_login"),
+        new("KMITL_MONITOR_ONLY", "[service] monitor_only"),
+        new("KMITL_LOGOUT_ON_EXIT", "[service] logout_on_exit"),
+        new("KMITL_LOGIN_ON_START", "[service] login_on_start"),
+        new("KMITL_STATUS_FILE", "[service] status_file"),
+        new("KMITL_MAX_RUNTIME", "[service] max_runtime"),
+        new("KMITL_LOG_LEVEL", "[logging] level"),
+        new("KMITL_LOG_FORMAT", "[logging] format"),
+        new("KMITL_LOG_ERROR_FILE_LEVEL", "[logging] error_file_level"),
+        new("KMITL_NOTIFICATIONS", "[notifications] enabled"),
+        new("KMITL_WEBHOOK_URL", "[notifications] webhook_url"),
+        new("KMITL_LANGUAGE", "[notifications] language"),
+        new("KMITL_THEME_ONLINE", "[theme] online"),
+        new("KMITL_THEME_OFFLINE", "[theme] offline"),
+        new("KMITL_THEME_CONNECTING", "[theme] connecting"),
+        new("KMITL_THEME_PAUSED", "[theme] paused"),
+        new("KMITL_THEME_NEEDS_ATTENTION", "[theme] needs_attention"),
+        new("KMITL_THEME_LOG_WARNING", "[theme] log_warning"),
+        new("KMITL_THEME_LOG_ERROR", "[theme] log_error"),
+        new("KMITL_API_PORT", "[api] port"),
+        new("KMITL_API_TOKEN", "[api] token", Secret: true),
+        new("KMITL_API_BIND", "[api] bind"),
+        new("KMITL_CONFIG", "config file path, below --config"),
+        new("KMITL_MOCK_PORTAL", "development: mock portal base URL, overrides every endpoint"),
+    ];
+
+    /// <summary>
+    /// Each of <see cref="EnvironmentVariables"/> with its current value, null when unset. Secrets that are
+    /// set show as <c>&lt;redacted&gt;</c>.
+    /// </summary>
+    public static IEnumerable<(EnvironmentVariable Variable, string? Value)> DescribeEnvironment() =>
+        EnvironmentVariables.Select(variable => Environment.GetEnvironmentVariable(variable.Name) is { } value
+            ? (variable, variable.Secret ? "<redacted>" : value)
+            : (variable, (string?)null));
+
     private static void ApplyEnvironmentOverrides(Config config)
     {
         var val = Environment.GetEnvironmentVariable("KMITL_USERNAME");
@@ -311,39 +1448,199 @@ public sealed class Config
         val = Environment.GetEnvironmentVariable("KMITL_IP");
         if (val != null) config.IpAddress = NullIfEmpty(val);
 
+        val = Environment.GetEnvironmentVariable("KMITL_MAC");
+        if (val != null) config.MacAddress = NullIfEmpty(val);
+
+        val = Environment.GetEnvironmentVariable("KMITL_INTERFACE");
+        if (val != null) config.Interface = NullIfEmpty(val);
+
+        val = Environment.GetEnvironmentVariable("KMITL_INTERNET_CHECK_FALLBACK_URLS");
+        if (val != null)
+            config.InternetCheckFallbackUrls = val.Split(',', StringSplitOptions.RemoveEmptyEntries | StringSplitOptions.TrimEntries).ToList();
+
+        val = Environment.GetEnvironmentVariable("KMITL_AUTH_TYPE");
+        if (val != null) config.AuthType = val;
+
+        val = Environment.GetEnvironmentVariable("KMITL_ACIP");
+        if (val != null) config.Acip = val;
+
+        val = Environment.GetEnvironmentVariable("KMITL_LEARN_ACIP");
+        if (val != null && bool.TryParse(val, out var learnAcip))
+            config.LearnAcip = learnAcip;
+
+        val = Environment.GetEnvironmentVariable("KMITL_LOGIN_METHOD");
+        if (val != null) config.LoginMethod = val;
+
+        val = Environment.GetEnvironmentVariable("KMITL_LOGIN_SUCCESS_PATTERN");
+        if (val != null) config.LoginSuccessPattern = NullIfEmpty(val);
+
+        val = Environment.GetEnvironmentVariable("KMITL_EXTRA_LOGIN_PARAMS");
+        if (val != null)
+            config.ExtraLoginParams = val.Split(',', StringSplitOptions.RemoveEmptyEntries | StringSplitOptions.TrimEntries)
+                .Select(pair => pair.Split('=', 2))
+                .Where(parts => parts.Length == 2)
+                .ToDictionary(parts => parts[0].Trim(), parts => parts[1].Trim());
+
+        val = Environment.GetEnvironmentVariable("KMITL_ADDITIONAL_MACS");
+        if (val != null)
+            config.AdditionalMacs = val.Split(',', StringSplitOptions.RemoveEmptyEntries | StringSplitOptions.TrimEntries).ToList();
+
+        val = Environment.GetEnvironmentVariable("KMITL_CONNECTIVITY_CHECK");
+        if (val != null) config.ConnectivityCheck = val;
+
+        val = Environment.GetEnvironmentVariable("KMITL_RECHECK_ON_NETWORK_CHANGE");
+        if (val != null && bool.TryParse(val, out var recheckOnNetworkChange))
+            config.RecheckOnNetworkChange = recheckOnNetworkChange;
+
+        val = Environment.GetEnvironmentVariable("KMITL_CACHE_DNS");
+        if (val != null && bool.TryParse(val, out var cacheDns))
+            config.CacheDns = cacheDns;
+
+        val = Environment.GetEnvironmentVariable("KMITL_MIN_TLS_VERSION");
+        if (val != null) config.MinTlsVersion = val;
+
+        // '|'-separated, since browser-style strings contain commas
+        val = Environment.GetEnvironmentVariable("KMITL_HEARTBEAT_USER_AGENTS");
+        if (val != null)
+            config.HeartbeatUserAgents = val.Split('|', StringSplitOptions.RemoveEmptyEntries | StringSplitOptions.TrimEntries).ToList();
+
+        val = Environment.GetEnvironmentVariable("KMITL_BYPASS_PROBE_CACHE");
+        if (val != null && bool.TryParse(val, out var bypassProbeCache))
+            config.BypassProbeCache = bypassProbeCache;
+
+        val = Environment.GetEnvironmentVariable("KMITL_WATCH_CERTIFICATE");
+        if (val != null && bool.TryParse(val, out var watchCertificate))
+            config.WatchCertificate = watchCertificate;
+
+        val = Environment.GetEnvironmentVariable("KMITL_USE_CREDENTIAL_STORE");
+        if (val != null && bool.TryParse(val, out var useCredentialStore))
+            config.UseCredentialStore = useCredentialStore;
+
         val = Environment.GetEnvironmentVariable("KMITL_INTERVAL");
-        if (val != null && ulong.TryParse(val, out var interval))
-            config.Interval = interval;
+        if (val != null && DurationParser.TryParseSeconds(val, out var interval))
+            config.Interval = (ulong)interval;
 
         val = Environment.GetEnvironmentVariable("KMITL_MAX_ATTEMPT");
         if (val != null && uint.TryParse(val, out var maxAttempt))
             config.MaxAttempt = maxAttempt;
 
+        val = Environment.GetEnvironmentVariable("KMITL_HEARTBEAT_FAILURE_THRESHOLD");
+        if (val != null && int.TryParse(val, out var heartbeatFailureThreshold))
+            config.HeartbeatFailureThreshold = heartbeatFailureThreshold;
+
         val = Environment.GetEnvironmentVariable("KMITL_AUTO_LOGIN");
         if (val != null && bool.TryParse(val, out var autoLogin))
             config.AutoLogin = autoLogin;
 
+        val = Environment.GetEnvironmentVariable("KMITL_MONITOR_ONLY");
+        if (val != null && bool.TryParse(val, out var monitorOnly))
+            config.MonitorOnly = monitorOnly;
+
+        val = Environment.GetEnvironmentVariable("KMITL_LOGOUT_ON_EXIT");
+        if (val != null && bool.TryParse(val, out var logoutOnExit))
+            config.LogoutOnExit = logoutOnExit;
+
+        val = Environment.GetEnvironmentVariable("KMITL_LOGIN_ON_START");
+        if (val != null && bool.TryParse(val, out var loginOnStart))
+            config.LoginOnStart = loginOnStart;
+
+        val = Environment.GetEnvironmentVariable("KMITL_STATUS_FILE");
+        if (val != null) config.StatusFile = NullIfEmpty(val);
+
+        val = Environment.GetEnvironmentVariable("KMITL_MAX_RUNTIME");
+        if (val != null && DurationParser.TryParseSeconds(val, out var maxRuntime))
+            config.MaxRuntime = maxRuntime;
+
         val = Environment.GetEnvironmentVariable("KMITL_LOG_LEVEL");
         if (val != null) config.LogLevel = val;
 
+        val = Environment.GetEnvironmentVariable("KMITL_LOG_FORMAT");
+        if (val != null) config.LogFormat = val;
+
+        val = Environment.GetEnvironmentVariable("KMITL_LOG_ERROR_FILE_LEVEL");
+        if (val != null) config.ErrorFileLevel = NullIfEmpty(val);
+
         val = Environment.GetEnvironmentVariable("KMITL_TIMEOUT");
-        if (val != null && int.TryParse(val, out var timeout))
+        if (val != null && DurationParser.TryParseSeconds(val, out var timeout))
             config.Timeout = timeout;
 
         val = Environment.GetEnvironmentVariable("KMITL_BACKOFF_INTERVAL");
-        if (val != null && int.TryParse(val, out var backoff))
+        if (val != null && DurationParser.TryParseSeconds(val, out var backoff))
             config.BackoffInterval = backoff;
 
+        val = Environment.GetEnvironmentVariable("KMITL_POST_LOGIN_GRACE");
+        if (val != null && DurationParser.TryParseSeconds(val, out var postLoginGrace))
+            config.PostLoginGrace = postLoginGrace;
+
+        val = Environment.GetEnvironmentVariable("KMITL_INTERVAL_WARNING_THRESHOLD");
+        if (val != null && DurationParser.TryParseSeconds(val, out var intervalWarning))
+            config.IntervalWarningThreshold = intervalWarning;
+
+        // Local development: send everything to a mock portal, overriding the individual URLs
+        val = Environment.GetEnvironmentVariable("KMITL_MOCK_PORTAL");
+        if (!string.IsNullOrEmpty(val)) UseMockPortal(config, val);
+
         val = Environment.GetEnvironmentVariable("KMITL_NOTIFICATIONS");
         if (val != null && bool.TryParse(val, out var notif))
             config.NotificationsEnabled = notif;
+
+        val = Environment.GetEnvironmentVariable("KMITL_WEBHOOK_URL");
+        if (val != null) config.WebhookUrl = NullIfEmpty(val);
+
+        val = Environment.GetEnvironmentVariable("KMITL_LANGUAGE");
+        if (val != null) config.Language = val;
+
+        val = Environment.GetEnvironmentVariable("KMITL_THEME_ONLINE");
+        if (val != null) config.ThemeOnline = val;
+
+        val = Environment.GetEnvironmentVariable("KMITL_THEME_OFFLINE");
+        if (val != null) config.ThemeOffline = val;
+
+        val = Environment.GetEnvironmentVariable("KMITL_THEME_CONNECTING");
+        if (val != null) config.ThemeConnecting = val;
+
+        val = Environment.GetEnvironmentVariable("KMITL_THEME_PAUSED");
+        if (val != null) config.ThemePaused = val;
+
+        val = Environment.GetEnvironmentVariable("KMITL_THEME_NEEDS_ATTENTION");
+        if (val != null) config.ThemeNeedsAttention = val;
+
+        val = Environment.GetEnvironmentVariable("KMITL_THEME_LOG_WARNING");
+        if (val != null) config.ThemeLogWarning = NullIfEmpty(val);
+
+        val = Environment.GetEnvironmentVariable("KMITL_THEME_LOG_ERROR");
+        if (val != null) config.ThemeLogError = NullIfEmpty(val);
+
+        val = Environment.GetEnvironmentVariable("KMITL_API_PORT");
+        if (val != null && int.TryParse(val, out var apiPort))
+            config.ApiPort = apiPort;
+
+        val = Environment.GetEnvironmentVariable("KMITL_API_TOKEN");
+        if (val != null) config.ApiToken = NullIfEmpty(val);
+
+        val = Environment.GetEnvironmentVariable("KMITL_API_BIND");
+        if (val != null) config.ApiBind = val;
     }
 
     private static void MigrateCredentials(Config config, ICredentialStore? credentialStore, ILogger? logger)
     {
-        if (string.IsNullOrEmpty(config.Password) || string.IsNullOrEmpty(config.Username) || credentialStore == null)
+        if (string.IsNullOrEmpty(config.Password) || string.IsNullOrEmpty(config.Username) || credentialStore == null
+            || !config.UseCredentialStore)
+            return;
+
+        // KMITL_PASSWORD is supplied on every start (containers, CI); copying it into the store on the side
+        // would leave a secret behind that the environment never asked to persist
+        if (config.Password != config._filePassword)
             return;
 
+        // Don't let a stale file password overwrite the store on every load; GetPassword prefers the store too
+        if (config.GetStoredPassword(credentialStore) is { } stored && stored != config.Password)
+        {
+            logger?.LogWarning("Not migrating the config file password for '{Username}': the credential store already " +
+                "holds a different one.", config.Username);
+            return;
+        }
+
         try
         {
             credentialStore.SetPasswordAsync(config.Username, config.Password).GetAwaiter().GetResult();
@@ -357,3 +1654,6 @@ public sealed class Config
     private static string? NullIfEmpty(string? value) =>
         string.IsNullOrWhiteSpace(value) ? null : value;
 }
+
+/// <summary>A <c>KMITL_*</c> variable and what it overrides; <paramref name="Secret"/> values are never printed.</summary>
+public sealed record EnvironmentVariable(string Name, string Overrides, bool Secret = false);
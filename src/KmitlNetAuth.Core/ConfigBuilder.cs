@@ -0,0 +1,133 @@
+using KmitlNetAuth.Core.Exceptions;
+
+namespace KmitlNetAuth.Core;
+
+/// <summary>
+/// Fluent construction of a <see cref="Config"/> for embedding the library. Anything not set keeps the
+/// same default as a missing key in config.toml, and <see cref="Build"/> runs <see cref="Config.Validate"/>.
+/// </summary>
+public sealed class ConfigBuilder
+{
+    private readonly Config _config;
+
+    public ConfigBuilder()
+        : this(new Config())
+    {
+    }
+
+    /// <summary>Starts from a copy of <paramref name="baseConfig"/>, e.g. one loaded from disk.</summary>
+    public ConfigBuilder(Config baseConfig)
+    {
+        _config = baseConfig.Clone();
+        _config.Passphrase = baseConfig.Passphrase;
+    }
+
+    public ConfigBuilder WithCredentials(string username, string? password)
+    {
+        _config.Username = username;
+        _config.Password = password;
+        return this;
+    }
+
+    public ConfigBuilder WithIpAddress(string? ipAddress)
+    {
+        _config.IpAddress = ipAddress;
+        return this;
+    }
+
+    public ConfigBuilder WithMacAddress(string? macAddress)
+    {
+        _config.MacAddress = macAddress;
+        return this;
+    }
+
+    public ConfigBuilder WithInterface(string? interfaceName)
+    {
+        _config.Interface = interfaceName;
+        return this;
+    }
+
+    /// <summary>Points login, heartbeat, internet check and logout at other endpoints; null keeps the default.</summary>
+    public ConfigBuilder WithEndpoints(string? portalUrl = null, string? heartbeatUrl = null,
+        string? internetCheckUrl = null, string? logoutUrl = null)
+    {
+        _config.PortalUrl = portalUrl ?? _config.PortalUrl;
+        _config.HeartbeatUrl = heartbeatUrl ?? _config.HeartbeatUrl;
+        _config.InternetCheckUrl = internetCheckUrl ?? _config.InternetCheckUrl;
+        _config.LogoutUrl = logoutUrl ?? _config.LogoutUrl;
+        return this;
+    }
+
+    public ConfigBuilder WithInterval(TimeSpan interval)
+    {
+        _config.Interval = (ulong)interval.TotalSeconds;
+        return this;
+    }
+
+    public ConfigBuilder WithTimeout(TimeSpan timeout)
+    {
+        _config.Timeout = (int)timeout.TotalSeconds;
+        return this;
+    }
+
+    public ConfigBuilder WithRetries(uint maxAttempt, TimeSpan backoffInterval)
+    {
+        _config.MaxAttempt = maxAttempt;
+        _config.BackoffInterval = (int)backoffInterval.TotalSeconds;
+        return this;
+    }
+
+    public ConfigBuilder WithConnectivityCheck(string mode)
+    {
+        _config.ConnectivityCheck = mode;
+        return this;
+    }
+
+    /// <summary>Never touch the OS credential store; the password must come from here, env or password_enc.</summary>
+    public ConfigBuilder WithoutCredentialStore()
+    {
+        _config.UseCredentialStore = false;
+        return this;
+    }
+
+    public ConfigBuilder MonitorOnly(bool monitorOnly = true)
+    {
+        _config.MonitorOnly = monitorOnly;
+        return this;
+    }
+
+    public ConfigBuilder WithNotifications(bool enabled, string? webhookUrl = null)
+    {
+        _config.NotificationsEnabled = enabled;
+        _config.WebhookUrl = webhookUrl;
+        return this;
+    }
+
+    /// <summary>Escape hatch for settings without a dedicated method.</summary>
+    public ConfigBuilder Configure(Action<Config> configure)
+    {
+        configure(_config);
+        return this;
+    }
+
+    /// <summary>Returns a validated copy of the config; the builder can keep being used afterwards.</summary>
+    /// <exception cref="ConfigException">One or more settings are invalid; the message lists them all.</exception>
+    public Config Build()
+    {
+        if (!TryBuild(out var config, out var errors))
+            throw new ConfigException($"Invalid config: {string.Join("; ", errors)}");
+
+        return config;
+    }
+
+    public bool TryBuild(
+        [System.Diagnostics.CodeAnalysis.NotNullWhen(true)] out Config? config,
+        out IReadOnlyList<string> errors)
+    {
+        var candidate = _config.Clone();
+        candidate.Passphrase = _config.Passphrase;
+        errors = candidate.Validate();
+        config = errors.Count == 0 ? candidate : null;
+        return config != null;
+    }
+}
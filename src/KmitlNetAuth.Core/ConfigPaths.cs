@@ -1,68 +1,140 @@
+using KmitlNetAuth.Core.Exceptions;
+
 namespace KmitlNetAuth.Core;
 
+/// <summary>
+/// Single source of truth for on-disk locations, shared by the CLI and the tray app.
+/// Never falls back to the current working directory: when no base directory can be
+/// determined (e.g. no HOME in a bare container) a <see cref="ConfigException"/> is thrown.
+/// </summary>
 public static class ConfigPaths
 {
     private const string AppName = "kmitlnetauth";
     private const string ConfigFileName = "config.toml";
 
+    /// <summary>
+    /// Resolves the config file path (the file that gets written on save). Order:
+    /// <list type="number">
+    /// <item>explicit path (<c>--config</c>)</item>
+    /// <item><c>KMITL_CONFIG</c> environment variable</item>
+    /// <item>per-user config (<c>$XDG_CONFIG_HOME</c>, <c>~/.config</c>, or <c>%APPDATA%</c>) if it exists</item>
+    /// <item>Linux: <c>/etc/kmitlnetauth/config.toml</c> if it exists</item>
+    /// <item>the per-user path, or <see cref="ConfigException"/> if it cannot be determined</item>
+    /// </list>
+    /// When the per-user file is used, the system file is still read underneath it; see <see cref="GetLayers"/>.
+    /// </summary>
     public static string Resolve(string? explicitPath = null)
     {
         if (!string.IsNullOrEmpty(explicitPath))
             return explicitPath;
 
-        if (OperatingSystem.IsLinux())
-        {
-            var globalPath = $"/etc/{AppName}/{ConfigFileName}";
-            if (File.Exists(globalPath))
-                return globalPath;
+        var envPath = Environment.GetEnvironmentVariable("KMITL_CONFIG");
+        if (!string.IsNullOrEmpty(envPath))
+            return envPath;
 
-            var xdgConfig = Environment.GetEnvironmentVariable("XDG_CONFIG_HOME")
-                ?? Path.Combine(Environment.GetFolderPath(Environment.SpecialFolder.UserProfile), ".config");
-            return Path.Combine(xdgConfig, AppName, ConfigFileName);
-        }
+        var systemPath = GetSystemConfigPath();
+        var hasSystem = systemPath != null && File.Exists(systemPath);
 
-        if (OperatingSystem.IsWindows())
+        string userPath;
+        try
         {
-            var appData = Environment.GetFolderPath(Environment.SpecialFolder.ApplicationData);
-            return Path.Combine(appData, AppName, ConfigFileName);
+            userPath = GetUserConfigPath();
         }
+        catch (ConfigException) when (hasSystem)
+        {
+            return systemPath!;
+        }
+
+        if (File.Exists(userPath) || !hasSystem)
+            return userPath;
 
-        // macOS / fallback
-        var home = Environment.GetFolderPath(Environment.SpecialFolder.UserProfile);
-        return Path.Combine(home, ".config", AppName, ConfigFileName);
+        return systemPath!;
     }
 
-    public static string GetLogDirectory()
+    /// <summary>
+    /// Config files to read for <paramref name="path"/>, lowest precedence first. The default
+    /// per-user file is layered over the system file; any other path is read on its own.
+    /// </summary>
+    public static IReadOnlyList<string> GetLayers(string path)
     {
-        if (OperatingSystem.IsLinux())
+        var systemPath = GetSystemConfigPath();
+        if (systemPath == null || !File.Exists(systemPath))
+            return [path];
+
+        try
         {
-            var xdgData = Environment.GetEnvironmentVariable("XDG_DATA_HOME")
-                ?? Path.Combine(Environment.GetFolderPath(Environment.SpecialFolder.UserProfile), ".local", "share");
-            return Path.Combine(xdgData, AppName, "logs");
+            return PathsEqual(path, GetUserConfigPath()) ? [systemPath, path] : [path];
         }
-
-        if (OperatingSystem.IsWindows())
+        catch (ConfigException)
         {
-            var localAppData = Environment.GetFolderPath(Environment.SpecialFolder.LocalApplicationData);
-            return Path.Combine(localAppData, AppName, "logs");
+            return [path];
         }
+    }
+
+    /// <summary>
+    /// True when no config file (nor legacy YAML) exists for <paramref name="path"/>, so the config is the
+    /// defaults plus <c>KMITL_*</c> environment variables, as in a container with no mounted file.
+    /// </summary>
+    public static bool IsEnvironmentOnly(string path) =>
+        !GetLayers(path).Any(layer => File.Exists(layer) || File.Exists(Path.ChangeExtension(layer, ".yaml")));
+
+    public static string? GetSystemConfigPath() =>
+        OperatingSystem.IsLinux() ? $"/etc/{AppName}/{ConfigFileName}" : null;
+
+    public static string GetUserConfigPath() =>
+        Path.Combine(GetUserConfigDirectory(), AppName, ConfigFileName);
+
+    private static bool PathsEqual(string a, string b) =>
+        string.Equals(Path.GetFullPath(a), Path.GetFullPath(b),
+            OperatingSystem.IsWindows() ? StringComparison.OrdinalIgnoreCase : StringComparison.Ordinal);
+
+    public static string GetLogDirectory() => Path.Combine(GetDataDirectory(), "logs");
+
+    /// <summary>Default location of the JSON status file, see <see cref="Config.StatusFile"/>.</summary>
+    public static string GetStatusFilePath() => Path.Combine(GetDataDirectory(), "status.json");
 
-        // macOS / fallback
-        var home = Environment.GetFolderPath(Environment.SpecialFolder.UserProfile);
-        return Path.Combine(home, ".local", "share", AppName, "logs");
+    /// <summary>Portal certificate fingerprints remembered by <see cref="PortalCertificateWatcher"/>.</summary>
+    public static string GetCertificateStatePath() => Path.Combine(GetDataDirectory(), "certificates.json");
+
+    private static string GetDataDirectory()
+    {
+        if (OperatingSystem.IsWindows())
+            return Path.Combine(GetFolder(Environment.SpecialFolder.LocalApplicationData), AppName);
+
+        // Linux / macOS
+        var xdgData = GetXdgDirectory("XDG_DATA_HOME")
+            ?? Path.Combine(GetFolder(Environment.SpecialFolder.UserProfile), ".local", "share");
+        return Path.Combine(xdgData, AppName);
     }
 
     public static string GetCredentialPath()
     {
         if (OperatingSystem.IsWindows())
-        {
-            var appData = Environment.GetFolderPath(Environment.SpecialFolder.ApplicationData);
-            return Path.Combine(appData, AppName, "credentials.dat");
-        }
+            return Path.Combine(GetUserConfigDirectory(), AppName, "credentials.dat");
 
         // Linux / macOS
-        var xdgConfig = Environment.GetEnvironmentVariable("XDG_CONFIG_HOME")
-            ?? Path.Combine(Environment.GetFolderPath(Environment.SpecialFolder.UserProfile), ".config");
-        return Path.Combine(xdgConfig, AppName, ".credentials");
+        return Path.Combine(GetUserConfigDirectory(), AppName, ".credentials");
+    }
+
+    private static string GetUserConfigDirectory()
+    {
+        if (OperatingSystem.IsWindows())
+            return GetFolder(Environment.SpecialFolder.ApplicationData);
+
+        return GetXdgDirectory("XDG_CONFIG_HOME")
+            ?? Path.Combine(GetFolder(Environment.SpecialFolder.UserProfile), ".config");
+    }
+
+    // The XDG spec says relative values must be ignored.
+    private static string? GetXdgDirectory(string variable) =>
+        Environment.GetEnvironmentVariable(variable) is { Length: > 0 } dir && Path.IsPathRooted(dir) ? dir : null;
+
+    private static string GetFolder(Environment.SpecialFolder folder)
+    {
+        var path = Environment.GetFolderPath(folder);
+        if (string.IsNullOrEmpty(path))
+            throw new ConfigException(
+                $"Cannot determine the {folder} directory (is HOME set?). Pass --config with an explicit path.");
+        return path;
     }
 }
@@ -0,0 +1,33 @@
+namespace KmitlNetAuth.Core;
+
+/// <summary>Where <see cref="Config.GetPassword"/> found the password, in the order it looks.</summary>
+public enum CredentialSource
+{
+    /// <summary>No source had a password.</summary>
+    None,
+
+    /// <summary>The <c>KMITL_PASSWORD</c> environment variable.</summary>
+    Environment,
+
+    /// <summary>A plain <c>password</c> in the config file, or one set in code or by the setup wizard.</summary>
+    Config,
+
+    /// <summary>The OS credential store (DPAPI on Windows, the encrypted credential file on Linux).</summary>
+    CredentialStore,
+
+    /// <summary><c>password_enc</c> in the config file, decrypted with the passphrase.</summary>
+    EncryptedConfig,
+}
+
+public static class CredentialSourceExtensions
+{
+    /// <summary>A short label for status and diagnostic output.</summary>
+    public static string Describe(this CredentialSource source) => source switch
+    {
+        CredentialSource.Environment => "environment (KMITL_PASSWORD)",
+        CredentialSource.Config => "config file (password)",
+        CredentialSource.CredentialStore => "credential store",
+        CredentialSource.EncryptedConfig => "config file (password_enc)",
+        _ => "none",
+    };
+}
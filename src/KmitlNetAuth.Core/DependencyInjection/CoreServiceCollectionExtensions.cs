@@ -1,14 +1,25 @@
 using System.Net;
+using System.Net.Security;
 using KmitlNetAuth.Core.Platform;
 using KmitlNetAuth.Core.Platform.Linux;
 using KmitlNetAuth.Core.Services;
 using Microsoft.Extensions.DependencyInjection;
+using Microsoft.Extensions.DependencyInjection.Extensions;
+using Microsoft.Extensions.Logging;
 
 namespace KmitlNetAuth.Core.DependencyInjection;
 
 public static class CoreServiceCollectionExtensions
 {
     public const string HttpClientName = "KmitlAuth";
+    public const string WebhookHttpClientName = "KmitlWebhook";
+
+    /// <summary>
+    /// Service key of the desktop notifier. Core never links a desktop toolkit; it only falls back to
+    /// <c>notify-send</c> on Linux and logging on Windows. A host with its own notifier (a tray icon,
+    /// a test double) registers it under this key before calling <see cref="AddKmitlNetAuth"/>.
+    /// </summary>
+    public const string DesktopNotificationKey = "desktop";
 
     public static IServiceCollection AddKmitlNetAuth(this IServiceCollection services, Config config)
     {
@@ -18,13 +29,42 @@ public static class CoreServiceCollectionExtensions
         {
             client.Timeout = TimeSpan.FromSeconds(config.Timeout);
         })
-        .ConfigurePrimaryHttpMessageHandler(() => new HttpClientHandler
+        .ConfigurePrimaryHttpMessageHandler(sp =>
+        {
+            var handler = new SocketsHttpHandler
+            {
+                CookieContainer = new CookieContainer(),
+                UseCookies = true,
+            };
+            handler.SslOptions.EnabledSslProtocols = config.EnabledSslProtocols;
+            if (config.AcceptInvalidCerts)
+                handler.SslOptions.RemoteCertificateValidationCallback = (_, _, _, _) => true;
+
+            // Only the TCP connect goes to the cached address; TLS still uses the hostname for SNI
+            if (config.CacheDns)
+                handler.ConnectCallback = sp.GetRequiredService<PortalDnsCache>().ConnectAsync;
+
+            // Runs once per new connection, after the handshake, so the negotiated version and the
+            // certificate are known whether or not it was verified
+            var tlsLogger = sp.GetService<ILoggerFactory>()?.CreateLogger("KmitlNetAuth.Core.Tls");
+            var certificateWatcher = sp.GetRequiredService<PortalCertificateWatcher>();
+            handler.PlaintextStreamFilter = (context, _) =>
+            {
+                if (context.PlaintextStream is SslStream ssl)
+                {
+                    tlsLogger?.LogDebug("TLS connection to {Host}: {Protocol}, {Cipher}",
+                        ssl.TargetHostName, ssl.SslProtocol, ssl.NegotiatedCipherSuite);
+                    certificateWatcher.Observe(ssl.TargetHostName, ssl.RemoteCertificate);
+                }
+                return ValueTask.FromResult(context.PlaintextStream);
+            };
+
+            return handler;
+        });
+
+        services.AddHttpClient(WebhookHttpClientName, client =>
         {
-            CookieContainer = new CookieContainer(),
-            UseCookies = true,
-            ServerCertificateCustomValidationCallback = config.AcceptInvalidCerts
-                ? HttpClientHandler.DangerousAcceptAnyServerCertificateValidator
-                : null,
+            client.Timeout = TimeSpan.FromSeconds(10);
         });
 
         // Platform-specific services
@@ -35,11 +75,14 @@ public static class CoreServiceCollectionExtensions
         else
         {
             services.AddSingleton<ICredentialStore, FileCredentialStore>();
-            services.AddSingleton<INotificationService, LinuxNotificationService>();
+            services.TryAddKeyedSingleton<INotificationService, LinuxNotificationService>(DesktopNotificationKey);
             services.AddSingleton<IAutoStartManager, LinuxAutoStartManager>();
+            services.AddSingleton<IPowerStatus, SysfsPowerStatus>();
         }
 
         services.AddSingleton<INetworkInfo, NetworkInfo>();
+        services.AddSingleton<INetworkChangeMonitor, NetworkChangeMonitor>();
+        services.AddSingleton<INotificationService>(CreateNotificationService);
 
         // Auth client - resolve HttpClient from named factory
         services.AddSingleton(sp =>
@@ -52,20 +95,50 @@ public static class CoreServiceCollectionExtensions
                 sp.GetRequiredService<INetworkInfo>(),
                 sp.GetService<ICredentialStore>(),
                 sp.GetRequiredService<INotificationService>(),
-                sp.GetRequiredService<Microsoft.Extensions.Logging.ILogger<AuthClient>>());
+                sp.GetRequiredService<Microsoft.Extensions.Logging.ILogger<AuthClient>>(),
+                new PortalDiagnostics());
         });
 
+        services.AddSingleton<PortalDnsCache>();
+        services.AddSingleton<PortalCertificateWatcher>();
+        services.AddSingleton<StatusFileWriter>();
+        services.AddSingleton<ControlApiServer>();
         services.AddSingleton<IAuthService, AuthService>();
 
         return services;
     }
 
+    // Desktop notifications fail silently on headless servers, so the webhook can be used
+    // alongside or instead of them. Status changes reach the tray icon through the auth service's
+    // StatusChanged event either way; [notifications] enabled only decides whether popups are shown.
+    private static INotificationService CreateNotificationService(IServiceProvider sp)
+    {
+        var config = sp.GetRequiredService<Config>();
+        var backends = new List<INotificationService>
+        {
+            new SwitchableNotificationService(
+                sp.GetRequiredKeyedService<INotificationService>(DesktopNotificationKey),
+                () => config.NotificationsEnabled),
+        };
+
+        if (config.WebhookUrl != null)
+        {
+            backends.Add(new WebhookNotificationService(
+                sp.GetRequiredService<IHttpClientFactory>().CreateClient(WebhookHttpClientName),
+                config.WebhookUrl,
+                sp.GetRequiredService<Microsoft.Extensions.Logging.ILogger<WebhookNotificationService>>()));
+        }
+
+        return backends.Count == 1 ? backends[0] : new CompositeNotificationService(backends);
+    }
+
     // Separate method to avoid loading Windows-specific types on Linux
     [System.Runtime.Versioning.SupportedOSPlatform("windows")]
     private static void RegisterWindowsServices(IServiceCollection services)
     {
         services.AddSingleton<ICredentialStore, Platform.Windows.DpapiCredentialStore>();
-        services.AddSingleton<INotificationService, Platform.Windows.WindowsNotificationService>();
+        services.TryAddKeyedSingleton<INotificationService, Platform.Windows.WindowsNotificationService>(DesktopNotificationKey);
         services.AddSingleton<IAutoStartManager, Platform.Windows.WindowsAutoStartManager>();
+        services.AddSingleton<IPowerStatus, Platform.Windows.WindowsPowerStatus>();
     }
 }
@@ -0,0 +1,51 @@
+using System.Globalization;
+using System.Text.RegularExpressions;
+
+namespace KmitlNetAuth.Core;
+
+/// <summary>
+/// Parses config durations: a bare number of seconds (<c>300</c>) or a human-readable
+/// string such as <c>"30s"</c>, <c>"5m"</c>, <c>"1h"</c> or <c>"1h30m"</c>.
+/// </summary>
+public static partial class DurationParser
+{
+    [GeneratedRegex(@"\G\s*(?<value>\d+)\s*(?<unit>d|h|m|s)", RegexOptions.IgnoreCase)]
+    private static partial Regex PartRegex();
+
+    public static bool TryParseSeconds(string? text, out int seconds)
+    {
+        seconds = 0;
+        if (string.IsNullOrWhiteSpace(text))
+            return false;
+
+        text = text.Trim();
+        if (int.TryParse(text, NumberStyles.None, CultureInfo.InvariantCulture, out seconds))
+            return true;
+
+        long total = 0;
+        var position = 0;
+        foreach (Match part in PartRegex().Matches(text))
+        {
+            if (!long.TryParse(part.Groups["value"].Value, NumberStyles.None, CultureInfo.InvariantCulture, out var value) || value > int.MaxValue)
+                return false;
+
+            total += value * char.ToLowerInvariant(part.Groups["unit"].Value[0]) switch
+            {
+                'd' => 86400,
+                'h' => 3600,
+                'm' => 60,
+                _ => 1,
+            };
+            position = part.Index + part.Length;
+
+            if (total > int.MaxValue)
+                return false;
+        }
+
+        if (position == 0 || position != text.Length)
+            return false;
+
+        seconds = (int)total;
+        return true;
+    }
+}
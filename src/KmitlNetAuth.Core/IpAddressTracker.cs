@@ -0,0 +1,30 @@
+using System.Net;
+
+namespace KmitlNetAuth.Core;
+
+/// <summary>
+/// Remembers the local IPv4 address between cycles and reports when it changes, e.g. after a DHCP lease
+/// renewal hands out a new one. The portal ties the session to the address, so the old session stops
+/// working. A cycle without an address (the interface is mid-renewal) neither reports a change nor
+/// forgets the last address, so a brief gap followed by the same address isn't a change.
+/// </summary>
+public sealed class IpAddressTracker
+{
+    public IPAddress? Current { get; private set; }
+
+    /// <summary>
+    /// Records <paramref name="address"/>. Returns true when it differs from a previously seen address,
+    /// with that address in <paramref name="previous"/>; the first address seen is not a change.
+    /// </summary>
+    public bool Update(IPAddress? address, out IPAddress? previous)
+    {
+        previous = Current;
+        var changed = IsChange(address);
+        if (address != null)
+            Current = address;
+        return changed;
+    }
+
+    /// <summary>Whether <see cref="Update"/> would report <paramref name="address"/> as a change, without recording it.</summary>
+    public bool IsChange(IPAddress? address) => address != null && Current != null && !Current.Equals(address);
+}
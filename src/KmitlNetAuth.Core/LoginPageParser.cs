@@ -0,0 +1,75 @@
+using System.Net;
+using System.Net.Sockets;
+using System.Text.RegularExpressions;
+
+namespace KmitlNetAuth.Core;
+
+/// <summary>
+/// Helpers for reading the captive portal's pages: the CSRF token or nonce some portals expect
+/// echoed back with the login POST, and the details its redirect carries.
+/// </summary>
+public static partial class LoginPageParser
+{
+    [GeneratedRegex(@"<input\b[^>]*>", RegexOptions.IgnoreCase)]
+    private static partial Regex InputTagRegex();
+
+    [GeneratedRegex(@"\b(?<name>[\w-]+)\s*=\s*(?:""(?<value>[^""]*)""|'(?<value>[^']*)'|(?<value>[^\s""'>]+))")]
+    private static partial Regex AttributeRegex();
+
+    [GeneratedRegex("csrf|xsrf|nonce|token", RegexOptions.IgnoreCase)]
+    private static partial Regex TokenWordRegex();
+
+    // acip, ac_ip, ac-ip, wlanacip
+    [GeneratedRegex(@"^(?:wlan)?ac[-_]?ip$", RegexOptions.IgnoreCase)]
+    private static partial Regex AcipParamRegex();
+
+    /// <summary>True if a rejection message or body complains about a missing or invalid form token.</summary>
+    public static bool MentionsFormToken(string text) => TokenWordRegex().IsMatch(text);
+
+    /// <summary>
+    /// The access controller address in the query string of a captive portal redirect such as
+    /// <c>https://portal/?wlanuserip=...&amp;wlanacip=10.252.13.10</c>, or null if it names none.
+    /// </summary>
+    public static string? GetAcipFromRedirect(Uri url)
+    {
+        foreach (var pair in url.Query.TrimStart('?').Split('&', StringSplitOptions.RemoveEmptyEntries))
+        {
+            var parts = pair.Split('=', 2);
+            if (parts.Length == 2 && AcipParamRegex().IsMatch(Uri.UnescapeDataString(parts[0])) &&
+                IPAddress.TryParse(Uri.UnescapeDataString(parts[1]), out var address) &&
+                address.AddressFamily == AddressFamily.InterNetwork)
+                return address.ToString();
+        }
+
+        return null;
+    }
+
+    /// <summary>
+    /// True if a captive portal redirect points at the login URL itself with a query string, which is how
+    /// portal variants that take the login as a GET announce it.
+    /// </summary>
+    public static bool IsLoginRedirect(Uri redirect, string loginUrl) =>
+        Uri.TryCreate(loginUrl, UriKind.Absolute, out var login) &&
+        redirect.Query.Length > 1 &&
+        Uri.Compare(redirect, login, UriComponents.SchemeAndServer | UriComponents.Path, UriFormat.Unescaped,
+            StringComparison.OrdinalIgnoreCase) == 0;
+
+    /// <summary>Returns the name/value pairs of every named <c>&lt;input type="hidden"&gt;</c> in the page.</summary>
+    public static Dictionary<string, string> GetHiddenFields(string html)
+    {
+        var fields = new Dictionary<string, string>();
+
+        foreach (Match tag in InputTagRegex().Matches(html))
+        {
+            var attributes = new Dictionary<string, string>(StringComparer.OrdinalIgnoreCase);
+            foreach (Match attr in AttributeRegex().Matches(tag.Value))
+                attributes.TryAdd(attr.Groups["name"].Value, WebUtility.HtmlDecode(attr.Groups["value"].Value));
+
+            if (attributes.TryGetValue("type", out var type) && type.Equals("hidden", StringComparison.OrdinalIgnoreCase) &&
+                attributes.TryGetValue("name", out var name) && name.Length > 0)
+                fields.TryAdd(name, attributes.GetValueOrDefault("value", ""));
+        }
+
+        return fields;
+    }
+}
@@ -0,0 +1,189 @@
+using System.Diagnostics.CodeAnalysis;
+using System.Globalization;
+using System.Text.Json;
+using System.Text.RegularExpressions;
+
+namespace KmitlNetAuth.Core;
+
+/// <summary>
+/// Interprets the body of a 2xx login response. The portal answers HTTP 200 even
+/// when it refuses the login, with a JSON body such as
+/// <c>{"success": false, "message": "..."}</c>. Non-JSON bodies are treated as success.
+/// </summary>
+public static partial class LoginResponseParser
+{
+    private static readonly string[] MessageKeys = ["message", "msg", "errmsg", "errorMessage", "error"];
+    private static readonly string[] FailureWords = ["fail", "failed", "failure", "error", "false"];
+    private static readonly string[] RetryAfterKeys = ["retryAfter", "retry_after", "lockTime", "lock_time"];
+
+    private const RegexOptions PatternOptions = RegexOptions.IgnoreCase | RegexOptions.CultureInvariant;
+
+    /// <summary>Longest a <see cref="Config.LoginSuccessPattern"/> match may run.</summary>
+    public static readonly TimeSpan PatternTimeout = TimeSpan.FromSeconds(1);
+
+    [GeneratedRegex(@"\block|too many (?:(?:login\s+)?attempts|failed|tries)|ล็อก|ล็อค|ระงับ", RegexOptions.IgnoreCase)]
+    private static partial Regex LockoutRegex();
+
+    [GeneratedRegex(@"session limit|concurrent|too many (?:sessions?|devices?)|already (?:logged in|online|in use)|max(?:imum)?\s+(?:number of\s+)?(?:sessions?|devices?|logins?)|เกินจำนวน|กำลังใช้งานอยู่|ออนไลน์อยู่", RegexOptions.IgnoreCase)]
+    private static partial Regex SessionLimitRegex();
+
+    [GeneratedRegex(@"<title[^>]*>(?<title>.*?)</title>", RegexOptions.IgnoreCase | RegexOptions.Singleline)]
+    private static partial Regex TitleRegex();
+
+    [GeneratedRegex(@"^\s*<(?:!doctype\s+html|html|head|body|title|meta)\b", RegexOptions.IgnoreCase)]
+    private static partial Regex HtmlStartRegex();
+
+    [GeneratedRegex(@"\s+")]
+    private static partial Regex WhitespaceRegex();
+
+    [GeneratedRegex(@"(?<value>\d+)\s*(?<unit>hours?|hrs?|h\b|minutes?|mins?|m\b|seconds?|secs?|s\b|ชั่วโมง|นาที|วินาที)", RegexOptions.IgnoreCase)]
+    private static partial Regex WaitRegex();
+
+    public static bool TryGetRejection(string body, [NotNullWhen(true)] out string? reason)
+    {
+        reason = null;
+
+        var trimmed = body.TrimStart();
+        if (!trimmed.StartsWith('{'))
+            return false;
+
+        try
+        {
+            using var doc = JsonDocument.Parse(trimmed);
+            var root = doc.RootElement;
+
+            var failed =
+                (root.TryGetProperty("success", out var success) && success.ValueKind == JsonValueKind.False) ||
+                (root.TryGetProperty("result", out var result) && result.ValueKind == JsonValueKind.String &&
+                    FailureWords.Contains(result.GetString(), StringComparer.OrdinalIgnoreCase));
+
+            if (!failed)
+                return false;
+
+            reason = GetMessage(root) ?? "Login rejected by portal";
+            return true;
+        }
+        catch (JsonException)
+        {
+            return false;
+        }
+    }
+
+    /// <summary>
+    /// True if the body is an HTML page rather than a login result, which happens when something other
+    /// than the portal intercepts the request. Decided by the markup the body starts with, not the
+    /// <c>Content-Type</c>, which some portals set to <c>text/html</c> on their JSON answers too. Check
+    /// <see cref="TryGetRejection"/> first.
+    /// </summary>
+    public static bool IsHtml(string body) => HtmlStartRegex().IsMatch(body);
+
+    /// <summary>A short, single-line description of an HTML page: its title, else the start of the body.</summary>
+    public static string Summarize(string body, int maxLength = 120)
+    {
+        var title = TitleRegex().Match(body);
+        var text = WhitespaceRegex().Replace(title.Success ? title.Groups["title"].Value : body, " ").Trim();
+        return text.Length <= maxLength ? text : text[..maxLength] + "...";
+    }
+
+    /// <summary>
+    /// True if a rejection is an account lockout. <paramref name="retryAfter"/> comes from a
+    /// numeric <c>retryAfter</c>/<c>lockTime</c> field (seconds) or a wait like "15 minutes"
+    /// in the message, and is null when the portal gives neither.
+    /// </summary>
+    public static bool IsLockout(string body, string reason, out TimeSpan? retryAfter)
+    {
+        retryAfter = null;
+        if (!LockoutRegex().IsMatch(reason))
+            return false;
+
+        retryAfter = GetRetryAfterField(body) ?? ParseWait(reason);
+        return true;
+    }
+
+    /// <summary>
+    /// True if a rejection says the account already has the maximum number of sessions, typically
+    /// because another device (a phone left on WiFi) is still logged in.
+    /// </summary>
+    public static bool IsSessionLimit(string reason) => SessionLimitRegex().IsMatch(reason);
+
+    /// <summary>
+    /// True if <paramref name="body"/> matches <see cref="Config.LoginSuccessPattern"/>. A pattern that
+    /// takes longer than <see cref="PatternTimeout"/> counts as no match rather than stalling the login.
+    /// </summary>
+    public static bool MatchesSuccessPattern(string body, string pattern)
+    {
+        try
+        {
+            return Regex.IsMatch(body, pattern, PatternOptions, PatternTimeout);
+        }
+        catch (RegexMatchTimeoutException)
+        {
+            return false;
+        }
+    }
+
+    /// <summary>Whether <paramref name="pattern"/> compiles, with the parser's message in <paramref name="error"/> if not.</summary>
+    public static bool IsValidPattern(string pattern, [NotNullWhen(false)] out string? error)
+    {
+        try
+        {
+            _ = new Regex(pattern, PatternOptions, PatternTimeout);
+            error = null;
+            return true;
+        }
+        catch (ArgumentException e)
+        {
+            error = e.Message;
+            return false;
+        }
+    }
+
+    private static TimeSpan? GetRetryAfterField(string body)
+    {
+        if (!body.TrimStart().StartsWith('{'))
+            return null;
+
+        try
+        {
+            using var doc = JsonDocument.Parse(body);
+            foreach (var key in RetryAfterKeys)
+            {
+                if (doc.RootElement.TryGetProperty(key, out var value) &&
+                    value.ValueKind == JsonValueKind.Number && value.TryGetInt32(out var seconds) && seconds > 0)
+                    return TimeSpan.FromSeconds(seconds);
+            }
+        }
+        catch (JsonException)
+        {
+        }
+
+        return null;
+    }
+
+    private static TimeSpan? ParseWait(string text)
+    {
+        var match = WaitRegex().Match(text);
+        if (!match.Success || !int.TryParse(match.Groups["value"].Value, CultureInfo.InvariantCulture, out var value))
+            return null;
+
+        var unit = match.Groups["unit"].Value.ToLowerInvariant();
+        return unit[0] switch
+        {
+            'h' or 'ช' => TimeSpan.FromHours(value),
+            'm' or 'น' => TimeSpan.FromMinutes(value),
+            _ => TimeSpan.FromSeconds(value),
+        };
+    }
+
+    private static string? GetMessage(JsonElement root)
+    {
+        foreach (var key in MessageKeys)
+        {
+            if (root.TryGetProperty(key, out var value) && value.ValueKind == JsonValueKind.String &&
+                !string.IsNullOrWhiteSpace(value.GetString()))
+                return value.GetString();
+        }
+
+        return null;
+    }
+}
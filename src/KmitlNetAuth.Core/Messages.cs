@@ -0,0 +1,136 @@
+using System.Globalization;
+
+namespace KmitlNetAuth.Core;
+
+/// <summary>
+/// User-facing strings shown in notifications and the tray menu. Placeholders use
+/// <see cref="string.Format(string, object[])"/> syntax; each key's comment lists them.
+/// </summary>
+public enum MessageKey
+{
+    ConnectedTitle,
+    ConnectedBody,
+    DisconnectedTitle,
+    DisconnectedBody,          // while reconnecting
+    DisconnectedBodyShort,
+    PausedTitle,
+    PausedBody,
+    NeedsAttentionTitle,
+    NeedsAttentionBody,
+    LoginSuccessTitle,
+    LoginSuccessBody,          // {0} username
+    LoginFailedTitle,
+    LoginFailedUnexpectedPage, // {0} page summary
+    LoginFailedStatus,         // {0} HTTP status
+    LoginRejectedTitle,
+    PortalUnreachableTitle,
+    PortalUnreachableBody,     // {0} cooldown seconds
+    AccountLockedTitle,
+    AccountLockedRetryIn,      // {0} minutes
+    AccountLockedWait,
+    SessionLimitTitle,
+    SessionLimitBody,          // {0} portal message
+    TrayShowHide,
+    TrayInterval,
+    TrayForgetCredentials,
+    TrayTrustCertificate,
+    TrayQuit,
+    TrayStatusBody,            // {0} username, {1} IP address, {2} last heartbeat time
+    TrayNever,
+    CertificateChangedTitle,
+    CertificateChangedBody,    // {0} host
+}
+
+/// <summary>
+/// The message catalog, selected by <see cref="Config.Language"/>. English is complete and is the
+/// fallback for any key a translation lacks; to add a language, add a code to <see cref="Languages"/>
+/// and a table below.
+/// </summary>
+public static class Messages
+{
+    /// <summary>Codes accepted by <c>[notifications] language</c>.</summary>
+    public static readonly IReadOnlyList<string> Languages = ["en", "th"];
+
+    private static readonly Dictionary<MessageKey, string> English = new()
+    {
+        [MessageKey.ConnectedTitle] = "Connected",
+        [MessageKey.ConnectedBody] = "Internet connection is active.",
+        [MessageKey.DisconnectedTitle] = "Disconnected",
+        [MessageKey.DisconnectedBody] = "Internet connection lost. Attempting to reconnect...",
+        [MessageKey.DisconnectedBodyShort] = "Internet connection lost.",
+        [MessageKey.PausedTitle] = "Paused",
+        [MessageKey.PausedBody] = "Auto-login is disabled.",
+        [MessageKey.NeedsAttentionTitle] = "Needs attention",
+        [MessageKey.NeedsAttentionBody] = "Check your credentials in Settings.",
+        [MessageKey.LoginSuccessTitle] = "Login Successful",
+        [MessageKey.LoginSuccessBody] = "Logged in as {0}",
+        [MessageKey.LoginFailedTitle] = "Login Failed",
+        [MessageKey.LoginFailedUnexpectedPage] = "Unexpected page from portal: {0}",
+        [MessageKey.LoginFailedStatus] = "Status: {0}",
+        [MessageKey.LoginRejectedTitle] = "Login Rejected",
+        [MessageKey.PortalUnreachableTitle] = "Portal Unreachable",
+        [MessageKey.PortalUnreachableBody] = "Login paused for {0}s after repeated connection errors.",
+        [MessageKey.AccountLockedTitle] = "Account Locked",
+        [MessageKey.AccountLockedRetryIn] = "Try again in {0} min.",
+        [MessageKey.AccountLockedWait] = "Wait before trying again.",
+        [MessageKey.SessionLimitTitle] = "Already Logged In Elsewhere",
+        [MessageKey.SessionLimitBody] = "{0}. Log out on the other device, then log in again.",
+        [MessageKey.TrayShowHide] = "Show / Hide",
+        [MessageKey.TrayInterval] = "Interval",
+        [MessageKey.TrayForgetCredentials] = "Forget Credentials",
+        [MessageKey.TrayTrustCertificate] = "Trust New Portal Certificate...",
+        [MessageKey.TrayQuit] = "Quit",
+        [MessageKey.TrayStatusBody] = "User: {0}\nIP: {1}\nLast heartbeat: {2}",
+        [MessageKey.TrayNever] = "never",
+        [MessageKey.CertificateChangedTitle] = "Portal Certificate Changed",
+        [MessageKey.CertificateChangedBody] = "{0} presented a different certificate than before. Check the log before trusting this network.",
+    };
+
+    private static readonly Dictionary<MessageKey, string> Thai = new()
+    {
+        [MessageKey.ConnectedTitle] = "เชื่อมต่อแล้ว",
+        [MessageKey.ConnectedBody] = "เชื่อมต่ออินเทอร์เน็ตได้แล้ว",
+        [MessageKey.DisconnectedTitle] = "การเชื่อมต่อหลุด",
+        [MessageKey.DisconnectedBody] = "อินเทอร์เน็ตหลุด กำลังเชื่อมต่อใหม่...",
+        [MessageKey.DisconnectedBodyShort] = "อินเทอร์เน็ตหลุด",
+        [MessageKey.PausedTitle] = "หยุดชั่วคราว",
+        [MessageKey.PausedBody] = "ปิดการเข้าสู่ระบบอัตโนมัติอยู่",
+        [MessageKey.NeedsAttentionTitle] = "ต้องตรวจสอบ",
+        [MessageKey.NeedsAttentionBody] = "ตรวจสอบชื่อผู้ใช้และรหัสผ่านในหน้าตั้งค่า",
+        [MessageKey.LoginSuccessTitle] = "เข้าสู่ระบบสำเร็จ",
+        [MessageKey.LoginSuccessBody] = "เข้าสู่ระบบในชื่อ {0}",
+        [MessageKey.LoginFailedTitle] = "เข้าสู่ระบบไม่สำเร็จ",
+        [MessageKey.LoginFailedUnexpectedPage] = "พอร์ทัลตอบกลับเป็นหน้าที่ไม่คาดคิด: {0}",
+        [MessageKey.LoginFailedStatus] = "สถานะ: {0}",
+        [MessageKey.LoginRejectedTitle] = "พอร์ทัลปฏิเสธการเข้าสู่ระบบ",
+        [MessageKey.PortalUnreachableTitle] = "ติดต่อพอร์ทัลไม่ได้",
+        [MessageKey.PortalUnreachableBody] = "หยุดเข้าสู่ระบบ {0} วินาที เพราะเชื่อมต่อผิดพลาดซ้ำหลายครั้ง",
+        [MessageKey.AccountLockedTitle] = "บัญชีถูกล็อก",
+        [MessageKey.AccountLockedRetryIn] = "ลองใหม่ในอีก {0} นาที",
+        [MessageKey.AccountLockedWait] = "รอสักครู่ก่อนลองใหม่",
+        [MessageKey.SessionLimitTitle] = "บัญชีนี้ใช้งานอยู่ที่อุปกรณ์อื่น",
+        [MessageKey.SessionLimitBody] = "{0} ออกจากระบบที่อุปกรณ์อื่นก่อน แล้วเข้าสู่ระบบใหม่",
+        [MessageKey.TrayShowHide] = "แสดง / ซ่อน",
+        [MessageKey.TrayInterval] = "ช่วงเวลาตรวจสอบ",
+        [MessageKey.TrayForgetCredentials] = "ลบข้อมูลเข้าสู่ระบบ",
+        [MessageKey.TrayTrustCertificate] = "เชื่อถือใบรับรองใหม่ของพอร์ทัล...",
+        [MessageKey.TrayQuit] = "ออก",
+        [MessageKey.TrayStatusBody] = "ผู้ใช้: {0}\nIP: {1}\nฮาร์ตบีตล่าสุด: {2}",
+        [MessageKey.TrayNever] = "ยังไม่มี",
+        [MessageKey.CertificateChangedTitle] = "ใบรับรองของพอร์ทัลเปลี่ยนไป",
+        [MessageKey.CertificateChangedBody] = "{0} ใช้ใบรับรองที่ต่างจากครั้งก่อน ตรวจสอบบันทึกก่อนไว้ใจเครือข่ายนี้",
+    };
+
+    /// <summary>The string for <paramref name="key"/> in <paramref name="language"/>, English if it has none.</summary>
+    public static string Get(string language, MessageKey key, params object[] args)
+    {
+        var table = language.ToLowerInvariant() switch
+        {
+            "th" => Thai,
+            _ => English,
+        };
+
+        var format = table.GetValueOrDefault(key) ?? English[key];
+        return args.Length == 0 ? format : string.Format(CultureInfo.InvariantCulture, format, args);
+    }
+}
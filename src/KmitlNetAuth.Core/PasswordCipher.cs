@@ -0,0 +1,67 @@
+using System.Security.Cryptography;
+using System.Text;
+
+namespace KmitlNetAuth.Core;
+
+/// <summary>
+/// Passphrase-based encryption for the <c>password_enc</c> config field.
+/// Used as a fallback when the OS credential store is unavailable, so the
+/// password never has to sit in config.toml as plaintext.
+/// </summary>
+/// <remarks>
+/// Key: PBKDF2-SHA256(passphrase, random salt). Cipher: AES-256-GCM.
+/// Output is a single opaque base64 blob: salt | nonce | tag | ciphertext.
+/// </remarks>
+public static class PasswordCipher
+{
+    private const int SaltSize = 16;
+    private const int NonceSize = 12;
+    private const int TagSize = 16;
+    private const int KeySize = 32;
+    private const int Iterations = 600_000;
+
+    public static string Encrypt(string plaintext, string passphrase)
+    {
+        var salt = RandomNumberGenerator.GetBytes(SaltSize);
+        var nonce = RandomNumberGenerator.GetBytes(NonceSize);
+        var plainBytes = Encoding.UTF8.GetBytes(plaintext);
+        var cipherBytes = new byte[plainBytes.Length];
+        var tag = new byte[TagSize];
+
+        using (var aes = new AesGcm(DeriveKey(passphrase, salt), TagSize))
+            aes.Encrypt(nonce, plainBytes, cipherBytes, tag);
+
+        var blob = new byte[SaltSize + NonceSize + TagSize + cipherBytes.Length];
+        salt.CopyTo(blob, 0);
+        nonce.CopyTo(blob, SaltSize);
+        tag.CopyTo(blob, SaltSize + NonceSize);
+        cipherBytes.CopyTo(blob, SaltSize + NonceSize + TagSize);
+
+        return Convert.ToBase64String(blob);
+    }
+
+    /// <summary>
+    /// Decrypts a blob produced by <see cref="Encrypt"/>.
+    /// Throws <see cref="CryptographicException"/> on a wrong passphrase or tampered data.
+    /// </summary>
+    public static string Decrypt(string encrypted, string passphrase)
+    {
+        var blob = Convert.FromBase64String(encrypted);
+        if (blob.Length < SaltSize + NonceSize + TagSize)
+            throw new CryptographicException("Encrypted password is truncated");
+
+        var salt = blob.AsSpan(0, SaltSize);
+        var nonce = blob.AsSpan(SaltSize, NonceSize);
+        var tag = blob.AsSpan(SaltSize + NonceSize, TagSize);
+        var cipherBytes = blob.AsSpan(SaltSize + NonceSize + TagSize);
+        var plainBytes = new byte[cipherBytes.Length];
+
+        using (var aes = new AesGcm(DeriveKey(passphrase, salt.ToArray()), TagSize))
+            aes.Decrypt(nonce, cipherBytes, tag, plainBytes);
+
+        return Encoding.UTF8.GetString(plainBytes);
+    }
+
+    private static byte[] DeriveKey(string passphrase, byte[] salt) =>
+        Rfc2898DeriveBytes.Pbkdf2(Encoding.UTF8.GetBytes(passphrase), salt, Iterations, HashAlgorithmName.SHA256, KeySize);
+}
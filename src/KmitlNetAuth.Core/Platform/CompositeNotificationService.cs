@@ -0,0 +1,24 @@
+namespace KmitlNetAuth.Core.Platform;
+
+/// <summary>Fans a notification out to every configured backend.</summary>
+public sealed class CompositeNotificationService : INotificationService
+{
+    private readonly IReadOnlyList<INotificationService> _backends;
+
+    public CompositeNotificationService(IReadOnlyList<INotificationService> backends)
+    {
+        _backends = backends;
+    }
+
+    public void Show(string title, string body)
+    {
+        foreach (var backend in _backends)
+            backend.Show(title, body);
+    }
+
+    public void Show(string title, string body, NotificationOptions options)
+    {
+        foreach (var backend in _backends)
+            backend.Show(title, body, options);
+    }
+}
@@ -0,0 +1,7 @@
+namespace KmitlNetAuth.Core.Platform;
+
+public interface INetworkChangeMonitor
+{
+    /// <summary>Raised when the OS reports an address or availability change worth re-checking for.</summary>
+    event EventHandler? NetworkChanged;
+}
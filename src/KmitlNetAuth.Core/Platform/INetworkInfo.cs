@@ -1,6 +1,27 @@
+using System.Net;
+
 namespace KmitlNetAuth.Core.Platform;
 
 public interface INetworkInfo
 {
-    string GetMacAddress();
+    /// <summary>
+    /// Returns the MAC of <paramref name="interfaceName"/> (matched by name or id), or of the
+    /// active non-loopback interface holding the default route (else the first active one) when null. <see cref="NetworkInfo.FallbackMacAddress"/> if none is found.
+    /// </summary>
+    string GetMacAddress(string? interfaceName = null);
+
+    /// <summary>The first IPv4 default gateway of <paramref name="interfaceName"/> or any active interface; null if none.</summary>
+    IPAddress? GetDefaultGateway(string? interfaceName = null);
+
+    /// <summary>
+    /// Whether <paramref name="interfaceName"/>, or any non-loopback interface when null, is up with an
+    /// IPv4 default route. False means a connection attempt can only fail (e.g. WiFi switched off).
+    /// </summary>
+    bool IsNetworkUp(string? interfaceName = null);
+
+    /// <summary>
+    /// The IPv4 address of <paramref name="interfaceName"/>, or of the active interface with an IPv4 default
+    /// route when null; null if there is none.
+    /// </summary>
+    IPAddress? GetIpAddress(string? interfaceName = null);
 }
@@ -1,6 +1,24 @@
 namespace KmitlNetAuth.Core.Platform;
 
+public enum NotificationUrgency
+{
+    Normal,
+    Critical,
+}
+
+/// <summary>
+/// Extra hints for a notification. <paramref name="Category"/> is a freedesktop category such as
+/// <c>network.disconnected</c>; <paramref name="OnRetry"/> adds a "Retry" action that runs it when clicked.
+/// </summary>
+public sealed record NotificationOptions(
+    NotificationUrgency Urgency = NotificationUrgency.Normal,
+    string? Category = null,
+    Action? OnRetry = null);
+
 public interface INotificationService
 {
     void Show(string title, string body);
+
+    /// <summary>Shows a notification with hints; backends without urgency or action support show it plainly.</summary>
+    void Show(string title, string body, NotificationOptions options) => Show(title, body);
 }
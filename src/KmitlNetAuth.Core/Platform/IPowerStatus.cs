@@ -0,0 +1,9 @@
+namespace KmitlNetAuth.Core.Platform;
+
+public readonly record struct PowerState(bool OnBattery, int? ChargePercent);
+
+public interface IPowerStatus
+{
+    /// <summary>Current power source, or null when it cannot be determined (e.g. desktops, containers).</summary>
+    PowerState? GetPowerState();
+}
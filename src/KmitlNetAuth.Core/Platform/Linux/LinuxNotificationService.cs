@@ -3,35 +3,143 @@ using Microsoft.Extensions.Logging;
 
 namespace KmitlNetAuth.Core.Platform.Linux;
 
+/// <summary>
+/// Notifications through <c>notify-send</c>. A notification replaces the last one of the same
+/// <see cref="NotificationOptions.Category"/> instead of stacking up beside it, which matters for critical ones
+/// that stay until dismissed, and <c>--wait</c> is only passed when there is a Retry action to wait for.
+/// </summary>
 public class LinuxNotificationService : INotificationService
 {
+    private const string RetryActionKey = "retry";
+
     private readonly ILogger<LinuxNotificationService> _logger;
+    private readonly Lock _lock = new();
+    private readonly Dictionary<string, string> _idsByCategory = new();
+    private readonly Dictionary<string, Process> _waitingByCategory = new();
+
+    // Set once notify-send turns out to be older than libnotify 0.7.10, which added --print-id, --replace-id and --action
+    private volatile bool _legacy;
 
     public LinuxNotificationService(ILogger<LinuxNotificationService> logger)
     {
         _logger = logger;
     }
 
-    public void Show(string title, string body)
+    public void Show(string title, string body) => Show(title, body, new NotificationOptions());
+
+    // notify-send blocks until the notification is closed with --wait, so it always runs in the background
+    public void Show(string title, string body, NotificationOptions options) => _ = ShowAsync(title, body, options);
+
+    private async Task ShowAsync(string title, string body, NotificationOptions options)
     {
         try
         {
-            var psi = new ProcessStartInfo
+            var legacy = _legacy;
+            string? replaceId = null;
+            if (!legacy && options.Category != null)
+            {
+                lock (_lock)
+                {
+                    _idsByCategory.TryGetValue(options.Category, out replaceId);
+                    // The replaced notification's Retry would otherwise be reported to both processes
+                    if (_waitingByCategory.Remove(options.Category, out var waiting))
+                        StopWaiting(waiting);
+                }
+            }
+
+            using var process = Process.Start(CreateStartInfo(title, body, options, legacy, replaceId));
+            if (process == null)
+                return;
+
+            var waits = !legacy && options.Category != null && options.OnRetry != null;
+            if (waits)
+            {
+                lock (_lock)
+                    _waitingByCategory[options.Category!] = process;
+            }
+
+            // With --print-id the id comes first, right away; with --wait the key of a clicked action follows on close
+            var id = legacy ? null : (await process.StandardOutput.ReadLineAsync())?.Trim();
+            if (options.Category != null && !string.IsNullOrEmpty(id))
+            {
+                lock (_lock)
+                    _idsByCategory[options.Category] = id;
+            }
+
+            var output = await process.StandardOutput.ReadToEndAsync();
+            await process.WaitForExitAsync();
+            if (waits)
+            {
+                lock (_lock)
+                {
+                    // Gone from the map: a newer notification of the category replaced this one and stopped it
+                    if (!_waitingByCategory.TryGetValue(options.Category!, out var waiting) || waiting != process)
+                        return;
+                    _waitingByCategory.Remove(options.Category!);
+                }
+            }
+
+            if (process.ExitCode != 0 && !legacy)
+            {
+                _legacy = true;
+                await ShowAsync(title, body, options);
+                return;
+            }
+
+            if (options.OnRetry != null && process.ExitCode == 0 && output.Trim() == RetryActionKey)
             {
-                FileName = "notify-send",
-                ArgumentList = { "--app-name=KMITL NetAuth", title, body },
-                RedirectStandardOutput = true,
-                RedirectStandardError = true,
-                UseShellExecute = false,
-                CreateNoWindow = true,
-            };
-
-            using var process = Process.Start(psi);
-            process?.WaitForExit(3000);
+                _logger.LogInformation("Retry clicked on \"{Title}\" notification", title);
+                options.OnRetry();
+            }
         }
         catch (Exception e)
         {
             _logger.LogWarning("Failed to show notification: {Error}", e.Message);
         }
     }
+
+    private void StopWaiting(Process process)
+    {
+        try
+        {
+            process.Kill();
+        }
+        catch (Exception e) when (e is InvalidOperationException or System.ComponentModel.Win32Exception)
+        {
+            _logger.LogDebug("notify-send already exited: {Error}", e.Message);
+        }
+    }
+
+    private static ProcessStartInfo CreateStartInfo(string title, string body, NotificationOptions options, bool legacy,
+        string? replaceId)
+    {
+        var psi = new ProcessStartInfo
+        {
+            FileName = "notify-send",
+            ArgumentList = { "--app-name=KMITL NetAuth" },
+            RedirectStandardOutput = true,
+            RedirectStandardError = true,
+            UseShellExecute = false,
+            CreateNoWindow = true,
+        };
+
+        psi.ArgumentList.Add(options.Urgency == NotificationUrgency.Critical ? "--urgency=critical" : "--urgency=normal");
+        if (options.Category != null)
+            psi.ArgumentList.Add($"--category={options.Category}");
+        if (!legacy)
+        {
+            psi.ArgumentList.Add("--print-id");
+            if (replaceId != null)
+                psi.ArgumentList.Add($"--replace-id={replaceId}");
+            if (options.OnRetry != null)
+            {
+                psi.ArgumentList.Add($"--action={RetryActionKey}=Retry");
+                psi.ArgumentList.Add("--wait");
+            }
+        }
+
+        psi.ArgumentList.Add(title);
+        psi.ArgumentList.Add(body);
+        return psi;
+    }
 }
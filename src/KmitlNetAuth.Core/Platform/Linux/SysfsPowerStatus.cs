@@ -0,0 +1,46 @@
+namespace KmitlNetAuth.Core.Platform.Linux;
+
+/// <summary>
+/// Reads <c>/sys/class/power_supply</c>. Returns null when no battery is present.
+/// </summary>
+public class SysfsPowerStatus : IPowerStatus
+{
+    private const string PowerSupplyDir = "/sys/class/power_supply";
+
+    public PowerState? GetPowerState()
+    {
+        try
+        {
+            if (!Directory.Exists(PowerSupplyDir))
+                return null;
+
+            var hasBattery = false;
+            var discharging = false;
+            int? lowestCharge = null;
+
+            // Multiple batteries: report discharging if any is, and the lowest charge
+            foreach (var supply in Directory.EnumerateDirectories(PowerSupplyDir))
+            {
+                if (ReadValue(supply, "type") != "Battery")
+                    continue;
+
+                hasBattery = true;
+                discharging |= ReadValue(supply, "status") == "Discharging";
+                if (int.TryParse(ReadValue(supply, "capacity"), out var charge))
+                    lowestCharge = lowestCharge is { } lowest ? Math.Min(lowest, charge) : charge;
+            }
+
+            return hasBattery ? new PowerState(discharging, lowestCharge) : null;
+        }
+        catch
+        {
+            return null;
+        }
+    }
+
+    private static string? ReadValue(string supplyDir, string name)
+    {
+        var path = Path.Combine(supplyDir, name);
+        return File.Exists(path) ? File.ReadAllText(path).Trim() : null;
+    }
+}
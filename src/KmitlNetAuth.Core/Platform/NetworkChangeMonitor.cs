@@ -0,0 +1,54 @@
+using System.Net.NetworkInformation;
+
+namespace KmitlNetAuth.Core.Platform;
+
+/// <summary>
+/// Forwards <see cref="NetworkChange"/> events (netlink on Linux, the IP Helper API on Windows).
+/// Changes are dropped while no network is available, or while <see cref="Config.Interface"/> is set
+/// and that interface is not up, so a cable being pulled doesn't trigger a pointless login.
+/// </summary>
+public sealed class NetworkChangeMonitor : INetworkChangeMonitor, IDisposable
+{
+    private readonly Config _config;
+
+    public event EventHandler? NetworkChanged;
+
+    public NetworkChangeMonitor(Config config)
+    {
+        _config = config;
+        NetworkChange.NetworkAddressChanged += OnNetworkChange;
+        NetworkChange.NetworkAvailabilityChanged += OnNetworkChange;
+    }
+
+    private void OnNetworkChange(object? sender, EventArgs e)
+    {
+        if (IsAllowedNetworkUp())
+            NetworkChanged?.Invoke(this, EventArgs.Empty);
+    }
+
+    private bool IsAllowedNetworkUp()
+    {
+        try
+        {
+            if (!NetworkInterface.GetIsNetworkAvailable())
+                return false;
+            if (_config.Interface == null)
+                return true;
+
+            return NetworkInterface.GetAllNetworkInterfaces().Any(n =>
+                n.OperationalStatus == OperationalStatus.Up &&
+                (string.Equals(n.Name, _config.Interface, StringComparison.OrdinalIgnoreCase) ||
+                 string.Equals(n.Id, _config.Interface, StringComparison.OrdinalIgnoreCase)));
+        }
+        catch (NetworkInformationException)
+        {
+            return false;
+        }
+    }
+
+    public void Dispose()
+    {
+        NetworkChange.NetworkAddressChanged -= OnNetworkChange;
+        NetworkChange.NetworkAvailabilityChanged -= OnNetworkChange;
+    }
+}
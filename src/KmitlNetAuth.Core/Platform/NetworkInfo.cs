@@ -1,28 +1,188 @@
+using System.Net;
 using System.Net.NetworkInformation;
+using System.Net.Sockets;
 
 namespace KmitlNetAuth.Core.Platform;
 
 public class NetworkInfo : INetworkInfo
 {
-    public string GetMacAddress()
+    public const string FallbackMacAddress = "000000000000";
+
+    public string GetMacAddress(string? interfaceName = null)
+    {
+        try
+        {
+            var nic = SelectInterface(NetworkInterface.GetAllNetworkInterfaces(), interfaceName);
+            return nic == null ? FallbackMacAddress : FormatMac(nic);
+        }
+        catch
+        {
+            return FallbackMacAddress;
+        }
+    }
+
+    /// <summary>
+    /// Every adapter with a hardware address, flagging the one <see cref="GetMacAddress"/> picks for
+    /// <paramref name="interfaceName"/>, for telling which MAC the portal will see.
+    /// </summary>
+    public static IReadOnlyList<InterfaceSummary> ListInterfaces(string? interfaceName = null)
     {
         try
         {
-            var nic = NetworkInterface.GetAllNetworkInterfaces()
-                .FirstOrDefault(n =>
-                    n.OperationalStatus == OperationalStatus.Up &&
-                    n.NetworkInterfaceType != NetworkInterfaceType.Loopback &&
-                    n.GetPhysicalAddress().GetAddressBytes().Length > 0);
+            var nics = NetworkInterface.GetAllNetworkInterfaces();
+            var selected = SelectInterface(nics, interfaceName);
+            return nics
+                .Where(n => n.NetworkInterfaceType != NetworkInterfaceType.Loopback &&
+                            n.GetPhysicalAddress().GetAddressBytes().Length > 0)
+                .Select(n => new InterfaceSummary(
+                    n.Name,
+                    n.Description,
+                    FormatMac(n),
+                    n.OperationalStatus == OperationalStatus.Up,
+                    GetIpv4Addresses(n),
+                    HasIpv4Gateway(n),
+                    n == selected))
+                .ToList();
+        }
+        catch (NetworkInformationException)
+        {
+            return [];
+        }
+    }
 
-            if (nic == null)
-                return "000000000000";
+    // Without a name, prefer the adapter holding the default route: that's the one the portal
+    // saw at DHCP time, not a VPN, VM bridge or idle adapter that happens to be listed first
+    private static NetworkInterface? SelectInterface(IEnumerable<NetworkInterface> nics, string? interfaceName) =>
+        nics
+            .Where(n =>
+                (interfaceName == null
+                    ? n.OperationalStatus == OperationalStatus.Up
+                    : string.Equals(n.Name, interfaceName, StringComparison.OrdinalIgnoreCase) ||
+                      string.Equals(n.Id, interfaceName, StringComparison.OrdinalIgnoreCase)) &&
+                n.NetworkInterfaceType != NetworkInterfaceType.Loopback &&
+                n.GetPhysicalAddress().GetAddressBytes().Length > 0)
+            .OrderByDescending(HasIpv4Gateway)
+            .FirstOrDefault();
 
-            var bytes = nic.GetPhysicalAddress().GetAddressBytes();
-            return BitConverter.ToString(bytes).Replace("-", "").ToLowerInvariant();
+    private static string FormatMac(NetworkInterface nic) =>
+        BitConverter.ToString(nic.GetPhysicalAddress().GetAddressBytes()).Replace("-", "").ToLowerInvariant();
+
+    private static IReadOnlyList<string> GetIpv4Addresses(NetworkInterface nic)
+    {
+        try
+        {
+            return nic.GetIPProperties().UnicastAddresses
+                .Where(a => a.Address.AddressFamily == AddressFamily.InterNetwork)
+                .Select(a => a.Address.ToString())
+                .ToList();
+        }
+        catch (NetworkInformationException)
+        {
+            return [];
+        }
+    }
+
+    public IPAddress? GetDefaultGateway(string? interfaceName = null)
+    {
+        try
+        {
+            return NetworkInterface.GetAllNetworkInterfaces()
+                .Where(n => n.OperationalStatus == OperationalStatus.Up &&
+                            n.NetworkInterfaceType != NetworkInterfaceType.Loopback &&
+                            (interfaceName == null ||
+                             string.Equals(n.Name, interfaceName, StringComparison.OrdinalIgnoreCase) ||
+                             string.Equals(n.Id, interfaceName, StringComparison.OrdinalIgnoreCase)))
+                .SelectMany(n => n.GetIPProperties().GatewayAddresses)
+                .Select(g => g.Address)
+                .FirstOrDefault(a => a.AddressFamily == AddressFamily.InterNetwork && !a.Equals(IPAddress.Any));
         }
         catch
         {
-            return "000000000000";
+            return null;
+        }
+    }
+
+    public IPAddress? GetIpAddress(string? interfaceName = null)
+    {
+        try
+        {
+            return NetworkInterface.GetAllNetworkInterfaces()
+                .Where(n => n.OperationalStatus == OperationalStatus.Up &&
+                            n.NetworkInterfaceType != NetworkInterfaceType.Loopback &&
+                            (interfaceName == null ||
+                             string.Equals(n.Name, interfaceName, StringComparison.OrdinalIgnoreCase) ||
+                             string.Equals(n.Id, interfaceName, StringComparison.OrdinalIgnoreCase)) &&
+                            HasIpv4Gateway(n))
+                .SelectMany(n => n.GetIPProperties().UnicastAddresses)
+                .Select(u => u.Address)
+                .FirstOrDefault(a => a.AddressFamily == AddressFamily.InterNetwork);
+        }
+        catch (NetworkInformationException)
+        {
+            return null;
+        }
+    }
+
+    public bool IsNetworkUp(string? interfaceName = null)
+    {
+        try
+        {
+            return NetworkInterface.GetAllNetworkInterfaces().Any(n =>
+                n.OperationalStatus == OperationalStatus.Up &&
+                n.NetworkInterfaceType != NetworkInterfaceType.Loopback &&
+                (interfaceName == null ||
+                 string.Equals(n.Name, interfaceName, StringComparison.OrdinalIgnoreCase) ||
+                 string.Equals(n.Id, interfaceName, StringComparison.OrdinalIgnoreCase)) &&
+                HasIpv4Gateway(n));
+        }
+        catch (NetworkInformationException)
+        {
+            // Can't tell; let the connectivity check decide rather than waiting forever
+            return true;
         }
     }
+
+    /// <summary>
+    /// True if the locally administered bit is set, which is how the per-network "private" or
+    /// "random" MACs of Windows, Android and iOS appear. <paramref name="mac"/> is in the normalized form.
+    /// </summary>
+    public static bool IsLocallyAdministered(string mac) =>
+        mac.Length == 12 && mac != FallbackMacAddress &&
+        (Convert.ToByte(mac[..2], 16) & 0x02) != 0;
+
+    private static bool HasIpv4Gateway(NetworkInterface nic)
+    {
+        try
+        {
+            return nic.GetIPProperties().GatewayAddresses
+                .Any(g => g.Address.AddressFamily == AddressFamily.InterNetwork && !g.Address.Equals(IPAddress.Any));
+        }
+        catch (NetworkInformationException)
+        {
+            return false;
+        }
+    }
+
+    /// <summary>
+    /// Normalizes "AA:BB:CC:DD:EE:FF" / "aa-bb-..." / "aabb.ccdd.eeff" to the portal's
+    /// 12-char lowercase form. Returns null if the value isn't a MAC address.
+    /// </summary>
+    public static string? NormalizeMacAddress(string? value)
+    {
+        if (string.IsNullOrWhiteSpace(value))
+            return null;
+
+        var hex = new string(value.Where(c => c is not (':' or '-' or '.')).ToArray()).Trim().ToLowerInvariant();
+        return hex.Length == 12 && hex.All(Uri.IsHexDigit) ? hex : null;
+    }
 }
+
+/// <summary>One adapter as listed by <see cref="NetworkInfo.ListInterfaces"/>. <c>Mac</c> is in the normalized form.</summary>
+public sealed record InterfaceSummary(
+    string Name,
+    string Description,
+    string Mac,
+    bool IsUp,
+    IReadOnlyList<string> Ipv4Addresses,
+    bool HasDefaultRoute,
+    bool Selected);
@@ -0,0 +1,9 @@
+namespace KmitlNetAuth.Core.Platform;
+
+/// <summary>Discards notifications; for one-off clients whose failures are reported on the console instead.</summary>
+public sealed class NullNotificationService : INotificationService
+{
+    public void Show(string title, string body)
+    {
+    }
+}
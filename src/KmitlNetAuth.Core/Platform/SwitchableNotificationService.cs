@@ -0,0 +1,30 @@
+namespace KmitlNetAuth.Core.Platform;
+
+/// <summary>
+/// Passes notifications to a backend only while <paramref name="isEnabled"/> says so, checked on every
+/// notification so turning desktop notifications off in settings takes effect without a restart.
+/// While off, the backend isn't touched at all (no <c>notify-send</c>, no D-Bus, no toast).
+/// </summary>
+public sealed class SwitchableNotificationService : INotificationService
+{
+    private readonly INotificationService _inner;
+    private readonly Func<bool> _isEnabled;
+
+    public SwitchableNotificationService(INotificationService inner, Func<bool> isEnabled)
+    {
+        _inner = inner;
+        _isEnabled = isEnabled;
+    }
+
+    public void Show(string title, string body)
+    {
+        if (_isEnabled())
+            _inner.Show(title, body);
+    }
+
+    public void Show(string title, string body, NotificationOptions options)
+    {
+        if (_isEnabled())
+            _inner.Show(title, body, options);
+    }
+}
@@ -0,0 +1,71 @@
+using System.Net.Http.Headers;
+using System.Text;
+using System.Text.Json.Nodes;
+using Microsoft.Extensions.Logging;
+
+namespace KmitlNetAuth.Core.Platform;
+
+/// <summary>
+/// POSTs notifications to a webhook. Discord and Slack URLs get their JSON payloads;
+/// anything else (e.g. ntfy) gets the body as plain text with the title in a <c>Title</c> header.
+/// Sending is fire-and-forget so a slow webhook never stalls the auth loop.
+/// </summary>
+public sealed class WebhookNotificationService : INotificationService
+{
+    private readonly HttpClient _httpClient;
+    private readonly Uri _url;
+    private readonly ILogger<WebhookNotificationService> _logger;
+
+    public WebhookNotificationService(HttpClient httpClient, string url, ILogger<WebhookNotificationService> logger)
+    {
+        _httpClient = httpClient;
+        _url = new Uri(url);
+        _logger = logger;
+    }
+
+    public void Show(string title, string body)
+    {
+        _ = SendAsync(title, body);
+    }
+
+    public async Task SendAsync(string title, string body)
+    {
+        try
+        {
+            using var request = CreateRequest(title, body);
+            using var response = await _httpClient.SendAsync(request);
+
+            if (!response.IsSuccessStatusCode)
+                _logger.LogWarning("Webhook notification failed: {Status}", response.StatusCode);
+        }
+        catch (Exception e)
+        {
+            _logger.LogWarning("Failed to send webhook notification: {Error}", e.Message);
+        }
+    }
+
+    private HttpRequestMessage CreateRequest(string title, string body)
+    {
+        var request = new HttpRequestMessage(HttpMethod.Post, _url);
+        var host = _url.Host.ToLowerInvariant();
+
+        if (host is "discord.com" or "discordapp.com" || host.EndsWith(".discord.com"))
+        {
+            request.Content = Json(new JsonObject { ["content"] = $"**{title}**\n{body}" });
+        }
+        else if (host == "hooks.slack.com")
+        {
+            request.Content = Json(new JsonObject { ["text"] = $"*{title}*\n{body}" });
+        }
+        else
+        {
+            request.Content = new StringContent(body, Encoding.UTF8, "text/plain");
+            request.Headers.Add("Title", title);
+        }
+
+        return request;
+    }
+
+    private static StringContent Json(JsonObject payload) =>
+        new(payload.ToJsonString(), Encoding.UTF8, new MediaTypeHeaderValue("application/json"));
+}
@@ -0,0 +1,41 @@
+using System.Runtime.InteropServices;
+using System.Runtime.Versioning;
+
+namespace KmitlNetAuth.Core.Platform.Windows;
+
+[SupportedOSPlatform("windows")]
+public class WindowsPowerStatus : IPowerStatus
+{
+    private const byte AcOffline = 0;
+    private const byte BatteryFlagNoBattery = 128;
+    private const byte BatteryFlagUnknown = 255;
+    private const byte BatteryPercentUnknown = 255;
+
+    public PowerState? GetPowerState()
+    {
+        if (!GetSystemPowerStatus(out var status))
+            return null;
+
+        if (status.BatteryFlag is BatteryFlagNoBattery or BatteryFlagUnknown)
+            return null;
+
+        return new PowerState(
+            status.ACLineStatus == AcOffline,
+            status.BatteryLifePercent == BatteryPercentUnknown ? null : status.BatteryLifePercent);
+    }
+
+    [StructLayout(LayoutKind.Sequential)]
+    private struct SystemPowerStatus
+    {
+        public byte ACLineStatus;
+        public byte BatteryFlag;
+        public byte BatteryLifePercent;
+        public byte SystemStatusFlag;
+        public int BatteryLifeTime;
+        public int BatteryFullLifeTime;
+    }
+
+    [DllImport("kernel32.dll", SetLastError = true)]
+    [return: MarshalAs(UnmanagedType.Bool)]
+    private static extern bool GetSystemPowerStatus(out SystemPowerStatus status);
+}
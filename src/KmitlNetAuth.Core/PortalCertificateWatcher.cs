@@ -0,0 +1,152 @@
+using System.Security.Cryptography;
+using System.Security.Cryptography.X509Certificates;
+using System.Text.Json;
+using KmitlNetAuth.Core.Exceptions;
+using KmitlNetAuth.Core.Platform;
+using Microsoft.Extensions.Logging;
+
+namespace KmitlNetAuth.Core;
+
+/// <summary>
+/// Trust on first use for the portal's TLS certificate. The SHA-256 fingerprint seen for each portal host is
+/// remembered in a state file, and a different one later is logged and shown as a notification, so a
+/// man-in-the-middle doesn't go unnoticed when <see cref="Config.AcceptInvalidCerts"/> skips verification.
+/// The old fingerprint stays pinned and every connection presenting the new one is warned about until the user
+/// accepts it with <see cref="AcceptPending"/> (<c>kmitlnetauth trust-certificate</c> or the tray menu); the new
+/// one waits in a pending file beside the state file meanwhile. Only the portal, heartbeat and logout hosts are
+/// tracked; the external internet check is served by CDNs that rotate certificates often.
+/// </summary>
+public sealed class PortalCertificateWatcher
+{
+    private readonly Config _config;
+    private readonly INotificationService _notificationService;
+    private readonly ILogger<PortalCertificateWatcher> _logger;
+    private readonly string? _statePath;
+    private readonly Lock _lock = new();
+    private readonly HashSet<string> _expiredWarned = new(StringComparer.OrdinalIgnoreCase);
+    private readonly HashSet<string> _changeNotified = new(StringComparer.OrdinalIgnoreCase);
+    private Dictionary<string, string>? _fingerprints;
+
+    public PortalCertificateWatcher(Config config, INotificationService notificationService, ILogger<PortalCertificateWatcher> logger)
+        : this(config, notificationService, logger, null)
+    {
+    }
+
+    /// <param name="statePath">Where fingerprints are kept; null uses <see cref="ConfigPaths.GetCertificateStatePath"/>.</param>
+    public PortalCertificateWatcher(Config config, INotificationService notificationService, ILogger<PortalCertificateWatcher> logger,
+        string? statePath)
+    {
+        _config = config;
+        _notificationService = notificationService;
+        _logger = logger;
+        _statePath = statePath;
+    }
+
+    /// <summary>Checks the certificate a new connection to <paramref name="host"/> presented. Other hosts are ignored.</summary>
+    public void Observe(string host, X509Certificate? certificate)
+    {
+        if (!_config.WatchCertificate || certificate == null || !IsPortalHost(host))
+            return;
+
+        var fingerprint = certificate.GetCertHashString(HashAlgorithmName.SHA256);
+        lock (_lock)
+        {
+            if (certificate is X509Certificate2 { NotAfter: var notAfter } && notAfter < DateTime.Now && _expiredWarned.Add(fingerprint))
+                _logger.LogWarning("The certificate of {Host} expired on {NotAfter:yyyy-MM-dd}.", host, notAfter);
+
+            var fingerprints = _fingerprints ??= Load(StatePath);
+            // The new one may have been accepted since, from another process
+            if (fingerprints.TryGetValue(host, out var known) && !known.Equals(fingerprint, StringComparison.OrdinalIgnoreCase))
+                fingerprints = _fingerprints = Load(StatePath);
+
+            if (!fingerprints.TryGetValue(host, out known))
+            {
+                _logger.LogInformation("Remembering the certificate of {Host}: SHA-256 {Fingerprint}", host, fingerprint);
+                fingerprints[host] = fingerprint;
+                Save(StatePath, fingerprints);
+                return;
+            }
+
+            if (known.Equals(fingerprint, StringComparison.OrdinalIgnoreCase))
+                return;
+
+            _logger.LogWarning("The certificate of {Host} changed from SHA-256 {Known} to {Fingerprint}. " +
+                "If the portal didn't renew it, someone may be intercepting the connection. " +
+                "Run 'kmitlnetauth trust-certificate' to accept it.", host, known, fingerprint);
+            if (!_changeNotified.Add(fingerprint))
+                return;
+
+            var pending = Load(PendingPath);
+            pending[host] = fingerprint;
+            Save(PendingPath, pending);
+            _notificationService.Show(Messages.Get(_config.Language, MessageKey.CertificateChangedTitle),
+                Messages.Get(_config.Language, MessageKey.CertificateChangedBody, host),
+                new NotificationOptions(NotificationUrgency.Critical, Category: "security.certificate"));
+        }
+    }
+
+    /// <summary>Changed certificates not accepted yet: the new SHA-256 fingerprint by host.</summary>
+    public IReadOnlyDictionary<string, string> GetPending()
+    {
+        lock (_lock)
+            return Load(PendingPath);
+    }
+
+    /// <summary>Pins the pending fingerprint of <paramref name="host"/> in place of the old one. False if none is pending.</summary>
+    public bool AcceptPending(string host)
+    {
+        lock (_lock)
+        {
+            var pending = Load(PendingPath);
+            if (!pending.Remove(host, out var fingerprint))
+                return false;
+
+            var fingerprints = _fingerprints = Load(StatePath);
+            fingerprints[host] = fingerprint;
+            Save(StatePath, fingerprints);
+            Save(PendingPath, pending);
+            _logger.LogInformation("Accepted the new certificate of {Host}: SHA-256 {Fingerprint}", host, fingerprint);
+            return true;
+        }
+    }
+
+    private bool IsPortalHost(string host) =>
+        new[] { _config.PortalUrl, _config.HeartbeatUrl, _config.LogoutUrl }
+            .Any(url => Uri.TryCreate(url, UriKind.Absolute, out var uri)
+                && (uri.IdnHost.Equals(host, StringComparison.OrdinalIgnoreCase) || uri.Host.Equals(host, StringComparison.OrdinalIgnoreCase)));
+
+    private string StatePath => _statePath ?? ConfigPaths.GetCertificateStatePath();
+
+    // certificates.json -> certificates.pending.json
+    private string PendingPath => Path.ChangeExtension(StatePath, ".pending.json");
+
+    private Dictionary<string, string> Load(string path)
+    {
+        try
+        {
+            if (File.Exists(path) && JsonSerializer.Deserialize<Dictionary<string, string>>(File.ReadAllText(path)) is { } stored)
+                return new Dictionary<string, string>(stored, StringComparer.OrdinalIgnoreCase);
+        }
+        catch (Exception e) when (e is IOException or UnauthorizedAccessException or JsonException or ConfigException)
+        {
+            _logger.LogWarning("Could not read remembered certificates: {Error}", e.Message);
+        }
+
+        return new Dictionary<string, string>(StringComparer.OrdinalIgnoreCase);
+    }
+
+    private void Save(string path, Dictionary<string, string> fingerprints)
+    {
+        try
+        {
+            if (Path.GetDirectoryName(path) is { Length: > 0 } dir)
+                Directory.CreateDirectory(dir);
+            File.WriteAllText(path, JsonSerializer.Serialize(fingerprints, new JsonSerializerOptions { WriteIndented = true }));
+        }
+        catch (Exception e) when (e is IOException or UnauthorizedAccessException or ConfigException)
+        {
+            // Still compared in memory for the rest of the run
+            _logger.LogWarning("Could not save remembered certificates: {Error}", e.Message);
+        }
+    }
+}
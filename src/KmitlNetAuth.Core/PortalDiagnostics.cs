@@ -0,0 +1,108 @@
+using System.Net;
+using System.Net.Security;
+using System.Net.Sockets;
+using System.Security.Authentication;
+
+namespace KmitlNetAuth.Core;
+
+/// <summary>The connection step that failed, in the order they run.</summary>
+public enum ReachabilityStage
+{
+    Dns,
+    Tcp,
+    Tls,
+}
+
+/// <param name="FailedStage">The first step that failed; null when the portal is reachable.</param>
+/// <param name="Summary">One line naming the host and what went wrong, for logs and the status file.</param>
+public sealed record PortalDiagnosis(ReachabilityStage? FailedStage, string Summary)
+{
+    public bool Reachable => FailedStage == null;
+}
+
+/// <summary>
+/// Splits an opaque "connection error" into DNS lookup, TCP connect and TLS handshake, and reports
+/// the first one that fails: no DNS usually means "not on the campus network", a refused or timed out
+/// connect a firewall or routing problem, and a failed handshake a certificate or proxy problem.
+/// </summary>
+public sealed class PortalDiagnostics
+{
+    private readonly Func<string, CancellationToken, Task<IPAddress[]>> _resolve;
+
+    public PortalDiagnostics()
+        : this(Dns.GetHostAddressesAsync)
+    {
+    }
+
+    public PortalDiagnostics(Func<string, CancellationToken, Task<IPAddress[]>> resolve)
+    {
+        _resolve = resolve;
+    }
+
+    /// <param name="timeout">Budget for all three steps together.</param>
+    public async Task<PortalDiagnosis> DiagnoseAsync(Uri url, TimeSpan timeout, bool acceptInvalidCerts,
+        CancellationToken ct = default)
+    {
+        using var cts = CancellationTokenSource.CreateLinkedTokenSource(ct);
+        cts.CancelAfter(timeout);
+
+        var host = url.IdnHost;
+        IPAddress[] addresses;
+        try
+        {
+            addresses = IPAddress.TryParse(host, out var literal) ? new[] { literal } : await _resolve(host, cts.Token);
+            if (addresses.Length == 0)
+                return new PortalDiagnosis(ReachabilityStage.Dns, $"DNS lookup of {host} returned no addresses");
+        }
+        catch (Exception e) when (IsStepFailure(e, ct))
+        {
+            return new PortalDiagnosis(ReachabilityStage.Dns, $"DNS lookup of {host} failed: {Describe(e)}");
+        }
+
+        using var socket = new Socket(SocketType.Stream, ProtocolType.Tcp);
+        try
+        {
+            await socket.ConnectAsync(addresses, url.Port, cts.Token);
+        }
+        catch (Exception e) when (IsStepFailure(e, ct))
+        {
+            return new PortalDiagnosis(ReachabilityStage.Tcp, $"TCP connect to {host}:{url.Port} failed: {Describe(e)}");
+        }
+
+        if (url.Scheme != Uri.UriSchemeHttps)
+            return new PortalDiagnosis(null, $"{host}:{url.Port} is reachable");
+
+        var certErrors = SslPolicyErrors.None;
+        try
+        {
+            await using var ssl = new SslStream(new NetworkStream(socket, ownsSocket: false));
+            await ssl.AuthenticateAsClientAsync(new SslClientAuthenticationOptions
+            {
+                TargetHost = host,
+                RemoteCertificateValidationCallback = (_, _, _, errors) =>
+                {
+                    certErrors = errors;
+                    return errors == SslPolicyErrors.None || acceptInvalidCerts;
+                },
+            }, cts.Token);
+        }
+        catch (Exception e) when (e is AuthenticationException or IOException || IsStepFailure(e, ct))
+        {
+            var reason = certErrors != SslPolicyErrors.None ? $"certificate not trusted ({certErrors})" : Describe(e);
+            return new PortalDiagnosis(ReachabilityStage.Tls, $"TLS handshake with {host}:{url.Port} failed: {reason}");
+        }
+
+        return new PortalDiagnosis(null, $"{host}:{url.Port} is reachable (DNS, TCP and TLS OK)");
+    }
+
+    // A timeout of our own budget is a step failure; the caller's token means shutdown and propagates
+    private static bool IsStepFailure(Exception e, CancellationToken ct) =>
+        e is SocketException || (e is OperationCanceledException && !ct.IsCancellationRequested);
+
+    private static string Describe(Exception e) => e switch
+    {
+        OperationCanceledException => "timed out",
+        SocketException se => $"{se.SocketErrorCode} ({se.Message})",
+        _ => e.Message,
+    };
+}
@@ -0,0 +1,105 @@
+using System.Collections.Concurrent;
+using System.Net;
+using System.Net.Sockets;
+using Microsoft.Extensions.Logging;
+
+namespace KmitlNetAuth.Core;
+
+/// <summary>
+/// Resolves the portal hosts once and connects to the cached addresses afterwards, because DNS is often
+/// slow or down right after joining a captive network. Used as the <see cref="SocketsHttpHandler.ConnectCallback"/>,
+/// so only the TCP connect changes: TLS still sees the hostname (SNI and certificate checks are unchanged).
+/// When a cached address stops answering the host is resolved again. Hosts that were never warmed up
+/// (e.g. the external internet check) are resolved normally on every connect.
+/// </summary>
+public sealed class PortalDnsCache
+{
+    private readonly ConcurrentDictionary<string, IPAddress[]> _addresses = new(StringComparer.OrdinalIgnoreCase);
+    private readonly Func<string, CancellationToken, Task<IPAddress[]>> _resolve;
+    private readonly ILogger<PortalDnsCache> _logger;
+
+    public PortalDnsCache(ILogger<PortalDnsCache> logger)
+        : this(logger, Dns.GetHostAddressesAsync)
+    {
+    }
+
+    public PortalDnsCache(ILogger<PortalDnsCache> logger, Func<string, CancellationToken, Task<IPAddress[]>> resolve)
+    {
+        _logger = logger;
+        _resolve = resolve;
+    }
+
+    /// <summary>Resolves the portal, heartbeat and logout hosts of <paramref name="config"/>. Failures are logged, not thrown.</summary>
+    public async Task WarmUpAsync(Config config, CancellationToken ct = default)
+    {
+        var hosts = new[] { config.PortalUrl, config.HeartbeatUrl, config.LogoutUrl }
+            .Select(url => Uri.TryCreate(url, UriKind.Absolute, out var uri) ? uri : null)
+            .Where(uri => uri is { HostNameType: UriHostNameType.Dns })
+            .Select(uri => uri!.IdnHost)
+            .Distinct(StringComparer.OrdinalIgnoreCase);
+
+        foreach (var host in hosts)
+        {
+            try
+            {
+                await ResolveAsync(host, ct);
+            }
+            catch (SocketException e)
+            {
+                _logger.LogWarning("Could not resolve {Host} ahead of time: {Error}", host, e.Message);
+            }
+        }
+    }
+
+    public IReadOnlyList<IPAddress>? GetCached(string host) =>
+        _addresses.TryGetValue(host, out var addresses) ? addresses : null;
+
+    public async ValueTask<Stream> ConnectAsync(SocketsHttpConnectionContext context, CancellationToken ct)
+    {
+        var endpoint = context.DnsEndPoint;
+        if (!_addresses.TryGetValue(endpoint.Host, out var cached))
+            return await ConnectAsync(socket => socket.ConnectAsync(endpoint, ct));
+
+        try
+        {
+            return await ConnectAsync(socket => socket.ConnectAsync(cached, endpoint.Port, ct));
+        }
+        catch (SocketException e)
+        {
+            _logger.LogInformation("Cached address for {Host} failed ({Error}), resolving again", endpoint.Host, e.Message);
+        }
+
+        var fresh = await ResolveAsync(endpoint.Host, ct);
+        return await ConnectAsync(socket => socket.ConnectAsync(fresh, endpoint.Port, ct));
+    }
+
+    private async Task<IPAddress[]> ResolveAsync(string host, CancellationToken ct)
+    {
+        var all = await _resolve(host, ct);
+
+        // Campus networks are IPv4; an AAAA record that can't be routed only adds a connect timeout
+        var ipv4 = all.Where(a => a.AddressFamily == AddressFamily.InterNetwork).ToArray();
+        var addresses = ipv4.Length > 0 ? ipv4 : all;
+        if (addresses.Length == 0)
+            throw new SocketException((int)SocketError.HostNotFound);
+
+        _addresses[host] = addresses;
+        _logger.LogInformation("Resolved {Host} to {Addresses}", host, string.Join(", ", addresses.Select(a => a.ToString())));
+        return addresses;
+    }
+
+    private static async ValueTask<Stream> ConnectAsync(Func<Socket, ValueTask> connect)
+    {
+        var socket = new Socket(SocketType.Stream, ProtocolType.Tcp) { NoDelay = true };
+        try
+        {
+            await connect(socket);
+            return new NetworkStream(socket, ownsSocket: true);
+        }
+        catch
+        {
+            socket.Dispose();
+            throw;
+        }
+    }
+}
@@ -0,0 +1,44 @@
+using System.Globalization;
+using System.Text.RegularExpressions;
+
+namespace KmitlNetAuth.Core;
+
+/// <summary>
+/// A login or heartbeat response as the portal sent it, kept so a changed response format can be
+/// diagnosed without a packet capture. The body is truncated to <see cref="MaxBodyLength"/> characters
+/// and passwords and tokens in it are redacted.
+/// </summary>
+public sealed partial record PortalResponse(
+    string Request,
+    DateTimeOffset ReceivedAt,
+    int StatusCode,
+    string? ContentType,
+    string Body)
+{
+    public const int MaxBodyLength = 4096;
+
+    private const string Redacted = "<redacted>";
+
+    // "password": "...", userPass=..., token: '...' and similar in JSON, form-encoded or script bodies
+    [GeneratedRegex(@"(?<key>[""']?\b\w*(?:pass(?:word)?|token|secret|session)\w*[""']?\s*[:=]\s*)(?<quote>[""']?)(?<value>[^""'&,\s}]*)", RegexOptions.IgnoreCase)]
+    private static partial Regex SecretFieldRegex();
+
+    /// <summary>Builds a response with <paramref name="body"/> redacted (including a literal <paramref name="password"/>) and truncated.</summary>
+    public static PortalResponse Capture(string request, int statusCode, string? contentType, string body, string? password = null)
+    {
+        if (!string.IsNullOrEmpty(password))
+            body = body.Replace(password, Redacted, StringComparison.Ordinal);
+        body = SecretFieldRegex().Replace(body, m =>
+            m.Groups["value"].Length == 0 ? m.Value : $"{m.Groups["key"].Value}{m.Groups["quote"].Value}{Redacted}");
+
+        if (body.Length > MaxBodyLength)
+            body = $"{body[..MaxBodyLength]}\n... ({body.Length - MaxBodyLength} more characters truncated)";
+
+        return new PortalResponse(request, DateTimeOffset.Now, statusCode, contentType, body);
+    }
+
+    /// <summary>A header line followed by the body, as shown on the Debug page and written to the debug file.</summary>
+    public override string ToString() =>
+        string.Create(CultureInfo.InvariantCulture,
+            $"{Request} response at {ReceivedAt:yyyy-MM-dd HH:mm:ss zzz}: HTTP {StatusCode} ({ContentType ?? "no content type"})\n\n{Body}");
+}
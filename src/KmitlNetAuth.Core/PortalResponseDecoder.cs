@@ -0,0 +1,77 @@
+using System.Text;
+using System.Text.RegularExpressions;
+
+namespace KmitlNetAuth.Core;
+
+/// <summary>
+/// Decodes portal response bodies in the charset they were sent in. The Thai portal pages can be TIS-620
+/// rather than UTF-8, and decoding those as UTF-8 garbles the messages in the log and stops Thai keywords
+/// from matching. The charset comes from a byte order mark, the <c>Content-Type</c> header or, for HTML
+/// that doesn't declare it in the header, a <c>&lt;meta&gt;</c> tag, and falls back to UTF-8.
+/// </summary>
+public static partial class PortalResponseDecoder
+{
+    // A meta charset has to appear within the first 1024 bytes of the document to count
+    private const int MetaScanLength = 1024;
+
+    [GeneratedRegex("""<meta[^>]+charset\s*=\s*["']?(?<charset>[\w.:-]+)""", RegexOptions.IgnoreCase)]
+    private static partial Regex MetaCharsetRegex();
+
+    static PortalResponseDecoder()
+    {
+        // windows-874 (TIS-620) and the other legacy code pages aren't available without this
+        Encoding.RegisterProvider(CodePagesEncodingProvider.Instance);
+    }
+
+    public static async Task<string> ReadAsync(HttpContent content, CancellationToken ct = default)
+    {
+        var body = await content.ReadAsByteArrayAsync(ct);
+        return Decode(body, content.Headers.ContentType?.CharSet);
+    }
+
+    /// <param name="charset">From the <c>Content-Type</c> header, if it had one.</param>
+    public static string Decode(byte[] body, string? charset)
+    {
+        var encoding = DetectFromBom(body) ?? GetEncoding(charset) ?? GetEncoding(FindMetaCharset(body)) ?? Encoding.UTF8;
+        var preamble = encoding.Preamble;
+        var skip = preamble.Length > 0 && body.AsSpan().StartsWith(preamble) ? preamble.Length : 0;
+        return encoding.GetString(body, skip, body.Length - skip);
+    }
+
+    private static Encoding? DetectFromBom(byte[] body) =>
+        body.AsSpan().StartsWith(Encoding.UTF8.Preamble) ? Encoding.UTF8
+        : body.AsSpan().StartsWith(Encoding.Unicode.Preamble) ? Encoding.Unicode
+        : body.AsSpan().StartsWith(Encoding.BigEndianUnicode.Preamble) ? Encoding.BigEndianUnicode
+        : null;
+
+    private static string? FindMetaCharset(byte[] body)
+    {
+        // The declaration itself is ASCII in every charset the portal could use
+        var head = Encoding.Latin1.GetString(body, 0, Math.Min(body.Length, MetaScanLength));
+        var match = MetaCharsetRegex().Match(head);
+        return match.Success ? match.Groups["charset"].Value : null;
+    }
+
+    /// <summary>Null for a missing or unknown charset, so the next source is tried.</summary>
+    private static Encoding? GetEncoding(string? charset)
+    {
+        if (string.IsNullOrWhiteSpace(charset))
+            return null;
+
+        var name = charset.Trim().Trim('"', '\'');
+        // windows-874 is TIS-620 plus a few punctuation marks, and is what browsers use for all three
+        if (name.Equals("tis-620", StringComparison.OrdinalIgnoreCase)
+            || name.Equals("tis620", StringComparison.OrdinalIgnoreCase)
+            || name.Equals("iso-8859-11", StringComparison.OrdinalIgnoreCase))
+            name = "windows-874";
+
+        try
+        {
+            return Encoding.GetEncoding(name);
+        }
+        catch (ArgumentException)
+        {
+            return null;
+        }
+    }
+}
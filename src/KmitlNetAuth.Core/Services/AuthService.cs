@@ -1,3 +1,5 @@
+using System.Diagnostics;
+using System.Globalization;
 using KmitlNetAuth.Core.Platform;
 using Microsoft.Extensions.Logging;
 
@@ -5,64 +7,258 @@ namespace KmitlNetAuth.Core.Services;
 
 public sealed class AuthService : IAuthService
 {
+    private static readonly TimeSpan LogoutTimeout = TimeSpan.FromSeconds(5);
+
+    // Used when the portal reports a lockout without saying how long it lasts
+    private static readonly TimeSpan DefaultLockoutWait = TimeSpan.FromMinutes(15);
+
+    // Lets DHCP and the portal redirect settle after a roam before checking
+    private static readonly TimeSpan NetworkSettleDelay = TimeSpan.FromSeconds(2);
+
+    /// <summary>Pause between the logout and the login of <see cref="ReloginAsync"/>, for the portal to drop the old session.</summary>
+    public static readonly TimeSpan ReloginDelay = TimeSpan.FromSeconds(2);
+
+    // Gives the portal a moment to open the firewall before checking that a login took effect
+    private static readonly TimeSpan PostLoginCheckDelay = TimeSpan.FromSeconds(1);
+
+    // Polls for an interface to come back this often while none is up; each poll is only a local query
+    private static readonly TimeSpan NetworkDownPollInterval = TimeSpan.FromSeconds(5);
+
+    // While paused the loop waits for Pause()/Resume(); this only catches auto_login switched back on in
+    // the config directly (the settings page), which doesn't signal
+    private static readonly TimeSpan PausedPollInterval = TimeSpan.FromSeconds(60);
+
+    // Waits are split into slices this long so a resume from sleep is noticed within one slice
+    private static readonly TimeSpan SleepCheckSlice = TimeSpan.FromSeconds(10);
+
+    /// <summary>Shortest wait between checks, whatever the config says, so a zero interval can't spin the loop.</summary>
+    public static readonly TimeSpan MinimumInterval = TimeSpan.FromSeconds(1);
+
+    /// <summary>How much longer than asked a wait may take before it is taken as the machine having slept.</summary>
+    public static readonly TimeSpan SleepGapThreshold = TimeSpan.FromSeconds(30);
+
     private readonly AuthClient _authClient;
     private readonly Config _config;
     private readonly INotificationService _notificationService;
+    private readonly IPowerStatus? _powerStatus;
+    private readonly INetworkChangeMonitor? _networkChangeMonitor;
+    private readonly StatusFileWriter? _statusFileWriter;
+    private readonly PortalDnsCache? _dnsCache;
+    private readonly ControlApiServer? _controlApi;
+    private readonly INetworkInfo? _networkInfo;
     private readonly ILogger<AuthService> _logger;
+    private bool _batterySlowdown;
+    private bool _intervalFloorWarned;
+    private bool _waitingForNetwork;
+    private DateTimeOffset? _lockedUntil;
+    private CancellationTokenSource? _wakeCts;
+    private readonly SemaphoreSlim _pauseChanged = new(0, 1);
+
+    // Keeps a manual login or re-login from interleaving with the loop's, e.g. logging in between a relogin's two halves
+    private readonly SemaphoreSlim _loginLock = new(1, 1);
+    private readonly EventHistory _events = new();
+    private readonly IpAddressTracker _ipAddress = new();
 
     public AuthStatus CurrentStatus { get; private set; } = AuthStatus.Offline;
+    public string? StatusReason { get; private set; }
+    public DateTimeOffset? NextCheckAt { get; private set; }
+    public TimeSpan NextCheckDelay { get; private set; }
+    public bool IsPaused { get; private set; }
+    public DateTimeOffset? ResumeAt { get; private set; }
+
+    /// <summary>The summary of the last completed cycle; null until one completes.</summary>
+    public TickOutcome? LastTick { get; private set; }
+
+    public IReadOnlyList<ServiceEvent> RecentEvents => _events.Snapshot();
     public event EventHandler<AuthStatusChangedEventArgs>? StatusChanged;
 
     public AuthService(
         AuthClient authClient,
         Config config,
         INotificationService notificationService,
-        ILogger<AuthService> logger)
+        ILogger<AuthService> logger,
+        IPowerStatus? powerStatus = null,
+        INetworkChangeMonitor? networkChangeMonitor = null,
+        StatusFileWriter? statusFileWriter = null,
+        PortalDnsCache? dnsCache = null,
+        ControlApiServer? controlApi = null,
+        INetworkInfo? networkInfo = null)
     {
         _authClient = authClient;
         _config = config;
         _notificationService = notificationService;
         _logger = logger;
+        _powerStatus = powerStatus;
+        _networkChangeMonitor = networkChangeMonitor;
+        _statusFileWriter = statusFileWriter;
+        _dnsCache = dnsCache;
+        _controlApi = controlApi;
+        _networkInfo = networkInfo;
     }
 
     public async Task RunAsync(CancellationToken ct)
+    {
+        if (_config.CacheDns && _dnsCache != null)
+            await _dnsCache.WarmUpAsync(_config, ct);
+
+        if (_networkChangeMonitor != null)
+            _networkChangeMonitor.NetworkChanged += OnNetworkChanged;
+        _authClient.RetryRequested += OnRetryRequested;
+
+        // Own token so the writer also stops if the loop fails
+        using var statusFileCts = CancellationTokenSource.CreateLinkedTokenSource(ct);
+        var statusFileTask = _statusFileWriter?.RunAsync(this, statusFileCts.Token) ?? Task.CompletedTask;
+        using var controlApiCts = CancellationTokenSource.CreateLinkedTokenSource(ct);
+        var controlApiTask = _controlApi?.RunAsync(this, controlApiCts.Token) ?? Task.CompletedTask;
+
+        try
+        {
+            await RunLoopAsync(ct);
+        }
+        finally
+        {
+            if (_networkChangeMonitor != null)
+                _networkChangeMonitor.NetworkChanged -= OnNetworkChanged;
+            _authClient.RetryRequested -= OnRetryRequested;
+
+            // Free the portal session on graceful shutdown instead of leaving it to time out
+            var loggedOut = ct.IsCancellationRequested && _config.LogoutOnExit && !_config.MonitorOnly
+                && await LogoutOnExitAsync();
+            if (ct.IsCancellationRequested)
+                LogShutdownSummary(loggedOut);
+
+            statusFileCts.Cancel();
+            await statusFileTask;
+            controlApiCts.Cancel();
+            await controlApiTask;
+        }
+    }
+
+    private async Task RunLoopAsync(CancellationToken ct)
     {
         var loginAttempts = 0u;
+        var loginTimeouts = 0;
+        var heartbeatFailures = 0;
+        DateTimeOffset? heartbeatedLoginAt = null;
         var maxAttempts = _config.MaxAttempt;
         var wasConnected = true;
+        var cycle = 0L;
 
         _logger.LogInformation("Auth service started. Username: {Username}, Interval: {Interval}s",
             _config.Username, _config.Interval);
+        if (_config.MonitorOnly)
+            _logger.LogInformation("Monitor-only mode: connectivity is watched but login is never attempted.");
+        if (_config.IsIntervalUnusuallyLong)
+            _logger.LogWarning("Interval is {Interval}s ({Minutes:F0} min): a lost connection can go unnoticed that long. " +
+                "Lower [service] interval if that isn't intended.", _config.Interval, _config.Interval / 60.0);
+
+        // Connect right away at boot instead of waiting for the first check to notice we're offline
+        if (_config.LoginOnStart && _config.AutoLogin && !IsPaused && !_config.MonitorOnly && IsNetworkUp())
+        {
+            _logger.LogInformation("Logging in on start...");
+            SetStatus(AuthStatus.Connecting);
+            await LoginAsync(ct);
+        }
 
         while (!ct.IsCancellationRequested)
         {
-            if (!_config.AutoLogin)
+            // Every line logged during this iteration, including the client's requests, carries the cycle id
+            using var cycleScope = _logger.BeginScope(new Dictionary<string, object> { ["Cycle"] = ++cycle });
+
+            if (IsPaused || !_config.AutoLogin)
+            {
+                if (IsPaused && ResumeAt is { } resumeAt && DateTimeOffset.Now >= resumeAt)
+                {
+                    _logger.LogInformation("Timed pause ended, resuming auto-login.");
+                    Resume();
+                    continue;
+                }
+
+                // Monitor-only never logs in, so a pause there would only stop the monitoring: keep
+                // checking and notifying, and let the timed resume above end the pause as usual
+                if (!_config.MonitorOnly)
+                {
+                    SetStatus(AuthStatus.Paused);
+                    await WaitForResumeAsync(ct);
+                    continue;
+                }
+            }
+
+            if (!IsNetworkUp())
             {
-                SetStatus(AuthStatus.Paused);
-                await Task.Delay(TimeSpan.FromSeconds(5), ct);
+                await WaitAsync(NetworkDownPollInterval, ct);
                 continue;
             }
 
-            SetStatus(AuthStatus.Connecting);
-            var hasInternet = await _authClient.CheckInternetAsync(ct);
+            NoteIpAddressChange();
+
+            // NeedsAttention stays visible until a cycle comes back online,
+            // instead of being overwritten by Connecting/Offline every interval.
+            var needsAttention = CurrentStatus == AuthStatus.NeedsAttention;
+            if (!needsAttention)
+                SetStatus(AuthStatus.Connecting);
+
+            var stats = _authClient.Stats;
+            var (heartbeatsBefore, heartbeatFailuresBefore) = (stats.Heartbeats, stats.HeartbeatFailures);
+            var (loginsBefore, loginFailuresBefore) = (stats.Logins, stats.LoginFailures);
+
+            var checkStarted = Stopwatch.GetTimestamp();
+            var hasInternet = await _authClient.CheckConnectivityAsync(ct);
+            var checkLatency = Stopwatch.GetElapsedTime(checkStarted);
+            stats.RecordConnectivity(hasInternet);
 
             if (hasInternet)
             {
                 if (!wasConnected)
                 {
                     _logger.LogInformation("Internet connection restored.");
-                    _notificationService.Show("Connected", "Internet connection is active.");
+                    _notificationService.Show(Messages.Get(_config.Language, MessageKey.ConnectedTitle),
+                        Messages.Get(_config.Language, MessageKey.ConnectedBody),
+                        new NotificationOptions(Category: "network.connected"));
                     wasConnected = true;
                 }
 
                 loginAttempts = 0;
+                loginTimeouts = 0;
                 SetStatus(AuthStatus.Online);
 
-                var heartbeatOk = await _authClient.HeartbeatAsync(ct);
-                if (!heartbeatOk)
+                // The portal can take a moment to activate a new session, so the first heartbeat after
+                // a login waits out the grace period and a failure of it doesn't count towards a re-login
+                var lastLoginAt = stats.LastLoginAt;
+                var firstAfterLogin = lastLoginAt != null && lastLoginAt != heartbeatedLoginAt;
+                heartbeatedLoginAt = lastLoginAt;
+
+                // With heartbeat_only the connectivity check was the heartbeat
+                var heartbeatOk = _config.ConnectivityCheck.Equals("heartbeat_only", StringComparison.OrdinalIgnoreCase)
+                    || await HeartbeatAsync(firstAfterLogin ? lastLoginAt : null, ct);
+                if (heartbeatOk)
+                    heartbeatFailures = 0;
+                else if (!firstAfterLogin)
+                    heartbeatFailures++;
+
+                if (!heartbeatOk && _config.MonitorOnly)
+                {
+                    _logger.LogWarning("Heartbeat failed (monitor-only, not logging in).");
+                }
+                else if (!heartbeatOk && firstAfterLogin)
+                {
+                    _logger.LogInformation("First heartbeat after login failed; not counting it while the session settles.");
+                }
+                else if (!heartbeatOk && GetLockoutRemaining() is { } remaining)
+                {
+                    _logger.LogWarning("Heartbeat failed, but the account is locked for {Remaining:F0}s more; not logging in.",
+                        remaining.TotalSeconds);
+                }
+                else if (!heartbeatOk && heartbeatFailures < _config.HeartbeatFailureThreshold)
                 {
-                    _logger.LogInformation("Heartbeat failed, attempting login...");
-                    await _authClient.LoginAsync(ct);
+                    _logger.LogWarning("Heartbeat failed ({Failures}/{Threshold}), will re-login if it keeps failing.",
+                        heartbeatFailures, _config.HeartbeatFailureThreshold);
+                }
+                else if (!heartbeatOk)
+                {
+                    _logger.LogInformation("Heartbeat failed {Failures} times in a row, attempting login...", heartbeatFailures);
+                    heartbeatFailures = 0;
+                    await LoginAsync(ct);
                 }
             }
             else
@@ -70,37 +266,444 @@ public sealed class AuthService : IAuthService
                 if (wasConnected)
                 {
                     _logger.LogWarning("Internet connection lost.");
-                    _notificationService.Show("Disconnected", "Internet connection lost. Attempting to reconnect...");
+                    stats.RecordDisconnect();
+                    _notificationService.Show(Messages.Get(_config.Language, MessageKey.DisconnectedTitle),
+                        Messages.Get(_config.Language, MessageKey.DisconnectedBody),
+                        new NotificationOptions(NotificationUrgency.Critical, "network.disconnected", () => _ = LoginAsync()));
                     wasConnected = false;
                 }
 
-                SetStatus(AuthStatus.Offline);
-                _logger.LogWarning("No internet connection. Attempting login...");
-
-                if (loginAttempts < maxAttempts)
+                if (_config.MonitorOnly)
+                {
+                    SetStatus(AuthStatus.Offline);
+                    _logger.LogWarning("No internet connection (monitor-only, not logging in).");
+                }
+                else if (GetLockoutRemaining() is { } remaining)
+                {
+                    // Every attempt during a lockout can extend it, so sit it out entirely
+                    _logger.LogWarning("Account locked. Not logging in for {Remaining:F0}s...", remaining.TotalSeconds);
+                    await WaitAsync(remaining, ct, wakeOnNetworkChange: false);
+                    _lockedUntil = null;
+                    await LoginAsync(ct);
+                }
+                else if (needsAttention)
                 {
-                    await _authClient.LoginAsync(ct);
+                    // Retrying rejected credentials every interval only risks a lockout.
+                    _logger.LogWarning("Needs attention: {Reason}. Retrying in {Backoff}s...",
+                        StatusReason, _config.BackoffInterval);
+                    await WaitAsync(TimeSpan.FromSeconds(_config.BackoffInterval), ct, wakeOnNetworkChange: false);
+                    await LoginAsync(ct);
+                }
+                else if (loginAttempts < maxAttempts)
+                {
+                    SetStatus(AuthStatus.Offline);
+                    _logger.LogWarning("No internet connection. Attempting login...");
+                    var result = await LoginAsync(ct);
                     loginAttempts++;
+                    loginTimeouts = result.Failure == LoginFailure.Timeout ? loginTimeouts + 1 : 0;
                 }
                 else
                 {
+                    SetStatus(AuthStatus.Offline);
                     _logger.LogError("Max login attempts reached. Waiting...");
-                    await Task.Delay(TimeSpan.FromSeconds(_config.BackoffInterval), ct);
+                    await WaitAsync(TimeSpan.FromSeconds(_config.BackoffInterval), ct);
                     loginAttempts = 0;
                 }
             }
 
-            await Task.Delay(TimeSpan.FromSeconds(_config.Interval), ct);
+            // Only while online: when we're offline the portal is busy enough with our own login
+            if (hasInternet && !_config.MonitorOnly)
+            {
+                foreach (var mac in _config.AdditionalMacs)
+                    await _authClient.LoginDeviceAsync(mac, ct);
+            }
+
+            LastTick = new TickOutcome(cycle, hasInternet,
+                TickOutcome.FromCounts(stats.Heartbeats - heartbeatsBefore, stats.HeartbeatFailures - heartbeatFailuresBefore),
+                TickOutcome.FromCounts(stats.Logins - loginsBefore, stats.LoginFailures - loginFailuresBefore),
+                checkLatency);
+            LogTick(LastTick);
+            _events.Add("cycle", LastTick.ToString());
+
+            await WaitAsync(ApplyIntervalFloor(GetNextWait(loginTimeouts)), ct);
+        }
+    }
+
+    /// <summary>
+    /// The interval, or sooner after a login timed out: a slow or congested network is worth retrying
+    /// before the next regular check, every <c>backoff_interval</c> at first and twice as long after each
+    /// further timeout. A refused connection or a rejection waits the full interval, since retrying
+    /// sooner wouldn't change the answer.
+    /// </summary>
+    private TimeSpan GetNextWait(int loginTimeouts)
+    {
+        var interval = GetEffectiveInterval();
+        if (loginTimeouts == 0)
+            return interval;
+
+        var backoff = TimeSpan.FromSeconds(_config.BackoffInterval * Math.Pow(2, Math.Min(loginTimeouts - 1, 16)));
+        if (backoff >= interval)
+            return interval;
+
+        _logger.LogInformation("Login timed out ({Count} in a row); retrying in {Backoff:F0}s.",
+            loginTimeouts, backoff.TotalSeconds);
+        return backoff;
+    }
+
+    /// <summary>
+    /// False while no interface is up (WiFi off, cable out). The loop then waits quietly instead of
+    /// making login attempts that can only fail with a connect error, logging once on entry and exit.
+    /// </summary>
+    private bool IsNetworkUp()
+    {
+        var up = _networkInfo?.IsNetworkUp(_config.Interface) ?? true;
+        if (up == !_waitingForNetwork)
+            return up;
+
+        _waitingForNetwork = !up;
+        if (up)
+        {
+            _logger.LogInformation("Network interface is up, resuming checks.");
+        }
+        else
+        {
+            _logger.LogInformation("No network interface is up; waiting for one before checking again.");
+            SetStatus(AuthStatus.Offline, "Waiting for network");
+        }
+
+        return up;
+    }
+
+    /// <summary>
+    /// Logs a change of the local IP address. The check that follows in the same cycle decides what to do:
+    /// a session that survived the change keeps heartbeating, and one the portal dropped shows up as the
+    /// captive portal and is logged in like any other.
+    /// </summary>
+    private void NoteIpAddressChange()
+    {
+        if (_networkInfo != null && _ipAddress.Update(_networkInfo.GetIpAddress(_config.Interface), out var previous))
+            _logger.LogInformation("Local IP address changed from {Previous} to {Current}, checking connectivity now.",
+                previous, _ipAddress.Current);
+    }
+
+    // The line already carries the cycle id from the log scope
+    private void LogTick(TickOutcome tick) =>
+        _logger.LogInformation("Cycle done: online={Online} heartbeat={Heartbeat} login={Login} latency={LatencyMs:F0}ms",
+            tick.Online, TickOutcome.Format(tick.Heartbeat), TickOutcome.Format(tick.Login), tick.Latency.TotalMilliseconds);
+
+    // Validation rejects interval = 0 on load, but the settings page and Config objects built in code skip it
+    private TimeSpan ApplyIntervalFloor(TimeSpan interval)
+    {
+        if (interval >= MinimumInterval)
+            return interval;
+
+        if (!_intervalFloorWarned)
+        {
+            _intervalFloorWarned = true;
+            _logger.LogWarning("Interval of {Interval}s is below the minimum; waiting {Minimum}s between checks instead.",
+                interval.TotalSeconds, MinimumInterval.TotalSeconds);
         }
+
+        return MinimumInterval;
     }
 
-    private void SetStatus(AuthStatus newStatus)
+    public void Pause(TimeSpan? duration = null)
     {
-        if (CurrentStatus == newStatus)
+        ResumeAt = duration is { } d ? DateTimeOffset.Now + d : null;
+        IsPaused = true;
+
+        if (ResumeAt is { } resumeAt)
+            _logger.LogInformation("Auto-login paused until {ResumeAt:HH:mm}.", resumeAt);
+        else
+            _logger.LogInformation("Auto-login paused.");
+        SignalPauseChanged();
+    }
+
+    public void Resume()
+    {
+        ResumeAt = null;
+        IsPaused = false;
+        _logger.LogInformation("Auto-login resumed.");
+        SignalPauseChanged();
+    }
+
+    // Wakes a paused loop to look at the pause again: resumed, or paused anew with another end time
+    private void SignalPauseChanged()
+    {
+        if (_pauseChanged.CurrentCount > 0)
+            return;
+        try { _pauseChanged.Release(); }
+        catch (SemaphoreFullException) { }
+    }
+
+    // Idle until the pause changes, a timed pause ends, or the fallback poll; no requests are sent meanwhile
+    private async Task WaitForResumeAsync(CancellationToken ct)
+    {
+        var delay = PausedPollInterval;
+        if (ResumeAt - DateTimeOffset.Now is { } untilResume && untilResume < delay)
+            delay = untilResume > TimeSpan.Zero ? untilResume : TimeSpan.Zero;
+
+        await _pauseChanged.WaitAsync(delay, ct);
+    }
+
+    private async Task<bool> LogoutOnExitAsync()
+    {
+        // The run token is already cancelled; bound the logout so a hung portal can't block exit
+        using var cts = new CancellationTokenSource(LogoutTimeout);
+        try
+        {
+            if (!await _authClient.LogoutAsync(cts.Token))
+                return false;
+            _logger.LogInformation("Logged out of the portal on exit.");
+            return true;
+        }
+        catch (OperationCanceledException)
+        {
+            _logger.LogWarning("Logout on exit timed out after {Timeout}s.", LogoutTimeout.TotalSeconds);
+            return false;
+        }
+    }
+
+    private void LogShutdownSummary(bool loggedOut)
+    {
+        var summary = _authClient.Stats.Summarize(loggedOut);
+        _logger.LogInformation(
+            "Auth service stopped after {Uptime}: {Logins} login(s), {Heartbeats} heartbeat(s) ({HeartbeatSuccessRate} succeeded), logged out: {LoggedOut}",
+            summary.Uptime.ToString(@"d\.hh\:mm\:ss"), summary.Logins, summary.Heartbeats,
+            summary.HeartbeatSuccessRate is { } rate ? rate.ToString("P0", CultureInfo.InvariantCulture) : "n/a",
+            summary.LoggedOut ? "yes" : "no");
+
+        // The writer's last write, after it's cancelled below, picks this up
+        if (_statusFileWriter != null)
+            _statusFileWriter.Shutdown = summary;
+    }
+
+    /// <summary>
+    /// Waits <paramref name="delay"/>, or less if <see cref="Config.RecheckOnNetworkChange"/> is on and
+    /// the network changes meanwhile. Lockout and rejected-credential waits pass
+    /// <paramref name="wakeOnNetworkChange"/> false, since cutting those short only risks a (longer) lockout.
+    /// </summary>
+    private async Task WaitAsync(TimeSpan delay, CancellationToken ct, bool wakeOnNetworkChange = true)
+    {
+        using var wake = CancellationTokenSource.CreateLinkedTokenSource(ct);
+        if (wakeOnNetworkChange)
+            Volatile.Write(ref _wakeCts, wake);
+
+        NextCheckDelay = delay;
+        NextCheckAt = DateTimeOffset.Now + delay;
+        try
+        {
+            var remaining = delay;
+            while (remaining > TimeSpan.Zero)
+            {
+                var slice = remaining < SleepCheckSlice ? remaining : SleepCheckSlice;
+                var started = DateTimeOffset.UtcNow;
+                await Task.Delay(slice, wake.Token);
+                var elapsed = DateTimeOffset.UtcNow - started;
+
+                // After a sleep the session is usually gone; check now rather than sit out the rest of
+                // the interval. Lockout waits keep going, counting the sleep towards the lockout.
+                if (DetectSleep(slice, elapsed) is { } slept && wakeOnNetworkChange)
+                {
+                    _logger.LogInformation("Resumed from sleep ({Slept:F0}s), checking connectivity now.", slept.TotalSeconds);
+                    await Task.Delay(NetworkSettleDelay, ct);
+                    return;
+                }
+
+                remaining -= elapsed > slice ? elapsed : slice;
+            }
+        }
+        catch (OperationCanceledException) when (!ct.IsCancellationRequested)
+        {
+            _logger.LogInformation("Network changed, checking connectivity now.");
+            await Task.Delay(NetworkSettleDelay, ct);
+        }
+        finally
+        {
+            Volatile.Write(ref _wakeCts, null);
+            NextCheckAt = null;
+        }
+    }
+
+    /// <summary>
+    /// How long the machine slept during a wait of <paramref name="expected"/> that took <paramref name="actual"/>
+    /// wall-clock time, or null if the difference is within <see cref="SleepGapThreshold"/>. Timers don't run
+    /// while suspended, so a wait ending far later than asked means the machine was asleep.
+    /// </summary>
+    public static TimeSpan? DetectSleep(TimeSpan expected, TimeSpan actual)
+    {
+        var gap = actual - expected;
+        return gap > SleepGapThreshold ? gap : null;
+    }
+
+    private void OnNetworkChanged(object? sender, EventArgs e)
+    {
+        // A new address is worth checking straight away even with recheck_on_network_change off
+        if (!_config.RecheckOnNetworkChange && !_ipAddress.IsChange(_networkInfo?.GetIpAddress(_config.Interface)))
+            return;
+
+        try
+        {
+            Volatile.Read(ref _wakeCts)?.Cancel();
+        }
+        catch (ObjectDisposedException)
+        {
+            // The wait ended on its own just now
+        }
+    }
+
+    /// <summary>
+    /// The configured interval, stretched by <see cref="Config.BatteryIntervalMultiplier"/>
+    /// while on battery below the threshold so laptops don't wake the radio as often.
+    /// </summary>
+    public TimeSpan GetEffectiveInterval()
+    {
+        var interval = TimeSpan.FromSeconds(_config.Interval);
+        if (_config.BatteryIntervalMultiplier <= 1 || _powerStatus == null)
+            return interval;
+
+        var state = _powerStatus.GetPowerState();
+        var slowdown = state is { OnBattery: true, ChargePercent: { } charge } && charge < _config.BatteryThresholdPercent;
+
+        if (slowdown != _batterySlowdown)
+        {
+            _batterySlowdown = slowdown;
+            if (slowdown)
+                _logger.LogInformation("On battery ({Charge}%), polling every {Interval}s.",
+                    state?.ChargePercent, _config.Interval * (ulong)_config.BatteryIntervalMultiplier);
+            else
+                _logger.LogInformation("Battery slowdown lifted, polling every {Interval}s.", _config.Interval);
+        }
+
+        return slowdown ? interval * _config.BatteryIntervalMultiplier : interval;
+    }
+
+    /// <summary>
+    /// Logs in the way the run loop does: the success notification only shows once a connectivity check
+    /// confirms the login got through, and a rejection or lockout updates the status.
+    /// </summary>
+    public async Task<LoginResult> LoginAsync(CancellationToken ct = default)
+    {
+        LoginResult result;
+        await _loginLock.WaitAsync(ct);
+        try
+        {
+            result = await LoginCoreAsync(ct);
+        }
+        finally
+        {
+            _loginLock.Release();
+        }
+
+        // Outside the lock: probing an unreachable portal can take the whole timeout
+        return await _authClient.DiagnoseFailureAsync(result, ct);
+    }
+
+    public async Task<ReloginResult> ReloginAsync(CancellationToken ct = default)
+    {
+        ReloginResult result;
+        await _loginLock.WaitAsync(ct);
+        try
+        {
+            bool? loggedOut = null;
+            if (string.IsNullOrEmpty(_config.LogoutUrl))
+            {
+                _logger.LogInformation("Re-login: no logout_url is configured, logging in without logging out first.");
+            }
+            else
+            {
+                loggedOut = await _authClient.LogoutAsync(ct);
+                await Task.Delay(ReloginDelay, ct);
+            }
+
+            result = new ReloginResult(loggedOut, await LoginCoreAsync(ct));
+        }
+        finally
+        {
+            _loginLock.Release();
+        }
+
+        return result with { Login = await _authClient.DiagnoseFailureAsync(result.Login, ct) };
+    }
+
+    private async Task<LoginResult> LoginCoreAsync(CancellationToken ct)
+    {
+        var result = await _authClient.AttemptLoginAsync(ct);
+        if (result.Success)
+        {
+            if (!await ConfirmLoginAsync(ct))
+                return LoginResult.Failed(LoginFailure.UnexpectedResponse, "Login reported success, but there is still no internet connection");
+
+            _notificationService.Show(Messages.Get(_config.Language, MessageKey.LoginSuccessTitle),
+                Messages.Get(_config.Language, MessageKey.LoginSuccessBody, _config.Username ?? ""));
+        }
+        else if (result.Failure is LoginFailure.Rejected or LoginFailure.MissingCredentials or LoginFailure.SessionLimit)
+        {
+            SetStatus(AuthStatus.NeedsAttention, result.Message);
+        }
+        else if (result.Failure == LoginFailure.AccountLocked)
+        {
+            var wait = result.RetryAfter ?? DefaultLockoutWait;
+            _lockedUntil = DateTimeOffset.Now + wait;
+            SetStatus(AuthStatus.NeedsAttention, $"Account locked: {result.Message} (retrying after {_lockedUntil:HH:mm})");
+        }
+
+        return result;
+    }
+
+    private async Task<bool> HeartbeatAsync(DateTimeOffset? afterLoginAt, CancellationToken ct)
+    {
+        if (afterLoginAt is { } loginAt && loginAt + TimeSpan.FromSeconds(_config.PostLoginGrace) - DateTimeOffset.Now is { } grace
+            && grace > TimeSpan.Zero)
+        {
+            _logger.LogDebug("Waiting {Grace:F1}s after login before the first heartbeat", grace.TotalSeconds);
+            await Task.Delay(grace, ct);
+        }
+
+        return await _authClient.HeartbeatAsync(ct);
+    }
+
+    /// <summary>
+    /// The portal can answer a login with 200 without letting the client through. Checking again
+    /// right away makes such a login count as a failure (and towards max_attempt, then the backoff)
+    /// instead of passing for success until the next interval.
+    /// </summary>
+    private async Task<bool> ConfirmLoginAsync(CancellationToken ct)
+    {
+        await Task.Delay(PostLoginCheckDelay, ct);
+        var online = await _authClient.CheckConnectivityAsync(ct);
+        _authClient.Stats.RecordConnectivity(online);
+        if (online)
+            return true;
+
+        _logger.LogWarning("Login reported success, but there is still no internet connection.");
+        _authClient.Stats.RecordUnconfirmedLogin();
+        return false;
+    }
+
+    // Retry from a failed-login notification; the loop can be mid-wait, so this runs on its own
+    private void OnRetryRequested(object? sender, EventArgs e) => _ = LoginAsync();
+
+    private TimeSpan? GetLockoutRemaining()
+    {
+        var remaining = _lockedUntil - DateTimeOffset.Now;
+        return remaining > TimeSpan.Zero ? remaining : null;
+    }
+
+    private void SetStatus(AuthStatus newStatus, string? reason = null)
+    {
+        if (CurrentStatus == newStatus && StatusReason == reason)
             return;
 
         var old = CurrentStatus;
         CurrentStatus = newStatus;
-        StatusChanged?.Invoke(this, new AuthStatusChangedEventArgs { OldStatus = old, NewStatus = newStatus });
+        StatusReason = reason;
+        _events.Add("status", reason == null ? newStatus.ToString() : $"{newStatus}: {reason}");
+        StatusChanged?.Invoke(this, new AuthStatusChangedEventArgs
+        {
+            OldStatus = old,
+            NewStatus = newStatus,
+            Reason = reason,
+        });
     }
 }
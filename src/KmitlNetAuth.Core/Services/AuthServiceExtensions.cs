@@ -0,0 +1,96 @@
+using System.Runtime.CompilerServices;
+using System.Runtime.ExceptionServices;
+using System.Threading.Channels;
+using Microsoft.Extensions.Logging;
+
+namespace KmitlNetAuth.Core.Services;
+
+public static class AuthServiceExtensions
+{
+    /// <summary>
+    /// <see cref="IAuthService.StatusChanged"/> as an async stream, for consumers that would rather
+    /// <c>await foreach</c> in their own loop than handle events on the service's thread. Changes are
+    /// buffered, so a slow consumer sees every one in order. Ends when <paramref name="ct"/> is cancelled.
+    /// </summary>
+    public static async IAsyncEnumerable<AuthStatusChangedEventArgs> StatusChangesAsync(
+        this IAuthService authService, [EnumeratorCancellation] CancellationToken ct = default)
+    {
+        var channel = Channel.CreateUnbounded<AuthStatusChangedEventArgs>(new UnboundedChannelOptions { SingleReader = true });
+        void OnStatusChanged(object? sender, AuthStatusChangedEventArgs e) => channel.Writer.TryWrite(e);
+
+        authService.StatusChanged += OnStatusChanged;
+        try
+        {
+            while (true)
+            {
+                AuthStatusChangedEventArgs change;
+                try
+                {
+                    change = await channel.Reader.ReadAsync(ct);
+                }
+                catch (OperationCanceledException) when (ct.IsCancellationRequested)
+                {
+                    yield break;
+                }
+
+                yield return change;
+            }
+        }
+        finally
+        {
+            authService.StatusChanged -= OnStatusChanged;
+        }
+    }
+
+    /// <summary>
+    /// Runs <see cref="IAuthService.RunAsync"/> and restarts it after <paramref name="restartDelay"/> (default 5s)
+    /// if it fails or returns before <paramref name="ct"/> is cancelled, so one unexpected exception doesn't leave
+    /// a process that is up but no longer logging in. After <paramref name="maxRestarts"/> restarts within
+    /// <paramref name="window"/> (default 10 minutes) the failure is rethrown, leaving it to the supervisor.
+    /// </summary>
+    public static async Task RunSupervisedAsync(this IAuthService authService, ILogger logger, CancellationToken ct,
+        TimeSpan? restartDelay = null, int maxRestarts = 5, TimeSpan? window = null)
+    {
+        var delay = restartDelay ?? TimeSpan.FromSeconds(5);
+        var period = window ?? TimeSpan.FromMinutes(10);
+        var restarts = new Queue<DateTimeOffset>();
+
+        while (true)
+        {
+            Exception? error = null;
+            try
+            {
+                await authService.RunAsync(ct);
+            }
+            catch (OperationCanceledException) when (ct.IsCancellationRequested)
+            {
+                throw;
+            }
+            catch (Exception e)
+            {
+                error = e;
+            }
+
+            if (ct.IsCancellationRequested)
+                return;
+
+            var now = DateTimeOffset.UtcNow;
+            while (restarts.Count > 0 && now - restarts.Peek() > period)
+                restarts.Dequeue();
+
+            if (restarts.Count >= maxRestarts)
+            {
+                logger.LogCritical(error, "Auth loop stopped {Count} times within {Window:F0} minutes, giving up",
+                    restarts.Count + 1, period.TotalMinutes);
+                if (error != null)
+                    ExceptionDispatchInfo.Throw(error);
+                throw new InvalidOperationException("Auth loop stopped unexpectedly");
+            }
+
+            restarts.Enqueue(now);
+            logger.LogError(error, "Auth loop stopped unexpectedly, restarting in {Delay:F0}s (restart {Restart} of {Max} per {Window:F0} minutes)",
+                delay.TotalSeconds, restarts.Count, maxRestarts, period.TotalMinutes);
+            await Task.Delay(delay, ct);
+        }
+    }
+}
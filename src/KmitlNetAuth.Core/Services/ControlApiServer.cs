@@ -0,0 +1,219 @@
+using System.Net;
+using System.Net.Sockets;
+using System.Security.Cryptography;
+using System.Text;
+using System.Text.Json.Nodes;
+using Microsoft.Extensions.Logging;
+
+namespace KmitlNetAuth.Core.Services;
+
+/// <summary>
+/// Optional HTTP control API for dashboards and scripts: <c>GET /status</c> (the same JSON as the status
+/// file), <c>GET /events</c> (recent history), <c>POST /login</c>, <c>POST /logout</c>, <c>POST /relogin</c> and
+/// <c>GET</c>/<c>POST /log-level</c> (<c>?level=debug</c>, not saved). Off unless <see cref="Config.ApiPort"/> and
+/// <see cref="Config.ApiToken"/> are set; every request needs <c>Authorization: Bearer &lt;token&gt;</c>.
+/// Listens on <see cref="Config.ApiBind"/>, localhost by default.
+/// </summary>
+public sealed class ControlApiServer
+{
+    private readonly Config _config;
+    private readonly AuthClient _authClient;
+    private readonly StatusFileWriter _statusFileWriter;
+    private readonly ILogger<ControlApiServer> _logger;
+    private readonly ILogLevelSwitch? _logLevelSwitch;
+
+    public ControlApiServer(Config config, AuthClient authClient, StatusFileWriter statusFileWriter, ILogger<ControlApiServer> logger,
+        ILogLevelSwitch? logLevelSwitch = null)
+    {
+        _config = config;
+        _authClient = authClient;
+        _statusFileWriter = statusFileWriter;
+        _logger = logger;
+        _logLevelSwitch = logLevelSwitch;
+    }
+
+    /// <summary>
+    /// The listener prefix for <see cref="Config.ApiBind"/>. HttpListener has no "any address" IP, so
+    /// <c>0.0.0.0</c> and <c>::</c> become its <c>+</c> wildcard; other IPv6 addresses get their brackets.
+    /// </summary>
+    public string Prefix => $"http://{FormatHost(_config.ApiBind)}:{_config.ApiPort}/";
+
+    private static string FormatHost(string bind)
+    {
+        var host = bind.Trim();
+        if (!IPAddress.TryParse(host.Trim('[', ']'), out var address))
+            return host;
+        if (address.Equals(IPAddress.Any) || address.Equals(IPAddress.IPv6Any))
+            return "+";
+        return address.AddressFamily == AddressFamily.InterNetworkV6 ? $"[{address}]" : address.ToString();
+    }
+
+    /// <summary>Serves requests until cancelled. Returns right away if the API is off or the port can't be bound.</summary>
+    public async Task RunAsync(IAuthService service, CancellationToken ct)
+    {
+        if (_config.ApiPort == 0)
+            return;
+
+        if (string.IsNullOrEmpty(_config.ApiToken))
+        {
+            _logger.LogWarning("[api] port is set but [api] token is empty; the control API stays off.");
+            return;
+        }
+
+        using var listener = new HttpListener();
+        listener.Prefixes.Add(Prefix);
+        try
+        {
+            listener.Start();
+        }
+        catch (HttpListenerException e)
+        {
+            _logger.LogError("Could not start the control API on {Prefix}: {Error}", Prefix, e.Message);
+            return;
+        }
+
+        _logger.LogInformation("Control API listening on {Prefix}", Prefix);
+        await using var stop = ct.Register(listener.Stop);
+
+        while (!ct.IsCancellationRequested)
+        {
+            HttpListenerContext context;
+            try
+            {
+                context = await listener.GetContextAsync();
+            }
+            catch (Exception e) when (e is HttpListenerException or ObjectDisposedException)
+            {
+                if (!ct.IsCancellationRequested)
+                    _logger.LogWarning("Control API stopped: {Error}", e.Message);
+                break;
+            }
+
+            _ = HandleAsync(context, service, ct);
+        }
+    }
+
+    private async Task HandleAsync(HttpListenerContext context, IAuthService service, CancellationToken ct)
+    {
+        var request = context.Request;
+        var response = context.Response;
+        try
+        {
+            if (!IsAuthorized(request.Headers["Authorization"]))
+            {
+                response.AddHeader("WWW-Authenticate", "Bearer");
+                await WriteJsonAsync(response, HttpStatusCode.Unauthorized, Error("missing or invalid bearer token"));
+                return;
+            }
+
+            var path = request.Url?.AbsolutePath.TrimEnd('/') ?? "";
+            var (status, body) = (request.HttpMethod, path) switch
+            {
+                ("GET", "/status") => (HttpStatusCode.OK, _statusFileWriter.CreateSnapshot(service)),
+                ("GET", "/events") => (HttpStatusCode.OK, Events(service)),
+                ("POST", "/login") => (HttpStatusCode.OK, await LoginAsync(service, ct)),
+                ("POST", "/logout") => (HttpStatusCode.OK, new JsonObject { ["success"] = await _authClient.LogoutAsync(ct) }),
+                ("POST", "/relogin") => (HttpStatusCode.OK, await ReloginAsync(service, ct)),
+                ("GET" or "POST", "/log-level") => HandleLogLevel(request.HttpMethod == "POST" ? request.QueryString["level"] ?? "" : null),
+                (_, "/status" or "/events" or "/login" or "/logout" or "/relogin" or "/log-level") => (HttpStatusCode.MethodNotAllowed, Error($"{request.HttpMethod} not allowed")),
+                _ => (HttpStatusCode.NotFound, Error($"no such endpoint: {path}")),
+            };
+
+            _logger.LogDebug("Control API {Method} {Path} -> {Status}", request.HttpMethod, path, (int)status);
+            await WriteJsonAsync(response, status, body);
+        }
+        catch (Exception e) when (e is HttpListenerException or IOException or OperationCanceledException)
+        {
+            // Client went away or the server is stopping
+        }
+        catch (Exception e)
+        {
+            // Nothing awaits this request's task, so an error not reported here would go unseen
+            _logger.LogError(e, "Control API {Method} {Path} failed", request.HttpMethod, request.Url?.AbsolutePath);
+            try
+            {
+                await WriteJsonAsync(response, HttpStatusCode.InternalServerError, Error("internal error"));
+            }
+            catch (Exception writeError) when (writeError is HttpListenerException or IOException or InvalidOperationException)
+            {
+                // Part of the response was already sent
+            }
+        }
+        finally
+        {
+            response.Close();
+        }
+    }
+
+    private async Task<JsonObject> LoginAsync(IAuthService service, CancellationToken ct)
+    {
+        _logger.LogInformation("Login requested through the control API");
+        return LoginJson(await service.LoginAsync(ct));
+    }
+
+    private async Task<JsonObject> ReloginAsync(IAuthService service, CancellationToken ct)
+    {
+        _logger.LogInformation("Re-login requested through the control API");
+        var result = await service.ReloginAsync(ct);
+        var json = LoginJson(result.Login);
+        json["logged_out"] = result.LoggedOut;
+        return json;
+    }
+
+    // newLevel is null for a GET, which only reports the current level
+    private (HttpStatusCode, JsonObject) HandleLogLevel(string? newLevel)
+    {
+        if (_logLevelSwitch == null)
+            return (HttpStatusCode.NotImplemented, Error("this host can't change its log level while running"));
+
+        if (newLevel != null)
+        {
+            if (!Config.IsKnownLogLevel(newLevel))
+                return (HttpStatusCode.BadRequest, Error($"unknown log level: '{newLevel}'"));
+
+            _logLevelSwitch.SetLevel(newLevel);
+            // Warning so the change shows up even when the new level is quieter
+            _logger.LogWarning("Log level set to {Level} through the control API (not saved)", _logLevelSwitch.Level);
+        }
+
+        return (HttpStatusCode.OK, new JsonObject { ["level"] = _logLevelSwitch.Level });
+    }
+
+    private static JsonObject LoginJson(LoginResult result) => new()
+    {
+        ["success"] = result.Success,
+        ["failure"] = result.Success ? null : result.Failure.ToString(),
+        ["message"] = result.Message,
+    };
+
+    private static JsonObject Events(IAuthService service) => new()
+    {
+        ["events"] = new JsonArray(service.RecentEvents.Select(e => new JsonObject
+        {
+            ["at"] = e.At,
+            ["kind"] = e.Kind,
+            ["message"] = e.Message,
+        }).ToArray<JsonNode?>()),
+    };
+
+    private bool IsAuthorized(string? header)
+    {
+        const string scheme = "Bearer ";
+        if (header == null || !header.StartsWith(scheme, StringComparison.OrdinalIgnoreCase))
+            return false;
+
+        return CryptographicOperations.FixedTimeEquals(
+            Encoding.UTF8.GetBytes(header[scheme.Length..].Trim()), Encoding.UTF8.GetBytes(_config.ApiToken ?? ""));
+    }
+
+    private static JsonObject Error(string message) => new() { ["error"] = message };
+
+    private static async Task WriteJsonAsync(HttpListenerResponse response, HttpStatusCode status, JsonObject body)
+    {
+        var bytes = Encoding.UTF8.GetBytes(body.ToJsonString());
+        response.StatusCode = (int)status;
+        response.ContentType = "application/json";
+        response.ContentLength64 = bytes.Length;
+        await response.OutputStream.WriteAsync(bytes);
+    }
+}
@@ -0,0 +1,43 @@
+namespace KmitlNetAuth.Core.Services;
+
+/// <summary>
+/// Something the auth service did: <c>status</c> for a status change, <c>cycle</c> for the one-line
+/// summary each run loop cycle ends with.
+/// </summary>
+public sealed record ServiceEvent(DateTimeOffset At, string Kind, string Message);
+
+/// <summary>
+/// The last <see cref="Capacity"/> service events, oldest first, so a dashboard that attaches to a running
+/// service can show recent history instead of starting blank. Safe to use from any thread.
+/// </summary>
+public sealed class EventHistory
+{
+    public const int DefaultCapacity = 50;
+
+    private readonly Queue<ServiceEvent> _events = new();
+    private readonly object _lock = new();
+
+    public EventHistory(int capacity = DefaultCapacity)
+    {
+        ArgumentOutOfRangeException.ThrowIfLessThan(capacity, 1);
+        Capacity = capacity;
+    }
+
+    public int Capacity { get; }
+
+    public void Add(string kind, string message)
+    {
+        lock (_lock)
+        {
+            if (_events.Count == Capacity)
+                _events.Dequeue();
+            _events.Enqueue(new ServiceEvent(DateTimeOffset.Now, kind, message));
+        }
+    }
+
+    public IReadOnlyList<ServiceEvent> Snapshot()
+    {
+        lock (_lock)
+            return [.. _events];
+    }
+}
@@ -4,5 +4,45 @@ public interface IAuthService
 {
     Task RunAsync(CancellationToken ct);
     AuthStatus CurrentStatus { get; }
+
+    /// <summary>Why the service is in <see cref="AuthStatus.NeedsAttention"/>; null otherwise.</summary>
+    string? StatusReason { get; }
+
+    /// <summary>When the run loop wakes up next; null while a cycle is running or paused.</summary>
+    DateTimeOffset? NextCheckAt { get; }
+
+    /// <summary>Length of the current wait, for progress display.</summary>
+    TimeSpan NextCheckDelay { get; }
+
+    /// <summary>
+    /// True from <see cref="Pause"/> until <see cref="Resume"/> or the end of a timed pause. Kept here rather than
+    /// in <see cref="Config.AutoLogin"/>, so a pause is never saved with the config.
+    /// </summary>
+    bool IsPaused { get; }
+
+    /// <summary>When a timed pause ends; null when running or paused indefinitely.</summary>
+    DateTimeOffset? ResumeAt { get; }
+
+    /// <summary>Recent status changes and cycle summaries, oldest first; see <see cref="EventHistory"/>.</summary>
+    IReadOnlyList<ServiceEvent> RecentEvents { get; }
+
+    /// <summary>Stops auto-login. With a duration it resumes by itself afterwards; without, until <see cref="Resume"/>.</summary>
+    void Pause(TimeSpan? duration = null);
+
+    void Resume();
+
+    /// <summary>
+    /// Logs in now, the way the run loop does: confirmed by a connectivity check before it counts or
+    /// notifies. Manual logins go through here so they update the status like the loop's own.
+    /// </summary>
+    Task<LoginResult> LoginAsync(CancellationToken ct = default);
+
+    /// <summary>
+    /// Logs out, waits a moment and logs in again through <see cref="LoginAsync"/>, to reset a session the
+    /// portal still holds but no longer honours. The logout is skipped without a logout_url; the login is
+    /// sent even if the logout failed.
+    /// </summary>
+    Task<ReloginResult> ReloginAsync(CancellationToken ct = default);
+
     event EventHandler<AuthStatusChangedEventArgs>? StatusChanged;
 }
@@ -0,0 +1,15 @@
+namespace KmitlNetAuth.Core.Services;
+
+/// <summary>
+/// Changes how much a running host logs, without a restart. Core has no logging backend of its own, so
+/// the host registers one (the CLI wraps Serilog's level switch); without it the control API reports that
+/// the level can't be changed. The change isn't saved to the config.
+/// </summary>
+public interface ILogLevelSwitch
+{
+    /// <summary>The current minimum level, e.g. <c>Information</c>.</summary>
+    string Level { get; }
+
+    /// <summary>Sets the minimum level; <paramref name="level"/> is one <see cref="Config.IsKnownLogLevel"/> accepts.</summary>
+    void SetLevel(string level);
+}
@@ -0,0 +1,20 @@
+namespace KmitlNetAuth.Core.Services;
+
+/// <summary>
+/// Min, mean, max and 95th percentile of a set of request timings, as printed by <c>kmitlnetauth bench</c>.
+/// The percentile is nearest-rank, so with fewer than 20 samples it is the slowest one.
+/// </summary>
+public sealed record LatencySummary(int Samples, TimeSpan Min, TimeSpan Average, TimeSpan Max, TimeSpan P95)
+{
+    /// <summary>Null when there are no samples, e.g. a step that was skipped in every cycle.</summary>
+    public static LatencySummary? FromSamples(IReadOnlyCollection<TimeSpan> samples)
+    {
+        if (samples.Count == 0)
+            return null;
+
+        var sorted = samples.Order().ToArray();
+        var rank = (int)Math.Ceiling(0.95 * sorted.Length) - 1;
+        return new LatencySummary(sorted.Length, sorted[0],
+            TimeSpan.FromTicks((long)sorted.Average(sample => sample.Ticks)), sorted[^1], sorted[rank]);
+    }
+}
@@ -0,0 +1,119 @@
+using System.Text.Json;
+using System.Text.Json.Nodes;
+using Microsoft.Extensions.Logging;
+
+namespace KmitlNetAuth.Core.Services;
+
+/// <summary>
+/// Periodically writes the service status and <see cref="AuthStats"/> to a JSON file, so scripts and
+/// other processes can monitor the service without an HTTP endpoint. Each write goes to a temp file
+/// that is then renamed over the old one, so readers never see a half-written file.
+/// </summary>
+public sealed class StatusFileWriter
+{
+    private readonly Config _config;
+    private readonly AuthClient _authClient;
+    private readonly ILogger<StatusFileWriter> _logger;
+    private bool _warned;
+
+    public StatusFileWriter(Config config, AuthClient authClient, ILogger<StatusFileWriter> logger)
+    {
+        _config = config;
+        _authClient = authClient;
+        _logger = logger;
+    }
+
+    public string FilePath => _config.StatusFile ?? ConfigPaths.GetStatusFilePath();
+
+    /// <summary>Set by the service as it stops, so the last write carries the run's summary.</summary>
+    public SessionSummary? Shutdown { get; set; }
+
+    /// <summary>Writes every <see cref="Config.StatusFileInterval"/> seconds until cancelled, then once more.</summary>
+    public async Task RunAsync(IAuthService service, CancellationToken ct)
+    {
+        if (_config.StatusFileInterval <= 0)
+            return;
+
+        using var timer = new PeriodicTimer(TimeSpan.FromSeconds(_config.StatusFileInterval));
+        try
+        {
+            do
+            {
+                Write(service);
+            }
+            while (await timer.WaitForNextTickAsync(ct));
+        }
+        catch (OperationCanceledException)
+        {
+        }
+
+        Write(service);
+    }
+
+    public void Write(IAuthService service)
+    {
+        var path = FilePath;
+        var tempPath = path + ".tmp";
+        try
+        {
+            if (Path.GetDirectoryName(path) is { Length: > 0 } dir)
+                Directory.CreateDirectory(dir);
+
+            File.WriteAllText(tempPath, CreateSnapshot(service).ToJsonString(new JsonSerializerOptions { WriteIndented = true }));
+            File.Move(tempPath, path, overwrite: true);
+            _warned = false;
+        }
+        catch (Exception e) when (e is IOException or UnauthorizedAccessException)
+        {
+            // Once per failure streak, not every interval
+            if (!_warned)
+                _logger.LogWarning("Could not write status file {Path}: {Error}", path, e.Message);
+            _warned = true;
+        }
+    }
+
+    public JsonObject CreateSnapshot(IAuthService service)
+    {
+        var stats = _authClient.Stats;
+        return new JsonObject
+        {
+            ["status"] = service.CurrentStatus.ToString(),
+            ["reason"] = service.StatusReason,
+            ["username"] = _config.Username,
+            ["updated_at"] = DateTimeOffset.Now,
+            ["started_at"] = stats.StartedAt,
+            ["next_check_at"] = service.NextCheckAt,
+            ["last_login_at"] = stats.LastLoginAt,
+            ["online_since"] = stats.OnlineSince,
+            ["connected_seconds"] = (long)stats.ConnectedFor.TotalSeconds,
+            ["total_connected_seconds"] = (long)stats.TotalConnected.TotalSeconds,
+            ["last_error"] = stats.LastError,
+            ["last_error_at"] = stats.LastErrorAt,
+            ["counters"] = new JsonObject
+            {
+                ["logins"] = stats.Logins,
+                ["login_failures"] = stats.LoginFailures,
+                ["heartbeats"] = stats.Heartbeats,
+                ["heartbeat_failures"] = stats.HeartbeatFailures,
+                ["disconnects"] = stats.Disconnects,
+            },
+            ["devices"] = new JsonArray(_authClient.DeviceLogins.Values.OrderBy(d => d.MacAddress).Select(d => new JsonObject
+            {
+                ["mac"] = d.MacAddress,
+                ["logged_in"] = d.Result.Success,
+                ["message"] = d.Result.Message,
+                ["at"] = d.At,
+            }).ToArray<JsonNode?>()),
+            ["shutdown"] = Shutdown is { } summary
+                ? new JsonObject
+                {
+                    ["uptime_seconds"] = (long)summary.Uptime.TotalSeconds,
+                    ["logins"] = summary.Logins,
+                    ["heartbeats"] = summary.Heartbeats,
+                    ["heartbeat_success_rate"] = summary.HeartbeatSuccessRate,
+                    ["logged_out"] = summary.LoggedOut,
+                }
+                : null,
+        };
+    }
+}
@@ -0,0 +1,29 @@
+using System.Globalization;
+
+namespace KmitlNetAuth.Core.Services;
+
+public enum TickStep
+{
+    /// <summary>Not attempted this cycle, e.g. no login while online or a login skipped by the circuit breaker.</summary>
+    Skipped,
+    Ok,
+    Failed,
+}
+
+/// <summary>
+/// What one run loop cycle did, logged at info as a single line so the big picture doesn't have to be
+/// pieced together from the check, heartbeat and login lines. <see cref="Latency"/> is how long the
+/// connectivity check took.
+/// </summary>
+public sealed record TickOutcome(long Cycle, bool Online, TickStep Heartbeat, TickStep Login, TimeSpan Latency)
+{
+    /// <summary>The step's result from how many attempts of it succeeded and failed during the cycle.</summary>
+    internal static TickStep FromCounts(long succeeded, long failed) =>
+        failed > 0 ? TickStep.Failed : succeeded > 0 ? TickStep.Ok : TickStep.Skipped;
+
+    public override string ToString() =>
+        string.Create(CultureInfo.InvariantCulture,
+            $"cycle {Cycle}: online={(Online ? "true" : "false")} heartbeat={Format(Heartbeat)} login={Format(Login)} latency={Latency.TotalMilliseconds:F0}ms");
+
+    internal static string Format(TickStep step) => step.ToString().ToLowerInvariant();
+}
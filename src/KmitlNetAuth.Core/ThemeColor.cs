@@ -0,0 +1,53 @@
+using System.Globalization;
+
+namespace KmitlNetAuth.Core;
+
+/// <summary>
+/// A colour from the <c>[theme]</c> config section: <c>#RRGGBB</c> (the <c>#</c> is optional) or one of
+/// <see cref="Names"/>. Front ends turn it into their own brush or escape code.
+/// </summary>
+public readonly record struct ThemeColor(byte R, byte G, byte B)
+{
+    private static readonly Dictionary<string, ThemeColor> Named = new(StringComparer.OrdinalIgnoreCase)
+    {
+        ["green"] = new(0x2E, 0xCC, 0x71),
+        ["red"] = new(0xE7, 0x4C, 0x3C),
+        ["amber"] = new(0xF3, 0x9C, 0x12),
+        ["yellow"] = new(0xF1, 0xC4, 0x0F),
+        ["orange"] = new(0xE6, 0x7E, 0x22),
+        ["blue"] = new(0x34, 0x98, 0xDB),
+        ["cyan"] = new(0x1A, 0xBC, 0x9C),
+        ["purple"] = new(0x9B, 0x59, 0xB6),
+        ["magenta"] = new(0xE8, 0x43, 0x93),
+        ["gray"] = new(0x95, 0xA5, 0xA6),
+        ["grey"] = new(0x95, 0xA5, 0xA6),
+        ["white"] = new(0xFF, 0xFF, 0xFF),
+        ["black"] = new(0x00, 0x00, 0x00),
+    };
+
+    /// <summary>Colour names accepted besides hex values.</summary>
+    public static IEnumerable<string> Names => Named.Keys;
+
+    public static bool TryParse(string? value, out ThemeColor color)
+    {
+        color = default;
+        if (string.IsNullOrWhiteSpace(value))
+            return false;
+
+        var trimmed = value.Trim();
+        if (Named.TryGetValue(trimmed, out color))
+            return true;
+
+        var hex = trimmed.TrimStart('#');
+        if (hex.Length != 6 || !uint.TryParse(hex, NumberStyles.HexNumber, CultureInfo.InvariantCulture, out var rgb))
+            return false;
+
+        color = new ThemeColor((byte)(rgb >> 16), (byte)(rgb >> 8), (byte)rgb);
+        return true;
+    }
+
+    /// <summary>The ANSI 24-bit foreground escape for terminals.</summary>
+    public string ToAnsi() => $"\x1b[38;2;{R};{G};{B}m";
+
+    public override string ToString() => $"#{R:X2}{G:X2}{B:X2}";
+}
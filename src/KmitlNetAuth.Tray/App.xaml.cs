@@ -1,7 +1,9 @@
+using System.Diagnostics;
 using System.IO;
 using System.Runtime.Versioning;
 using KmitlNetAuth.Core;
 using KmitlNetAuth.Core.DependencyInjection;
+using KmitlNetAuth.Core.Exceptions;
 using Microsoft.Extensions.DependencyInjection;
 using Microsoft.Extensions.Hosting;
 using Serilog;
@@ -17,10 +19,27 @@ public partial class App : System.Windows.Application
     {
         base.OnStartup(e);
 
-        var configPath = ConfigPaths.Resolve();
-        var config = Config.Load(configPath);
+        string configPath, logDir;
+        try
+        {
+            configPath = ConfigPaths.Resolve();
+            logDir = ConfigPaths.GetLogDirectory();
+        }
+        catch (ConfigException ex)
+        {
+            System.Windows.MessageBox.Show(ex.Message, "KMITL NetAuth", System.Windows.MessageBoxButton.OK,
+                System.Windows.MessageBoxImage.Error);
+            Shutdown(1);
+            return;
+        }
+
+        var config = LoadConfig(configPath);
+        if (config == null)
+        {
+            Shutdown(1);
+            return;
+        }
 
-        var logDir = ConfigPaths.GetLogDirectory();
         Directory.CreateDirectory(logDir);
 
         Log.Logger = new LoggerConfiguration()
@@ -28,6 +47,7 @@ public partial class App : System.Windows.Application
             .WriteTo.Sink(LogBufferSink.Instance)
             .WriteTo.File(
                 Path.Combine(logDir, "tray-.log"),
+                outputTemplate: "{Timestamp:yyyy-MM-dd HH:mm:ss.fff zzz} [{Level:u3}] {Cycle:'#'0' '}{Message:lj}{NewLine}{Exception}",
                 rollingInterval: RollingInterval.Day,
                 retainedFileCountLimit: 14)
             .CreateLogger();
@@ -41,10 +61,10 @@ public partial class App : System.Windows.Application
 
         _host = builder.Build();
 
-        // First-run wizard: username is empty -> force completion BEFORE the
-        // hosted AuthWorker starts, so it picks up the saved credentials.
+        // First-run wizard: username is empty (KMITL_USERNAME counts) -> force completion
+        // BEFORE the hosted AuthWorker starts, so it picks up the saved credentials.
         var wizardRan = false;
-        if (string.IsNullOrWhiteSpace(config.Username))
+        if (string.IsNullOrWhiteSpace(config.Username) && config.FirstRunWizard)
         {
             var setup = new SetupWindow(_host.Services, configPath);
             var ok = setup.ShowDialog();
@@ -69,6 +89,47 @@ public partial class App : System.Windows.Application
         }
     }
 
+    /// <summary>
+    /// Loads the config, refusing to fall back to defaults when the file exists but doesn't parse:
+    /// an empty username would start the first-run wizard, which saves over the user's settings.
+    /// Instead the file is opened in Notepad and the load retried until it parses or the user quits.
+    /// </summary>
+    private static Config? LoadConfig(string configPath)
+    {
+        var editorOpened = false;
+        while (true)
+        {
+            try
+            {
+                return Config.Load(configPath, throwOnParseError: true);
+            }
+            catch (ConfigException ex)
+            {
+                if (!editorOpened)
+                {
+                    try
+                    {
+                        Process.Start("notepad.exe", $"\"{configPath}\"");
+                        editorOpened = true;
+                    }
+                    catch (Exception)
+                    {
+                        // The message below still names the file
+                    }
+                }
+
+                var answer = System.Windows.MessageBox.Show(
+                    $"{ex.Message}\n\nYour settings have not been changed. Fix the file, save it, then click OK to " +
+                    "try again, or Cancel to quit.",
+                    "KMITL NetAuth - Config error",
+                    System.Windows.MessageBoxButton.OKCancel,
+                    System.Windows.MessageBoxImage.Error);
+                if (answer != System.Windows.MessageBoxResult.OK)
+                    return null;
+            }
+        }
+    }
+
     protected override void OnExit(System.Windows.ExitEventArgs e)
     {
         if (_host != null)
@@ -21,7 +21,7 @@ public sealed class AuthWorker : BackgroundService
 
         try
         {
-            await _authService.RunAsync(stoppingToken);
+            await _authService.RunSupervisedAsync(_logger, stoppingToken);
         }
         catch (OperationCanceledException)
         {
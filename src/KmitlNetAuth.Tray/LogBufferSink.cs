@@ -17,7 +17,8 @@ public sealed class LogBufferSink : ILogEventSink
     public void Emit(LogEvent logEvent)
     {
         var message = logEvent.RenderMessage();
-        var line = $"[{logEvent.Timestamp:HH:mm:ss}] [{logEvent.Level}] {message}";
+        var cycle = logEvent.Properties.TryGetValue("Cycle", out var value) ? $"#{value} " : "";
+        var line = $"[{logEvent.Timestamp:HH:mm:ss}] [{logEvent.Level}] {cycle}{message}";
         if (logEvent.Exception != null)
             line += Environment.NewLine + logEvent.Exception;
 
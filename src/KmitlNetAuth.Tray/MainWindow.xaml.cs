@@ -2,6 +2,7 @@ using System.ComponentModel;
 using System.Drawing;
 using System.Runtime.Versioning;
 using System.Windows;
+using System.Windows.Input;
 using KmitlNetAuth.Core;
 using KmitlNetAuth.Core.Services;
 using KmitlNetAuth.Tray.Pages;
@@ -23,12 +24,20 @@ public partial class MainWindow : FluentWindow
     private readonly ILogger<MainWindow> _logger;
     private readonly UpdateChecker _updateChecker;
     private readonly WinForms.NotifyIcon _notifyIcon;
+    private readonly Icon _trayIcon;
 
     // Cached page instances keyed by type. Pages have constructor dependencies
     // that the default wpfui Activator-based creation cannot satisfy, so we
     // pre-build them here and expose them via a custom INavigationViewPageProvider.
     private readonly Dictionary<Type, object> _pageCache = new();
 
+    private static readonly (string Label, ulong Seconds)[] IntervalPresets =
+    [
+        ("1 minute", 60),
+        ("5 minutes", 300),
+        ("15 minutes", 900),
+    ];
+
     public MainWindow(IServiceProvider services, string configPath)
     {
         // Watch OS theme so Mica/accent colors update on theme change.
@@ -55,18 +64,41 @@ public partial class MainWindow : FluentWindow
             if (extracted != null)
                 trayIcon = extracted;
         }
+        _trayIcon = trayIcon;
 
-        var showItem = new WinForms.ToolStripMenuItem("Show / Hide");
+        var showItem = new WinForms.ToolStripMenuItem(Localized(MessageKey.TrayShowHide));
         showItem.Click += (_, _) => ToggleVisibility();
 
-        var quitItem = new WinForms.ToolStripMenuItem("Quit");
+        var intervalItem = new WinForms.ToolStripMenuItem(Localized(MessageKey.TrayInterval));
+        foreach (var (label, seconds) in IntervalPresets)
+        {
+            var presetItem = new WinForms.ToolStripMenuItem(label) { Tag = seconds };
+            presetItem.Click += OnIntervalPresetClicked;
+            intervalItem.DropDownItems.Add(presetItem);
+        }
+        intervalItem.DropDownOpening += (_, _) => UpdateIntervalChecks(intervalItem);
+
+        var forgetItem = new WinForms.ToolStripMenuItem(Localized(MessageKey.TrayForgetCredentials));
+        forgetItem.Click += OnForgetCredentialsClicked;
+
+        var trustItem = new WinForms.ToolStripMenuItem(Localized(MessageKey.TrayTrustCertificate)) { Visible = false };
+        trustItem.Click += OnTrustCertificateClicked;
+
+        var quitItem = new WinForms.ToolStripMenuItem(Localized(MessageKey.TrayQuit));
         quitItem.Click += OnQuitClicked;
 
         var contextMenu = new WinForms.ContextMenuStrip();
         contextMenu.Items.Add(showItem);
+        contextMenu.Items.Add(intervalItem);
+        contextMenu.Items.Add(forgetItem);
+        contextMenu.Items.Add(trustItem);
         contextMenu.Items.Add(new WinForms.ToolStripSeparator());
         contextMenu.Items.Add(quitItem);
 
+        // Only offered while a changed certificate waits to be accepted
+        contextMenu.Opening += (_, _) =>
+            trustItem.Visible = _services.GetRequiredService<PortalCertificateWatcher>().GetPending().Count > 0;
+
         _notifyIcon = new WinForms.NotifyIcon
         {
             Icon = trayIcon,
@@ -75,6 +107,11 @@ public partial class MainWindow : FluentWindow
             ContextMenuStrip = contextMenu,
         };
         _notifyIcon.DoubleClick += (_, _) => ToggleVisibility();
+        _notifyIcon.MouseClick += (_, e) =>
+        {
+            if (e.Button == WinForms.MouseButtons.Left)
+                ShowStatusBalloon();
+        };
 
         // Subscribe to status changes for balloon tips
         _authService.StatusChanged += OnStatusChanged;
@@ -82,6 +119,8 @@ public partial class MainWindow : FluentWindow
         // Auto-update check on startup
         _ = _updateChecker.StartAsync();
 
+        PreviewKeyDown += OnPreviewKeyDown;
+
         // Navigate to the default page after the NavigationView is realized.
         Loaded += (_, _) => RootNavigation.Navigate(typeof(DashboardPage));
     }
@@ -129,11 +168,18 @@ public partial class MainWindow : FluentWindow
 
     private void OnStatusChanged(object? sender, AuthStatusChangedEventArgs e)
     {
+        var needsAttention = e.NewStatus == AuthStatus.NeedsAttention;
+
+        // Swap to the warning icon so the problem is visible without opening the window.
+        _notifyIcon.Icon = needsAttention ? SystemIcons.Warning : _trayIcon;
+        _notifyIcon.Text = needsAttention ? $"KMITL NetAuth - {Localized(MessageKey.NeedsAttentionTitle)}" : "KMITL NetAuth";
+
         var (title, body) = e.NewStatus switch
         {
-            AuthStatus.Online => ("Connected", "Internet connection is active."),
-            AuthStatus.Offline => ("Disconnected", "Internet connection lost."),
-            AuthStatus.Paused => ("Paused", "Auto-login is disabled."),
+            AuthStatus.Online => (Localized(MessageKey.ConnectedTitle), Localized(MessageKey.ConnectedBody)),
+            AuthStatus.Offline => (Localized(MessageKey.DisconnectedTitle), Localized(MessageKey.DisconnectedBodyShort)),
+            AuthStatus.Paused => (Localized(MessageKey.PausedTitle), Localized(MessageKey.PausedBody)),
+            AuthStatus.NeedsAttention => (Localized(MessageKey.NeedsAttentionTitle), e.Reason ?? Localized(MessageKey.NeedsAttentionBody)),
             _ => ((string?)null, (string?)null),
         };
 
@@ -143,7 +189,130 @@ public partial class MainWindow : FluentWindow
                 3000,
                 $"KMITL NetAuth - {title}",
                 body,
-                WinForms.ToolTipIcon.Info);
+                needsAttention ? WinForms.ToolTipIcon.Error : WinForms.ToolTipIcon.Info);
+        }
+    }
+
+    /// <summary>
+    /// Shows status, username, IP and last heartbeat as a balloon on a left click, for a glance at the
+    /// connection without opening the window.
+    /// </summary>
+    private void ShowStatusBalloon()
+    {
+        var config = _services.GetRequiredService<Config>();
+        var stats = _services.GetRequiredService<AuthClient>().Stats;
+        var (_, currentIp) = KmitlNetAuth.Core.Platform.DhcpDetector.GetNetworkStatus();
+
+        var status = _authService.CurrentStatus == AuthStatus.NeedsAttention
+            ? Localized(MessageKey.NeedsAttentionTitle)
+            : _authService.CurrentStatus.ToString();
+        if (_authService.StatusReason is { } reason)
+            status += $" ({reason})";
+
+        var body = Messages.Get(config.Language, MessageKey.TrayStatusBody,
+            string.IsNullOrEmpty(config.Username) ? "-" : config.Username,
+            string.IsNullOrEmpty(currentIp) ? config.IpAddress ?? "-" : currentIp,
+            stats.LastHeartbeatAt?.ToString("HH:mm:ss") ?? Localized(MessageKey.TrayNever));
+
+        _notifyIcon.ShowBalloonTip(3000, $"KMITL NetAuth - {status}", body, WinForms.ToolTipIcon.Info);
+    }
+
+    /// <summary>
+    /// Window-wide shortcuts: Ctrl+L logs in now (from any page), Ctrl+Q quits.
+    /// </summary>
+    private async void OnPreviewKeyDown(object sender, System.Windows.Input.KeyEventArgs e)
+    {
+        if (Keyboard.Modifiers != ModifierKeys.Control)
+            return;
+
+        switch (e.Key)
+        {
+            case Key.L:
+                e.Handled = true;
+                RootNavigation.Navigate(typeof(DashboardPage));
+                await ((DashboardPage)CreatePage(typeof(DashboardPage))).LoginNowAsync();
+                break;
+            case Key.Q:
+                e.Handled = true;
+                OnQuitClicked(this, EventArgs.Empty);
+                break;
+        }
+    }
+
+    private string Localized(MessageKey key) => Messages.Get(_services.GetRequiredService<Config>().Language, key);
+
+    private void UpdateIntervalChecks(WinForms.ToolStripMenuItem intervalItem)
+    {
+        // A custom interval from the config file leaves every preset unchecked
+        var interval = _services.GetRequiredService<Config>().Interval;
+        foreach (WinForms.ToolStripMenuItem item in intervalItem.DropDownItems)
+            item.Checked = (ulong)item.Tag! == interval;
+    }
+
+    /// <summary>
+    /// Applies an interval preset and saves it. The running service reads the interval
+    /// from the shared config, so it takes effect from the next wait. Only the interval is written to
+    /// the config file; the rest of the live config may come from the system file or the environment.
+    /// </summary>
+    private void OnIntervalPresetClicked(object? sender, EventArgs e)
+    {
+        var config = _services.GetRequiredService<Config>();
+        config.Interval = (ulong)((WinForms.ToolStripMenuItem)sender!).Tag!;
+
+        try
+        {
+            var file = Config.LoadFile(_configPath);
+            file.SetValue("service.interval", config.Interval.ToString(System.Globalization.CultureInfo.InvariantCulture));
+            file.Save(_configPath, _services.GetService<KmitlNetAuth.Core.Platform.ICredentialStore>(), _logger);
+            _logger.LogInformation("Interval set to {Interval}s from the tray menu", config.Interval);
+        }
+        catch (Exception ex)
+        {
+            _logger.LogError(ex, "Failed to save interval");
+        }
+
+        if (_pageCache.TryGetValue(typeof(SettingsPage), out var settingsPage))
+            ((SettingsPage)settingsPage).RefreshInterval();
+    }
+
+    /// <summary>
+    /// Deletes the saved password after confirmation, then reopens the setup wizard so new
+    /// credentials can be entered straight away (e.g. when handing the machine to someone else).
+    /// </summary>
+    private void OnForgetCredentialsClicked(object? sender, EventArgs e)
+    {
+        var config = _services.GetRequiredService<Config>();
+        var answer = System.Windows.MessageBox.Show(
+            $"Delete the saved password for {config.Username}? Auto-login stops until new credentials are entered.",
+            "Forget Credentials - KMITL NetAuth",
+            System.Windows.MessageBoxButton.YesNo,
+            System.Windows.MessageBoxImage.Warning);
+        if (answer != System.Windows.MessageBoxResult.Yes)
+            return;
+
+        config.ForgetPassword(_configPath, _services.GetService<KmitlNetAuth.Core.Platform.ICredentialStore>(), _logger);
+        _logger.LogInformation("Saved credentials forgotten for {Username}", config.Username);
+
+        new SetupWindow(_services, _configPath).ShowDialog();
+    }
+
+    /// <summary>
+    /// Shows each changed portal certificate's new fingerprint and pins the ones the user confirms, which
+    /// stops the warnings about them.
+    /// </summary>
+    private void OnTrustCertificateClicked(object? sender, EventArgs e)
+    {
+        var watcher = _services.GetRequiredService<PortalCertificateWatcher>();
+        foreach (var (host, fingerprint) in watcher.GetPending())
+        {
+            var answer = System.Windows.MessageBox.Show(
+                $"{host} now presents a certificate with SHA-256 fingerprint\n{fingerprint}\n\n" +
+                "Trust it? Only do so if the portal renewed its certificate.",
+                "Portal Certificate - KMITL NetAuth",
+                System.Windows.MessageBoxButton.YesNo,
+                System.Windows.MessageBoxImage.Warning);
+            if (answer == System.Windows.MessageBoxResult.Yes)
+                watcher.AcceptPending(host);
         }
     }
 
@@ -5,6 +5,7 @@ using System.Windows.Controls;
 using System.Windows.Media;
 using System.Windows.Threading;
 using KmitlNetAuth.Core;
+using KmitlNetAuth.Core.Platform;
 using KmitlNetAuth.Core.Services;
 using Microsoft.Extensions.DependencyInjection;
 using Microsoft.Extensions.Logging;
@@ -21,6 +22,10 @@ public partial class DashboardPage : Page
     private readonly DispatcherTimer _uptimeTimer;
     private readonly DateTime _startTime;
     private DateTime _lastStatusChange;
+    private DateTime _ipRefreshedAt;
+
+    // The detected address only changes on a reconnect; enumerating interfaces every tick isn't worth it
+    private static readonly TimeSpan IpRefreshInterval = TimeSpan.FromSeconds(10);
 
     public DashboardPage(IServiceProvider services)
     {
@@ -35,10 +40,10 @@ public partial class DashboardPage : Page
 
         // Populate static info
         UsernameText.Text = string.IsNullOrEmpty(_config.Username) ? "(not set)" : _config.Username;
-        IpAddressText.Text = string.IsNullOrEmpty(_config.IpAddress) ? "(auto-detect)" : _config.IpAddress;
+        UpdateIpAddress();
 
         // Set initial status
-        UpdateStatusDisplay(_authService.CurrentStatus);
+        UpdateStatusDisplay(_authService.CurrentStatus, _authService.StatusReason);
         UpdatePauseButton();
 
         // Subscribe to real-time status changes
@@ -50,6 +55,13 @@ public partial class DashboardPage : Page
         {
             var uptime = DateTime.Now - _startTime;
             UptimeText.Text = FormatUptime(uptime);
+            UpdateConnectedTime();
+            UpdateNextCheck();
+            UpdateLongIntervalWarning();
+            UpdateDevices();
+            UpdatePauseButton();
+            if (DateTime.Now - _ipRefreshedAt >= IpRefreshInterval)
+                UpdateIpAddress();
         };
         _uptimeTimer.Start();
 
@@ -65,38 +77,123 @@ public partial class DashboardPage : Page
         _lastStatusChange = DateTime.Now;
         Dispatcher.Invoke(() =>
         {
-            UpdateStatusDisplay(e.NewStatus);
+            UpdateStatusDisplay(e.NewStatus, e.Reason);
             UpdatePauseButton();
+            // A reconnect is when DHCP hands out a new address
+            UpdateIpAddress();
         });
     }
 
-    private void UpdateStatusDisplay(AuthStatus status)
+    // A configured ip_address is what the portal is sent; otherwise show the address the interface has now
+    private void UpdateIpAddress()
+    {
+        _ipRefreshedAt = DateTime.Now;
+        if (!string.IsNullOrEmpty(_config.IpAddress))
+        {
+            IpAddressText.Text = _config.IpAddress;
+            return;
+        }
+
+        var (_, currentIp) = DhcpDetector.GetNetworkStatus();
+        IpAddressText.Text = string.IsNullOrEmpty(currentIp) ? "(auto-detect)" : $"{currentIp} (auto-detect)";
+    }
+
+    private void UpdateStatusDisplay(AuthStatus status, string? reason)
     {
-        StatusText.Text = status.ToString();
+        StatusText.Text = status == AuthStatus.NeedsAttention ? "Needs attention" : status.ToString();
+        StatusReasonText.Text = reason ?? "";
+        StatusReasonText.Visibility = string.IsNullOrEmpty(reason) ? Visibility.Collapsed : Visibility.Visible;
         LastChangeText.Text = _lastStatusChange.ToString("HH:mm:ss");
 
-        StatusIndicator.Fill = status switch
+        // Colours come from [theme]; the reason line shares the offline colour
+        StatusIndicator.Fill = ThemeBrush(_config.GetStatusColor(status));
+        StatusReasonText.Foreground = ThemeBrush(_config.ThemeOffline);
+    }
+
+    private static SolidColorBrush ThemeBrush(string value) =>
+        ThemeColor.TryParse(value, out var color)
+            ? new SolidColorBrush(System.Windows.Media.Color.FromRgb(color.R, color.G, color.B))
+            : new SolidColorBrush(Colors.Gray);
+
+    private void UpdateNextCheck()
+    {
+        if (_authService.NextCheckAt is not { } due)
         {
-            AuthStatus.Online => new SolidColorBrush(System.Windows.Media.Color.FromRgb(0x2E, 0xCC, 0x71)),    // green
-            AuthStatus.Offline => new SolidColorBrush(System.Windows.Media.Color.FromRgb(0xE7, 0x4C, 0x3C)),    // red
-            AuthStatus.Connecting => new SolidColorBrush(System.Windows.Media.Color.FromRgb(0xF3, 0x9C, 0x12)),  // amber
-            AuthStatus.Paused => new SolidColorBrush(System.Windows.Media.Color.FromRgb(0x95, 0xA5, 0xA6)),      // gray
-            _ => new SolidColorBrush(Colors.Gray),
-        };
+            NextCheckText.Text = _authService.CurrentStatus != AuthStatus.Paused
+                ? "Checking..."
+                : _authService.ResumeAt is { } resumeAt
+                    ? $"Paused, resumes in {FormatUptime(Max(resumeAt - DateTimeOffset.Now, TimeSpan.Zero))}"
+                    : "Paused";
+            NextCheckProgress.Value = 0;
+            return;
+        }
+
+        var remaining = Max(due - DateTimeOffset.Now, TimeSpan.Zero);
+
+        NextCheckText.Text = $"in {FormatUptime(remaining)}";
+        var total = _authService.NextCheckDelay.TotalSeconds;
+        NextCheckProgress.Value = total > 0 ? 1 - remaining.TotalSeconds / total : 0;
+    }
+
+    // Re-read every tick, since the interval can be changed on the settings page
+    private void UpdateLongIntervalWarning()
+    {
+        LongIntervalText.Visibility = _config.IsIntervalUnusuallyLong ? Visibility.Visible : Visibility.Collapsed;
+        if (!_config.IsIntervalUnusuallyLong)
+            return;
+
+        LongIntervalText.Text = $"Checking only every {FormatUptime(TimeSpan.FromSeconds(_config.Interval))}; " +
+            "a dropped connection can go unnoticed that long. Lower the interval if that isn't intended.";
+        LongIntervalText.Foreground = ThemeBrush(_config.ThemeConnecting);
+    }
+
+    private void UpdateDevices()
+    {
+        DevicesCard.Visibility = _config.AdditionalMacs.Count > 0 ? Visibility.Visible : Visibility.Collapsed;
+        if (_config.AdditionalMacs.Count == 0)
+            return;
+
+        var logins = _authClient.DeviceLogins;
+        DevicesText.Text = string.Join(Environment.NewLine, _config.AdditionalMacs.Select(mac =>
+        {
+            var key = NetworkInfo.NormalizeMacAddress(mac) ?? mac;
+            return logins.TryGetValue(key, out var login)
+                ? $"{mac}: {(login.Result.Success ? "logged in" : login.Result.Message ?? "failed")} at {login.At:HH:mm}"
+                : $"{mac}: waiting for the first cycle online";
+        }));
+    }
+
+    private void UpdateConnectedTime()
+    {
+        var stats = _authClient.Stats;
+        ConnectedForText.Text = stats.OnlineSince is { } since
+            ? $"{FormatUptime(stats.ConnectedFor)} (since {since:HH:mm})"
+            : "Not connected";
+        TotalConnectedText.Text = FormatUptime(stats.TotalConnected);
     }
 
+    // With auto_login off in the settings there is no pause to toggle
     private void UpdatePauseButton()
     {
-        PauseButton.Content = _config.AutoLogin ? "Pause" : "Resume";
+        PauseButton.Content = _authService.IsPaused ? "Resume" : "Pause";
+        PauseButton.IsEnabled = _config.AutoLogin;
     }
 
-    private async void OnLoginNowClicked(object sender, RoutedEventArgs e)
+    private async void OnLoginNowClicked(object sender, RoutedEventArgs e) => await LoginNowAsync();
+
+    /// <summary>
+    /// Triggers a manual login, same as clicking "Login Now". Ignored while one is in flight.
+    /// </summary>
+    public async Task LoginNowAsync()
     {
+        if (!LoginButton.IsEnabled)
+            return;
+
         LoginButton.IsEnabled = false;
         LoginButton.Content = "Logging in...";
         try
         {
-            await _authClient.LoginAsync();
+            await _authService.LoginAsync();
             _logger.LogInformation("Manual login triggered from dashboard");
         }
         catch (Exception ex)
@@ -110,13 +207,61 @@ public partial class DashboardPage : Page
         }
     }
 
+    private async void OnReloginClicked(object sender, RoutedEventArgs e)
+    {
+        ReloginButton.IsEnabled = false;
+        LoginButton.IsEnabled = false;
+        ReloginButton.Content = "Re-logging in...";
+        try
+        {
+            var result = await _authService.ReloginAsync();
+            var logout = result.LoggedOut switch { true => "OK", false => "failed", null => "skipped (no logout_url)" };
+            var login = result.Login.Success ? "OK" : $"failed ({result.Login.Message ?? result.Login.Failure.ToString()})";
+            _logger.LogInformation("Manual re-login from dashboard: logout {Logout}, login {Login}", logout, login);
+            ShowReloginResult($"Logout: {logout} · Login: {login}");
+        }
+        catch (Exception ex)
+        {
+            _logger.LogError(ex, "Manual re-login failed");
+            ShowReloginResult($"Re-login failed: {ex.Message}");
+        }
+        finally
+        {
+            ReloginButton.IsEnabled = true;
+            LoginButton.IsEnabled = true;
+            ReloginButton.Content = "Re-login";
+        }
+    }
+
+    private void ShowReloginResult(string text)
+    {
+        ReloginResultText.Text = text;
+        ReloginResultText.Visibility = Visibility.Visible;
+    }
+
     private void OnPauseResumeClicked(object sender, RoutedEventArgs e)
     {
-        _config.AutoLogin = !_config.AutoLogin;
+        if (_authService.IsPaused)
+        {
+            _authService.Resume();
+            UpdatePauseButton();
+            return;
+        }
+
+        // Pick a duration; "Until resumed" keeps the old indefinite pause
+        PauseButton.ContextMenu.PlacementTarget = PauseButton;
+        PauseButton.ContextMenu.IsOpen = true;
+    }
+
+    private void OnPauseForClicked(object sender, RoutedEventArgs e)
+    {
+        var minutes = int.Parse((string)((System.Windows.Controls.MenuItem)sender).Tag);
+        _authService.Pause(minutes > 0 ? TimeSpan.FromMinutes(minutes) : null);
         UpdatePauseButton();
-        _logger.LogInformation("Auto-login {State} from dashboard", _config.AutoLogin ? "resumed" : "paused");
     }
 
+    private static TimeSpan Max(TimeSpan a, TimeSpan b) => a > b ? a : b;
+
     private static string FormatUptime(TimeSpan ts)
     {
         if (ts.TotalDays >= 1)
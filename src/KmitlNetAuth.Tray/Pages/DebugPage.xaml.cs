@@ -38,13 +38,16 @@ public partial class DebugPage : Page
         ConfigPathText.Text = _configPath;
 
         // Credential store status
-        var hasPassword = !string.IsNullOrEmpty(_config.GetPassword(_credentialStore));
+        var source = _config.GetPasswordSource(_credentialStore);
+        var password = source == CredentialSource.None ? "password not found" : $"password from {source.Describe()}";
         CredentialStoreText.Text = _credentialStore != null
-            ? $"{_credentialStore.GetType().Name} (password {(hasPassword ? "stored" : "not found")})"
-            : "Not available";
+            ? $"{_credentialStore.GetType().Name} ({password})"
+            : $"Not available ({password})";
 
         // Network info
-        MacAddressText.Text = _networkInfo.GetMacAddress();
+        MacAddressText.Text = _config.MacAddress != null
+            ? $"{_authClient.MacAddress} (override, detected {_networkInfo.GetMacAddress(_config.Interface)})"
+            : _authClient.MacAddress;
 
         var (isDhcp, currentIp) = DhcpDetector.GetNetworkStatus();
         CurrentIpText.Text = string.IsNullOrEmpty(currentIp) ? "(unknown)" : currentIp;
@@ -56,6 +59,8 @@ public partial class DebugPage : Page
             $"Heartbeat:      {_config.HeartbeatUrl}\n" +
             $"Internet Check: {_config.InternetCheckUrl}";
 
+        UpdateLastResponse();
+
         // Raw config
         try
         {
@@ -70,6 +75,9 @@ public partial class DebugPage : Page
         }
     }
 
+    private void UpdateLastResponse() =>
+        LastResponseText.Text = _authClient.LastResponse?.ToString() ?? "(no login or heartbeat response yet)";
+
     private async void OnTestLoginClicked(object sender, RoutedEventArgs e)
     {
         TestLoginButton.IsEnabled = false;
@@ -86,6 +94,7 @@ public partial class DebugPage : Page
         finally
         {
             TestLoginButton.IsEnabled = true;
+            UpdateLastResponse();
         }
     }
 
@@ -96,7 +105,10 @@ public partial class DebugPage : Page
         try
         {
             var result = await _authClient.HeartbeatAsync();
-            TestResultText.Text = result ? "Heartbeat: SUCCESS" : "Heartbeat: FAILED";
+            var status = _authClient.LastResponse is { Request: "Heartbeat" } response
+                ? $" (HTTP {response.StatusCode})"
+                : " (no response)";
+            TestResultText.Text = (result ? "Heartbeat: SUCCESS" : "Heartbeat: FAILED") + status;
         }
         catch (Exception ex)
         {
@@ -105,6 +117,7 @@ public partial class DebugPage : Page
         finally
         {
             TestHeartbeatButton.IsEnabled = true;
+            UpdateLastResponse();
         }
     }
 
@@ -9,6 +9,7 @@ namespace KmitlNetAuth.Tray.Pages;
 public partial class LogPage : Page
 {
     private string _currentFilter = "All";
+    private string _searchTerm = "";
 
     public LogPage()
     {
@@ -78,6 +79,15 @@ public partial class LogPage : Page
         }
     }
 
+    private void OnSearchChanged(object sender, TextChangedEventArgs e)
+    {
+        if (LogOutput == null)
+            return;
+
+        _searchTerm = SearchBox.Text.Trim();
+        RefreshLog();
+    }
+
     private void OnClearClicked(object sender, RoutedEventArgs e)
     {
         LogBufferSink.Instance.Clear();
@@ -105,6 +115,9 @@ public partial class LogPage : Page
 
     private bool PassesFilter(string line)
     {
+        if (_searchTerm.Length > 0 && !line.Contains(_searchTerm, StringComparison.OrdinalIgnoreCase))
+            return false;
+
         if (_currentFilter == "All")
             return true;
 
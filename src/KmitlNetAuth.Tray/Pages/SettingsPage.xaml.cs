@@ -1,3 +1,4 @@
+using System.Globalization;
 using System.Runtime.Versioning;
 using System.Windows;
 using System.Windows.Controls;
@@ -30,6 +31,9 @@ public partial class SettingsPage : Page
         PopulateFields();
     }
 
+    /// <summary>Re-reads the interval after it was changed from the tray menu, so Save doesn't revert it.</summary>
+    public void RefreshInterval() => IntervalBox.Value = _config.Interval;
+
     private void PopulateFields()
     {
         // Auth
@@ -64,6 +68,10 @@ public partial class SettingsPage : Page
         AutoUpdateToggle.IsChecked = _config.AutoUpdateCheck;
     }
 
+    private static string Number(double value) => ((long)value).ToString(CultureInfo.InvariantCulture);
+
+    private static string Flag(bool? isChecked) => isChecked == true ? "true" : "false";
+
     private void SelectLogLevel(string level)
     {
         foreach (ComboBoxItem item in LogLevelBox.Items)
@@ -106,39 +114,51 @@ public partial class SettingsPage : Page
         }
         UsernameError.Visibility = Visibility.Collapsed;
 
-        // Auth
-        _config.Username = UsernameBox.Text.Trim();
-        _config.Password = PasswordBox.Password;
-        _config.IpAddress = string.IsNullOrWhiteSpace(IpAddressBox.Text)
-            ? null
-            : IpAddressBox.Text.Trim();
-
-        // Network
-        _config.PortalUrl = PortalUrlBox.Text.Trim();
-        _config.HeartbeatUrl = HeartbeatUrlBox.Text.Trim();
-        _config.InternetCheckUrl = InternetCheckUrlBox.Text.Trim();
-        _config.Timeout = (int)(TimeoutBox.Value ?? 10);
-        _config.AcceptInvalidCerts = AcceptInvalidCertsToggle.IsChecked == true;
-
-        // Service
-        _config.Interval = (ulong)(IntervalBox.Value ?? 300);
-        _config.MaxAttempt = (uint)(MaxAttemptBox.Value ?? 20);
-        _config.BackoffInterval = (int)(BackoffIntervalBox.Value ?? 60);
-        _config.AutoLogin = AutoLoginToggle.IsChecked == true;
-
-        // Logging
-        if (LogLevelBox.SelectedItem is ComboBoxItem levelItem)
-            _config.LogLevel = levelItem.Content?.ToString() ?? "Information";
-        _config.LogRetentionDays = (int)(LogRetentionBox.Value ?? 30);
-
-        // Misc
-        _config.NotificationsEnabled = NotificationsToggle.IsChecked == true;
-        _config.StartMinimized = StartMinimizedToggle.IsChecked == true;
-        _config.AutoUpdateCheck = AutoUpdateToggle.IsChecked == true;
-
+        var logLevel = LogLevelBox.SelectedItem is ComboBoxItem levelItem
+            ? levelItem.Content?.ToString() ?? "Information"
+            : _config.LogLevel;
+        var edits = new Dictionary<string, string>
+        {
+            ["auth.username"] = UsernameBox.Text.Trim(),
+            ["auth.ip_address"] = IpAddressBox.Text.Trim(),
+            ["auth.portal_url"] = PortalUrlBox.Text.Trim(),
+            ["auth.heartbeat_url"] = HeartbeatUrlBox.Text.Trim(),
+            ["auth.internet_check_url"] = InternetCheckUrlBox.Text.Trim(),
+            ["network.timeout"] = Number(TimeoutBox.Value ?? 10),
+            ["network.accept_invalid_certs"] = Flag(AcceptInvalidCertsToggle.IsChecked),
+            ["service.interval"] = Number(IntervalBox.Value ?? 300),
+            ["service.max_attempt"] = Number(MaxAttemptBox.Value ?? 20),
+            ["service.backoff_interval"] = Number(BackoffIntervalBox.Value ?? 60),
+            ["service.auto_login"] = Flag(AutoLoginToggle.IsChecked),
+            ["logging.level"] = logLevel,
+            ["logging.retention_days"] = Number(LogRetentionBox.Value ?? 30),
+            ["notifications.enabled"] = Flag(NotificationsToggle.IsChecked),
+            ["tray.start_minimized"] = Flag(StartMinimizedToggle.IsChecked),
+            ["update.auto_check"] = Flag(AutoUpdateToggle.IsChecked),
+        };
+
+        // Only what was changed here goes into the config file, and the file is edited on its own: the
+        // live config also holds values from the system file and the environment, which don't belong in it
         try
         {
-            _config.Save(_configPath, _credentialStore);
+            var file = Config.LoadFile(_configPath);
+            foreach (var (key, value) in edits.Where(edit => _config.GetValue(edit.Key) != edit.Value))
+            {
+                file.SetValue(key, value);
+                _config.SetValue(key, value);
+            }
+
+            // The credential store keys the password by the file's username
+            if (PasswordBox.Password != _config.GetPassword(_credentialStore))
+            {
+                if (file.Username != _config.Username)
+                    file.SetValue("auth.username", _config.Username);
+                _config.Password = file.Password = PasswordBox.Password;
+            }
+
+            // A password the setup wizard was told not to remember stays out of the store
+            file.RememberPassword = _config.RememberPassword;
+            file.Save(_configPath, _credentialStore);
             SaveStatus.Text = "Saved";
             _logger.LogInformation("Settings saved");
         }
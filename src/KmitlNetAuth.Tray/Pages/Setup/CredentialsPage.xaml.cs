@@ -18,6 +18,11 @@ public partial class CredentialsPage : Page
     /// <summary>Current value of the password field.</summary>
     public string Password => PasswordBox.Password ?? string.Empty;
 
+    /// <summary>
+    /// Whether the password should be saved. When false it is only kept in memory until the app exits.
+    /// </summary>
+    public bool RememberPassword => RememberPasswordBox.IsChecked == true;
+
     /// <summary>
     /// Current value of the IP address field, or null if empty.
     /// </summary>
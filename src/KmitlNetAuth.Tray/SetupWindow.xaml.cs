@@ -157,10 +157,17 @@ public partial class SetupWindow : FluentWindow
 
         try
         {
-            _config.Save(_configPath, _credentialStore);
+            // The singleton also holds system-layer and environment values; only the wizard's go to the file
+            var file = Config.LoadFile(_configPath);
+            file.SetValue("auth.username", _config.Username);
+            file.SetValue("auth.ip_address", _config.IpAddress ?? "");
+            file.Password = _config.Password;
+            file.Save(_configPath, _credentialStore, rememberPassword: _credentialsPage.RememberPassword);
+            _config.RememberPassword = file.RememberPassword;
             _logger?.LogInformation(
-                "Setup wizard completed for user {Username}",
-                _config.Username);
+                "Setup wizard completed for user {Username} (password {Remembered})",
+                _config.Username,
+                _credentialsPage.RememberPassword ? "saved" : "kept for this session only");
         }
         catch (Exception ex)
         {
@@ -7,7 +7,8 @@ public class CliTests
     private static readonly string CliProjectPath = Path.GetFullPath(
         Path.Combine(AppContext.BaseDirectory, "..", "..", "..", "..", "..", "src", "KmitlNetAuth.Cli", "KmitlNetAuth.Cli.csproj"));
 
-    private static async Task<(string StdOut, string StdErr, int ExitCode)> RunCliAsync(string args, int timeoutMs = 60_000)
+    private static async Task<(string StdOut, string StdErr, int ExitCode)> RunCliAsync(string args, int timeoutMs = 60_000,
+        IReadOnlyDictionary<string, string>? environment = null)
     {
         var psi = new ProcessStartInfo
         {
@@ -17,6 +18,8 @@ public class CliTests
             RedirectStandardError = true,
             UseShellExecute = false,
         };
+        foreach (var (name, value) in environment ?? new Dictionary<string, string>())
+            psi.Environment[name] = value;
 
         using var process = Process.Start(psi)!;
         using var cts = new CancellationTokenSource(timeoutMs);
@@ -60,6 +63,79 @@ public class CliTests
         Assert.Contains("Username", output);
     }
 
+    [Fact]
+    public async Task PrintConfig_ShowsSourcesAndExits()
+    {
+        var (stdout, _, exitCode) = await RunCliAsync("--print-config");
+
+        Assert.Contains("[service]", stdout);
+        Assert.Contains("# default", stdout);
+        Assert.Equal(0, exitCode);
+    }
+
+    [Theory]
+    [InlineData("[service]\ninterval = 300\n", 0)]
+    [InlineData("[service]\ninterval = -1\n", 1)]
+    [InlineData("[service\ninterval = 300\n", 1)]
+    [InlineData("[service]\ninterval = \"5 minutes\"\n", 1)]
+    public async Task ConfigCheck_ReportsValidityInExitCode(string content, int expectedExitCode)
+    {
+        var path = Path.Combine(Path.GetTempPath(), $"kmitl_cli_check_{Guid.NewGuid():N}.toml");
+        File.WriteAllText(path, content);
+        try
+        {
+            var (stdout, stderr, exitCode) = await RunCliAsync($"--config-check --config \"{path}\"");
+
+            Assert.Equal(expectedExitCode, exitCode);
+            Assert.Contains(expectedExitCode == 0 ? "OK:" : "Invalid config", stdout + stderr);
+        }
+        finally
+        {
+            File.Delete(path);
+        }
+    }
+
+    [Fact]
+    public async Task EnvironmentOnly_NoConfigFile_ChecksAndPrintsConfigFromEnvironment()
+    {
+        var environment = new Dictionary<string, string>
+        {
+            ["KMITL_CONFIG"] = Path.Combine(Path.GetTempPath(), $"kmitl_cli_missing_{Guid.NewGuid():N}.toml"),
+            ["KMITL_USERNAME"] = "container_user",
+            ["KMITL_PASSWORD"] = "container_pass",
+            ["KMITL_INTERVAL"] = "2m",
+        };
+
+        var check = await RunCliAsync("--config-check", environment: environment);
+        var print = await RunCliAsync("--print-config", environment: environment);
+
+        Assert.Equal(0, check.ExitCode);
+        Assert.Contains("OK: environment only", check.StdOut);
+        Assert.Equal(0, print.ExitCode);
+        Assert.Contains("container_user", print.StdOut);
+        Assert.Contains("interval = 120", print.StdOut);
+        Assert.DoesNotContain("container_pass", print.StdOut);
+        Assert.False(File.Exists(environment["KMITL_CONFIG"]));
+    }
+
+    [Fact]
+    public async Task LogFileAndNoLogFile_AreRejectedTogether()
+    {
+        var (stdout, stderr, exitCode) = await RunCliAsync("--log-file out.log --no-log-file");
+
+        Assert.Contains("cannot be used together", stdout + stderr);
+        Assert.NotEqual(0, exitCode);
+    }
+
+    [Fact]
+    public async Task QuietAndVerbose_AreRejectedTogether()
+    {
+        var (stdout, stderr, exitCode) = await RunCliAsync("-q -v");
+
+        Assert.Contains("--quiet cannot be combined", stdout + stderr);
+        Assert.NotEqual(0, exitCode);
+    }
+
     [Fact]
     public async Task Setup_Help_ShowsSetupInfo()
     {
@@ -69,6 +145,102 @@ public class CliTests
         Assert.Contains("setup", output.ToLowerInvariant());
     }
 
+    [Fact]
+    public async Task Doctor_Help_ShowsDoctorInfo()
+    {
+        var (stdout, stderr, exitCode) = await RunCliAsync("doctor --help");
+        var output = stdout + stderr;
+
+        Assert.Contains("doctor", output.ToLowerInvariant());
+    }
+
+    [Fact]
+    public async Task Heartbeat_Help_ShowsHeartbeatInfo()
+    {
+        var (stdout, stderr, exitCode) = await RunCliAsync("heartbeat --help");
+        var output = stdout + stderr;
+
+        Assert.Contains("raw portal response", output);
+    }
+
+    [Fact]
+    public async Task Logs_NegativeLines_IsRejected()
+    {
+        var (_, stderr, exitCode) = await RunCliAsync("logs --lines -1");
+
+        Assert.NotEqual(0, exitCode);
+        Assert.Contains("--lines must not be negative", stderr);
+    }
+
+    [Fact]
+    public async Task Bench_ZeroCycles_IsRejected()
+    {
+        var (_, stderr, exitCode) = await RunCliAsync("bench --cycles 0");
+
+        Assert.NotEqual(0, exitCode);
+        Assert.Contains("--cycles must be at least 1", stderr);
+    }
+
+    [Fact]
+    public async Task ConfigExport_WritesConfigWithoutPassword()
+    {
+        var tmpDir = Path.Combine(Path.GetTempPath(), $"kmitl_cli_test_{Guid.NewGuid():N}");
+        Directory.CreateDirectory(tmpDir);
+        try
+        {
+            var source = Path.Combine(tmpDir, "config.toml");
+            var exported = Path.Combine(tmpDir, "exported.toml");
+            File.WriteAllText(source, """
+                [auth]
+                username = "export_user"
+                password = "export_secret"
+                """);
+
+            var (_, _, exitCode) = await RunCliAsync($"config export \"{exported}\" --config \"{source}\"");
+
+            Assert.Equal(0, exitCode);
+            var content = File.ReadAllText(exported);
+            Assert.Contains("username = \"export_user\"", content);
+            Assert.DoesNotContain("export_secret", content);
+        }
+        finally
+        {
+            Directory.Delete(tmpDir, true);
+        }
+    }
+
+    [Fact]
+    public async Task ConfigExport_LeavesOutTheApiToken()
+    {
+        var tmpDir = Path.Combine(Path.GetTempPath(), $"kmitl_cli_test_{Guid.NewGuid():N}");
+        Directory.CreateDirectory(tmpDir);
+        try
+        {
+            var source = Path.Combine(tmpDir, "config.toml");
+            var exported = Path.Combine(tmpDir, "exported.toml");
+            File.WriteAllText(source, """
+                [auth]
+                username = "export_user"
+
+                [api]
+                port = 8765
+                token = "export_api_token"
+                """);
+
+            var (_, _, exitCode) = await RunCliAsync($"config export \"{exported}\" --config \"{source}\"");
+
+            Assert.Equal(0, exitCode);
+            var content = File.ReadAllText(exported);
+            Assert.Contains("port = 8765", content);
+            Assert.DoesNotContain("export_api_token", content);
+            Assert.Contains("token = \"\"", content);
+        }
+        finally
+        {
+            Directory.Delete(tmpDir, true);
+        }
+    }
+
     [Fact]
     public async Task UnknownCommand_ShowsError()
     {
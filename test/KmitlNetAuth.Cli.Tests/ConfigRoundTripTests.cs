@@ -18,6 +18,7 @@ public class ConfigRoundTripTests
                 PortalUrl = "https://portal.example.com/login",
                 HeartbeatUrl = "https://heartbeat.example.com/api",
                 InternetCheckUrl = "http://check.example.com/ok",
+                InternetCheckFallbackUrls = ["http://probe1.example.com/204", "http://probe2.example.com/ok"],
                 Timeout = 30,
                 AcceptInvalidCerts = false,
                 HeartbeatUserAgent = "TestAgent/1.0",
@@ -44,6 +45,7 @@ public class ConfigRoundTripTests
             Assert.Equal(original.PortalUrl, loaded.PortalUrl);
             Assert.Equal(original.HeartbeatUrl, loaded.HeartbeatUrl);
             Assert.Equal(original.InternetCheckUrl, loaded.InternetCheckUrl);
+            Assert.Equal(original.InternetCheckFallbackUrls, loaded.InternetCheckFallbackUrls);
             Assert.Equal(original.Timeout, loaded.Timeout);
             Assert.Equal(original.AcceptInvalidCerts, loaded.AcceptInvalidCerts);
             Assert.Equal(original.HeartbeatUserAgent, loaded.HeartbeatUserAgent);
@@ -0,0 +1,55 @@
+using System.Net;
+using System.Text.Json;
+using KmitlNetAuth.Cli.Commands;
+using KmitlNetAuth.Core;
+
+namespace KmitlNetAuth.Cli.Tests;
+
+public class StatusCommandTests
+{
+    private static readonly TimeSpan StepDelay = TimeSpan.FromMilliseconds(100);
+
+    [Theory]
+    [InlineData(true, true, StatusCommand.ExitAuthenticated)]
+    [InlineData(true, false, StatusCommand.ExitNotAuthenticated)]
+    [InlineData(false, true, StatusCommand.ExitOffline)]
+    [InlineData(false, false, StatusCommand.ExitOffline)]
+    public async Task ProbeAsync_PrintsTheResultAndExitsWithItsCode(bool online, bool authenticated, int expectedExitCode)
+    {
+        var config = new Config { Username = "testuser" };
+        using var httpClient = new HttpClient(new StubHandler(request =>
+            request.Method == HttpMethod.Post
+                ? new HttpResponseMessage(authenticated ? HttpStatusCode.OK : HttpStatusCode.Unauthorized)
+                : new HttpResponseMessage(online ? HttpStatusCode.NoContent : HttpStatusCode.ServiceUnavailable)));
+
+        var (json, exitCode) = await StatusCommand.ProbeAsync(config, httpClient);
+
+        using var doc = JsonDocument.Parse(json);
+        Assert.Equal(online, doc.RootElement.GetProperty("online").GetBoolean());
+        Assert.Equal(authenticated, doc.RootElement.GetProperty("authenticated").GetBoolean());
+        Assert.Equal("testuser", doc.RootElement.GetProperty("username").GetString());
+        Assert.Equal(expectedExitCode, exitCode);
+    }
+
+    [Fact]
+    public async Task ProbeAsync_LatencyCoversTheCheckAndTheHeartbeat()
+    {
+        var config = new Config { Username = "testuser" };
+        using var httpClient = new HttpClient(new StubHandler(_ => new HttpResponseMessage(HttpStatusCode.NoContent)));
+
+        var (json, _) = await StatusCommand.ProbeAsync(config, httpClient);
+
+        using var doc = JsonDocument.Parse(json);
+        Assert.True(doc.RootElement.GetProperty("latency_ms").GetInt64() >= (StepDelay * 2).TotalMilliseconds);
+    }
+
+    // Answers every request after StepDelay, so test runs don't depend on the real network
+    private sealed class StubHandler(Func<HttpRequestMessage, HttpResponseMessage> respond) : HttpMessageHandler
+    {
+        protected override async Task<HttpResponseMessage> SendAsync(HttpRequestMessage request, CancellationToken ct)
+        {
+            await Task.Delay(StepDelay, ct);
+            return respond(request);
+        }
+    }
+}
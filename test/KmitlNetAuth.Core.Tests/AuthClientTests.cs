@@ -16,9 +16,9 @@ public sealed class MockHttpHandler : HttpMessageHandler
     public HttpRequestMessage? LastRequest { get; private set; }
     public string? LastRequestContent { get; private set; }
 
-    public void SetResponse(HttpStatusCode status, string content = "")
+    public void SetResponse(HttpStatusCode status, string content = "", string mediaType = "text/plain")
     {
-        _response = new HttpResponseMessage(status) { Content = new StringContent(content) };
+        _response = new HttpResponseMessage(status) { Content = new StringContent(content, null, mediaType) };
         _exception = null;
     }
 
@@ -96,6 +96,33 @@ public sealed class AuthClientTests
         Assert.False(result);
     }
 
+    [Fact]
+    public async Task LoginAsync_HttpError_NotifiesCriticalWithRetry()
+    {
+        _handler.SetResponse(HttpStatusCode.InternalServerError);
+        var client = CreateClient();
+
+        await client.LoginAsync();
+
+        _notificationService.Received(1).Show("Login Failed", Arg.Any<string>(),
+            Arg.Is<NotificationOptions>(o => o.Urgency == NotificationUrgency.Critical && o.OnRetry != null));
+    }
+
+    [Fact]
+    public async Task AttemptLoginAsync_UpdatesStats()
+    {
+        var client = CreateClient();
+        _handler.SetResponse(HttpStatusCode.OK, "Login OK");
+        await client.AttemptLoginAsync();
+        _handler.SetResponse(HttpStatusCode.OK, """{"success": false, "message": "Wrong password"}""");
+        await client.AttemptLoginAsync();
+
+        Assert.Equal(1, client.Stats.Logins);
+        Assert.Equal(1, client.Stats.LoginFailures);
+        Assert.NotNull(client.Stats.LastLoginAt);
+        Assert.Equal("Wrong password", client.Stats.LastError);
+    }
+
     [Fact]
     public async Task LoginAsync_EmptyCredentials_ReturnsFalse()
     {
@@ -108,6 +135,152 @@ public sealed class AuthClientTests
         Assert.False(result);
     }
 
+    [Fact]
+    public async Task AttemptLoginAsync_PortalRejection_ReturnsRejectedWithMessage()
+    {
+        _handler.SetResponse(HttpStatusCode.OK, """{"success": false, "message": "Invalid password"}""");
+        var client = CreateClient();
+
+        var result = await client.AttemptLoginAsync();
+
+        Assert.Equal(LoginFailure.Rejected, result.Failure);
+        Assert.Equal("Invalid password", result.Message);
+    }
+
+    [Fact]
+    public async Task AttemptLoginAsync_Lockout_ReturnsAccountLockedAndNotifies()
+    {
+        _handler.SetResponse(HttpStatusCode.OK, """{"success": false, "message": "Account locked for 30 minutes"}""");
+        var client = CreateClient();
+
+        var result = await client.AttemptLoginAsync();
+
+        Assert.Equal(LoginFailure.AccountLocked, result.Failure);
+        Assert.Equal(TimeSpan.FromMinutes(30), result.RetryAfter);
+        // Retrying straight away would only extend the lockout
+        _notificationService.Received(1).Show("Account Locked", Arg.Any<string>(),
+            Arg.Is<NotificationOptions>(o => o.OnRetry == null));
+    }
+
+    [Fact]
+    public async Task AttemptLoginAsync_HtmlPage_ReturnsUnexpectedResponseWithSnippet()
+    {
+        _handler.SetResponse(HttpStatusCode.OK, "<html><head><title>Access Denied</title></head><body></body></html>");
+        var client = CreateClient();
+
+        var result = await client.AttemptLoginAsync();
+
+        Assert.Equal(LoginFailure.UnexpectedResponse, result.Failure);
+        Assert.Contains("Access Denied", result.Message);
+    }
+
+    [Theory]
+    [InlineData("""{"success": false, "message": "Invalid password"}""", LoginFailure.Rejected)]
+    [InlineData("""{"success": true}""", LoginFailure.None)]
+    public async Task AttemptLoginAsync_JsonServedAsHtml_IsParsedAsJson(string body, LoginFailure expected)
+    {
+        // Only the header says HTML; the body decides
+        _handler.SetResponse(HttpStatusCode.OK, body, "text/html");
+        var client = CreateClient();
+
+        var result = await client.AttemptLoginAsync();
+
+        Assert.Equal(expected, result.Failure);
+    }
+
+    [Theory]
+    [InlineData("<html><body>ยินดีต้อนรับ, you are now online</body></html>", "now online", LoginFailure.None)]
+    [InlineData("""{"code": 0, "data": "ok"}""", @"""code""\s*:\s*0\b", LoginFailure.None)]
+    [InlineData("""{"status": "pending"}""", "now online", LoginFailure.UnexpectedResponse)]
+    [InlineData("""{"success": false, "message": "Invalid password"}""", "now online", LoginFailure.Rejected)]
+    public async Task AttemptLoginAsync_SuccessPattern_OverridesBuiltInChecks(string body, string pattern, LoginFailure expected)
+    {
+        _handler.SetResponse(HttpStatusCode.OK, body);
+        var client = CreateClient(new Config { Username = "testuser", Password = "testpass", LoginSuccessPattern = pattern });
+
+        var result = await client.AttemptLoginAsync();
+
+        Assert.Equal(expected, result.Failure);
+    }
+
+    [Fact]
+    public async Task AttemptLoginAsync_Unauthorized_ReturnsRejected()
+    {
+        _handler.SetResponse(HttpStatusCode.Unauthorized);
+        var client = CreateClient();
+
+        var result = await client.AttemptLoginAsync();
+
+        Assert.Equal(LoginFailure.Rejected, result.Failure);
+    }
+
+    [Fact]
+    public async Task AttemptLoginAsync_TokenRejection_RetriesWithScrapedToken()
+    {
+        var posts = new List<string>();
+        var handler = new StatefulHttpHandler(request =>
+        {
+            if (request.Method == HttpMethod.Get)
+            {
+                return new HttpResponseMessage(HttpStatusCode.OK)
+                {
+                    Content = new StringContent("""<form><input type="hidden" name="csrf_token" value="abc123"></form>"""),
+                };
+            }
+
+            var body = request.Content!.ReadAsStringAsync().Result;
+            posts.Add(body);
+            return new HttpResponseMessage(HttpStatusCode.OK)
+            {
+                Content = new StringContent(body.Contains("csrf_token=abc123")
+                    ? """{"success": true}"""
+                    : """{"success": false, "message": "Invalid CSRF token"}"""),
+            };
+        });
+        var client = new AuthClient(
+            new HttpClient(handler),
+            new Config { Username = "testuser", Password = "testpass" },
+            _networkInfo,
+            _credentialStore,
+            _notificationService,
+            NullLogger<AuthClient>.Instance);
+
+        var result = await client.AttemptLoginAsync();
+
+        Assert.True(result.Success);
+        Assert.Equal(2, posts.Count);
+    }
+
+    [Fact]
+    public async Task AttemptLoginAsync_ConcurrentCalls_AreSerialized()
+    {
+        var inFlight = 0;
+        var maxInFlight = 0;
+        var handler = new StatefulHttpHandler(_ =>
+        {
+            var current = Interlocked.Increment(ref inFlight);
+            maxInFlight = Math.Max(maxInFlight, current);
+            Thread.Sleep(50);
+            Interlocked.Decrement(ref inFlight);
+            return new HttpResponseMessage(HttpStatusCode.OK) { Content = new StringContent("Login OK") };
+        });
+        var client = new AuthClient(
+            new HttpClient(handler),
+            new Config { Username = "testuser", Password = "testpass" },
+            _networkInfo,
+            _credentialStore,
+            _notificationService,
+            NullLogger<AuthClient>.Instance);
+
+        var results = await Task.WhenAll(
+            Task.Run(() => client.AttemptLoginAsync()),
+            Task.Run(() => client.AttemptLoginAsync()),
+            Task.Run(() => client.AttemptLoginAsync()));
+
+        Assert.All(results, r => Assert.True(r.Success));
+        Assert.Equal(1, maxInFlight);
+    }
+
     [Fact]
     public async Task LoginAsync_SendsCorrectFormData()
     {
@@ -132,6 +305,295 @@ public sealed class AuthClientTests
         Assert.Contains("authType=1", content);
     }
 
+    [Fact]
+    public async Task LoginAsync_AuthTypeAndExtraParams_MergedIntoForm()
+    {
+        _handler.SetResponse(HttpStatusCode.OK);
+        var config = new Config
+        {
+            Username = "staffuser",
+            Password = "staffpass",
+            AuthType = "2",
+            ExtraLoginParams = new() { ["agreed"] = "0", ["ssid"] = "KMITL-WIFI" },
+        };
+        var client = CreateClient(config);
+
+        await client.LoginAsync();
+
+        var content = _handler.LastRequestContent;
+        Assert.Contains("authType=2", content);
+        Assert.Contains("agreed=0", content);
+        Assert.Contains("ssid=KMITL-WIFI", content);
+        Assert.DoesNotContain("agreed=1", content);
+    }
+
+    [Theory]
+    [InlineData(true, "10.252.99.1")]
+    [InlineData(false, "10.252.1.1")]
+    public async Task LoginAsync_AcipFromPortalRedirect_UsedWhenLearning(bool learnAcip, string expectedAcip)
+    {
+        string? loginContent = null;
+        var handler = new StatefulHttpHandler(request =>
+        {
+            if (request.Method == HttpMethod.Post)
+            {
+                loginContent = request.Content!.ReadAsStringAsync().Result;
+                return new HttpResponseMessage(HttpStatusCode.OK);
+            }
+
+            // The captive portal intercepts the connectivity probe
+            var redirect = new HttpResponseMessage(HttpStatusCode.Found);
+            redirect.Headers.Location = new Uri("https://portal.kmitl.ac.th/?wlanuserip=10.0.0.5&wlanacip=10.252.99.1");
+            return redirect;
+        });
+        var config = new Config
+        {
+            Username = "testuser",
+            Password = "testpass",
+            Acip = "10.252.1.1",
+            LearnAcip = learnAcip,
+            InternetCheckFallbackUrls = [],
+        };
+        var networkInfo = Substitute.For<INetworkInfo>();
+        networkInfo.GetMacAddress().Returns("aabbccddeeff");
+        var client = new AuthClient(new HttpClient(handler), config, networkInfo, null,
+            _notificationService, NullLogger<AuthClient>.Instance);
+
+        Assert.False(await client.CheckInternetAsync());
+        await client.LoginAsync();
+
+        Assert.Equal(expectedAcip, client.Acip);
+        Assert.Contains($"acip={expectedAcip}", loginContent);
+    }
+
+    [Theory]
+    [InlineData("post_form", true, false)]
+    [InlineData("get_redirect", false, true)]
+    [InlineData("auto", true, true)]
+    [InlineData("auto", false, false)]
+    public async Task LoginAsync_LoginMethod_SendsFieldsAsFormOrQueryString(string method, bool redirectToLogin, bool expectGet)
+    {
+        const string portalUrl = "https://portal.kmitl.ac.th:19008/portalauth/login";
+        HttpMethod? loginMethod = null;
+        string? fields = null;
+        var handler = new StatefulHttpHandler(request =>
+        {
+            if (request.RequestUri!.GetLeftPart(UriPartial.Path) == portalUrl)
+            {
+                loginMethod = request.Method;
+                fields = request.Content?.ReadAsStringAsync().Result ?? request.RequestUri!.Query.TrimStart('?');
+                return new HttpResponseMessage(HttpStatusCode.OK);
+            }
+
+            var redirect = new HttpResponseMessage(HttpStatusCode.Found);
+            redirect.Headers.Location = new Uri(redirectToLogin ? $"{portalUrl}?wlanuserip=10.0.0.5" : "https://portal.kmitl.ac.th/?wlanuserip=10.0.0.5");
+            return redirect;
+        });
+        var config = new Config
+        {
+            Username = "testuser",
+            Password = "testpass",
+            PortalUrl = portalUrl,
+            LoginMethod = method,
+            InternetCheckFallbackUrls = [],
+        };
+        var networkInfo = Substitute.For<INetworkInfo>();
+        networkInfo.GetMacAddress().Returns("aabbccddeeff");
+        var client = new AuthClient(new HttpClient(handler), config, networkInfo, null,
+            _notificationService, NullLogger<AuthClient>.Instance);
+
+        Assert.False(await client.CheckInternetAsync());
+        await client.LoginAsync();
+
+        Assert.Equal(expectGet, client.UsesGetLogin);
+        Assert.Equal(expectGet ? HttpMethod.Get : HttpMethod.Post, loginMethod);
+        Assert.Contains("userName=testuser", fields);
+        Assert.Contains("userPass=testpass", fields);
+    }
+
+    [Fact]
+    public async Task LoginAsync_KeepsLastResponseWithoutPassword()
+    {
+        _handler.SetResponse(HttpStatusCode.OK, """{"success": true, "echo": "formpass"}""");
+        var client = CreateClient(new Config { Username = "formuser", Password = "formpass" });
+
+        await client.LoginAsync();
+
+        Assert.NotNull(client.LastResponse);
+        Assert.Equal("Login", client.LastResponse.Request);
+        Assert.Equal(200, client.LastResponse.StatusCode);
+        Assert.DoesNotContain("formpass", client.LastResponse.Body);
+    }
+
+    [Fact]
+    public async Task AttemptLoginAsync_SessionLimit_NotifiesWithoutRetrying()
+    {
+        var config = new Config { Username = "testuser", Password = "testpass" };
+        var requests = 0;
+        var handler = new StatefulHttpHandler(_ =>
+        {
+            requests++;
+            return new HttpResponseMessage(HttpStatusCode.OK)
+            {
+                Content = new StringContent("""{"success": false, "message": "User is already logged in on another device"}"""),
+            };
+        });
+        var client = new AuthClient(new HttpClient(handler), config, _networkInfo, _credentialStore,
+            _notificationService, NullLogger<AuthClient>.Instance);
+
+        var result = await client.AttemptLoginAsync();
+
+        Assert.Equal(LoginFailure.SessionLimit, result.Failure);
+        Assert.Equal(1, requests);
+        _notificationService.Received(1).Show("Already Logged In Elsewhere", Arg.Any<string>(), Arg.Any<NotificationOptions>());
+    }
+
+    [Fact]
+    public async Task LoginAsync_MacAddressOverride_SentInsteadOfDetected()
+    {
+        _handler.SetResponse(HttpStatusCode.OK);
+        var config = new Config
+        {
+            Username = "macuser",
+            Password = "macpass",
+            MacAddress = "11:22:33:AA:BB:CC",
+        };
+        var client = CreateClient(config);
+
+        await client.LoginAsync();
+
+        Assert.Contains("umac=112233aabbcc", _handler.LastRequestContent);
+    }
+
+    [Fact]
+    public async Task AttemptLoginAsync_RepeatedConnectionErrors_OpenCircuitAndSkip()
+    {
+        _handler.SetException(new HttpRequestException("Connection refused"));
+        var config = new Config
+        {
+            Username = "testuser",
+            Password = "testpass",
+            CircuitBreakerThreshold = 2,
+        };
+        var client = CreateClient(config);
+
+        await client.AttemptLoginAsync();
+        await client.AttemptLoginAsync();
+        var skipped = await client.AttemptLoginAsync();
+
+        Assert.Equal(LoginFailure.CircuitOpen, skipped.Failure);
+        Assert.Equal(CircuitState.Open, client.PortalCircuitState);
+        _notificationService.Received(1).Show("Portal Unreachable", Arg.Any<string>(), Arg.Any<NotificationOptions>());
+    }
+
+    [Fact]
+    public async Task AttemptLoginAsync_ConnectionError_ReportsFailingStage()
+    {
+        _handler.SetException(new HttpRequestException("Name or service not known"));
+        var config = new Config { Username = "testuser", Password = "testpass" };
+        var diagnostics = new PortalDiagnostics((_, _) => throw new System.Net.Sockets.SocketException(11001));
+        var client = new AuthClient(_httpClient, config, _networkInfo, _credentialStore,
+            _notificationService, NullLogger<AuthClient>.Instance, diagnostics);
+
+        var result = await client.DiagnoseFailureAsync(await client.AttemptLoginAsync());
+
+        Assert.Equal(LoginFailure.ConnectionError, result.Failure);
+        Assert.StartsWith("DNS lookup of portal.kmitl.ac.th failed", result.Message);
+    }
+
+    [Fact]
+    public async Task DiagnoseFailureAsync_ProbesOncePerOutage()
+    {
+        var probes = 0;
+        var diagnostics = new PortalDiagnostics((_, _) =>
+        {
+            probes++;
+            throw new System.Net.Sockets.SocketException(11001);
+        });
+        var config = new Config { Username = "testuser", Password = "testpass", CircuitBreakerThreshold = 10 };
+        var client = new AuthClient(_httpClient, config, _networkInfo, _credentialStore,
+            _notificationService, NullLogger<AuthClient>.Instance, diagnostics);
+
+        _handler.SetException(new HttpRequestException("Name or service not known"));
+        await client.DiagnoseFailureAsync(await client.AttemptLoginAsync());
+        var second = await client.DiagnoseFailureAsync(await client.AttemptLoginAsync());
+        Assert.Equal(1, probes);
+        Assert.StartsWith("DNS lookup of portal.kmitl.ac.th failed", second.Message);
+
+        // The portal answering ends the outage, so the next connection error is probed again
+        _handler.SetResponse(HttpStatusCode.OK, "ok");
+        await client.AttemptLoginAsync();
+        _handler.SetException(new HttpRequestException("Name or service not known"));
+        await client.DiagnoseFailureAsync(await client.AttemptLoginAsync());
+        Assert.Equal(2, probes);
+    }
+
+    [Fact]
+    public async Task AttemptLoginAsync_SlowPortal_FailsWithTimeout()
+    {
+        var handler = new DelayingHttpHandler(TimeSpan.FromSeconds(5));
+        var client = new AuthClient(new HttpClient(handler) { Timeout = TimeSpan.FromMilliseconds(100) },
+            new Config { Username = "testuser", Password = "testpass" }, _networkInfo, _credentialStore,
+            _notificationService, NullLogger<AuthClient>.Instance);
+
+        var result = await client.AttemptLoginAsync();
+
+        Assert.Equal(LoginFailure.Timeout, result.Failure);
+    }
+
+    [Fact]
+    public async Task AttemptLoginAsync_ConnectionRefused_FailsWithConnectionRefused()
+    {
+        _handler.SetException(new HttpRequestException("Connection refused",
+            new System.Net.Sockets.SocketException((int)System.Net.Sockets.SocketError.ConnectionRefused)));
+        var client = CreateClient();
+
+        var result = await client.AttemptLoginAsync();
+
+        Assert.Equal(LoginFailure.ConnectionRefused, result.Failure);
+    }
+
+    // --- LogoutAsync ---
+
+    [Fact]
+    public async Task LogoutAsync_PostsToLogoutUrl()
+    {
+        _handler.SetResponse(HttpStatusCode.OK);
+        var client = CreateClient(LogoutConfig());
+
+        var result = await client.LogoutAsync();
+
+        Assert.True(result);
+        Assert.Equal(LogoutConfig().LogoutUrl, _handler.LastRequest!.RequestUri!.ToString());
+        Assert.Contains("userName=testuser", _handler.LastRequestContent);
+    }
+
+    [Fact]
+    public async Task LogoutAsync_NoLogoutUrl_SendsNothing()
+    {
+        _handler.SetResponse(HttpStatusCode.OK);
+        var client = CreateClient();
+
+        Assert.False(await client.LogoutAsync());
+        Assert.Null(_handler.LastRequest);
+    }
+
+    [Fact]
+    public async Task LogoutAsync_NetworkError_ReturnsFalse()
+    {
+        _handler.SetException(new HttpRequestException("Connection refused"));
+        var client = CreateClient(LogoutConfig());
+
+        Assert.False(await client.LogoutAsync());
+    }
+
+    private static Config LogoutConfig() => new()
+    {
+        Username = "testuser",
+        Password = "testpass",
+        LogoutUrl = "https://portal.example.com/logout",
+    };
+
     // --- HeartbeatAsync ---
 
     [Fact]
@@ -177,6 +639,39 @@ public sealed class AuthClientTests
         Assert.Contains("newauth=1", content);
     }
 
+    [Fact]
+    public async Task HeartbeatAsync_NoUserAgentSet_SendsTheDocumentedDefault()
+    {
+        _handler.SetResponse(HttpStatusCode.OK);
+        var client = CreateClient(new Config { Username = "hbuser" });
+
+        await client.HeartbeatAsync();
+
+        // The value CLAUDE.md documents for the real portal; spaces go out form-encoded as '+'
+        Assert.Contains("os=Chrome+v116.0.5845.141+on+Windows+10+64-bit", _handler.LastRequestContent!);
+    }
+
+    [Fact]
+    public async Task HeartbeatAsync_UserAgentList_KeepsOnePickForTheSession()
+    {
+        _handler.SetResponse(HttpStatusCode.OK);
+        var config = new Config
+        {
+            Username = "hbuser",
+            HeartbeatUserAgent = "Single/1.0",
+            HeartbeatUserAgents = ["AgentA/1.0", "AgentB/2.0"],
+        };
+        var client = CreateClient(config);
+
+        await client.HeartbeatAsync();
+        var first = _handler.LastRequestContent!;
+        await client.HeartbeatAsync();
+
+        Assert.DoesNotContain("Single", first);
+        Assert.True(first.Contains("os=AgentA%2F1.0") || first.Contains("os=AgentB%2F2.0"));
+        Assert.Equal(first, _handler.LastRequestContent);
+    }
+
     // --- CheckInternetAsync ---
 
     [Fact]
@@ -211,4 +706,147 @@ public sealed class AuthClientTests
 
         Assert.False(result);
     }
+
+    [Fact]
+    public async Task CheckInternetAsync_PrimaryBlocked_FallsBackToNextProbe()
+    {
+        var requested = new List<string>();
+        var handler = new StatefulHttpHandler(request =>
+        {
+            var url = request.RequestUri!.GetLeftPart(UriPartial.Path);
+            requested.Add(url);
+            return url.Contains("generate_204")
+                ? new HttpResponseMessage(HttpStatusCode.NoContent)
+                : new HttpResponseMessage(HttpStatusCode.Forbidden) { Content = new StringContent("blocked") };
+        });
+        var config = new Config
+        {
+            InternetCheckFallbackUrls = ["http://probe.example/hotspot-detect.html", "http://probe.example/generate_204"],
+        };
+        var client = new AuthClient(new HttpClient(handler), config, _networkInfo, null,
+            _notificationService, NullLogger<AuthClient>.Instance);
+
+        var result = await client.CheckInternetAsync();
+
+        Assert.True(result);
+        Assert.Equal(new[] { config.InternetCheckUrl, "http://probe.example/hotspot-detect.html", "http://probe.example/generate_204" },
+            requested);
+    }
+
+    [Theory]
+    [InlineData(true, false)]
+    [InlineData(false, true)]
+    public async Task CheckInternetAsync_CachedSuccess_IsNotTrustedWhenBypassing(bool bypass, bool expected)
+    {
+        HttpRequestMessage? probe = null;
+        var handler = new StatefulHttpHandler(request =>
+        {
+            probe = request;
+            var response = new HttpResponseMessage(HttpStatusCode.OK) { Content = new StringContent("success") };
+            response.Headers.Age = TimeSpan.FromMinutes(10);
+            return response;
+        });
+        var config = new Config { BypassProbeCache = bypass, InternetCheckFallbackUrls = [] };
+        var client = new AuthClient(new HttpClient(handler), config, _networkInfo, null,
+            _notificationService, NullLogger<AuthClient>.Instance);
+
+        var result = await client.CheckInternetAsync();
+
+        Assert.Equal(expected, result);
+        Assert.NotNull(probe);
+        Assert.Equal(bypass, probe.Headers.CacheControl?.NoCache == true);
+        Assert.Equal(bypass, probe.RequestUri!.Query.StartsWith("?_=", StringComparison.Ordinal));
+    }
+
+    [Fact]
+    public async Task CheckInternetAsync_CdnCacheHit_TriesTheNextProbe()
+    {
+        var requested = new List<string>();
+        var handler = new StatefulHttpHandler(request =>
+        {
+            var url = request.RequestUri!.GetLeftPart(UriPartial.Path);
+            requested.Add(url);
+            var response = new HttpResponseMessage(HttpStatusCode.NoContent);
+            if (requested.Count == 1)
+                response.Headers.Add("X-Cache", "HIT from cdn-edge");
+            return response;
+        });
+        var config = new Config { InternetCheckFallbackUrls = ["http://probe.example/generate_204"] };
+        var client = new AuthClient(new HttpClient(handler), config, _networkInfo, null,
+            _notificationService, NullLogger<AuthClient>.Instance);
+
+        var result = await client.CheckInternetAsync();
+
+        Assert.True(result);
+        Assert.Equal(new[] { config.InternetCheckUrl, "http://probe.example/generate_204" }, requested);
+    }
+
+    [Fact]
+    public async Task CheckInternetAsync_AppleStyleSuccessPage_ReturnsTrue()
+    {
+        _handler.SetResponse(HttpStatusCode.OK, "<HTML><HEAD><TITLE>Success</TITLE></HEAD><BODY>Success</BODY></HTML>");
+        var client = CreateClient(new Config { InternetCheckUrl = "http://captive.apple.com/hotspot-detect.html" });
+
+        var result = await client.CheckInternetAsync();
+
+        Assert.True(result);
+    }
+
+    // --- CheckConnectivityAsync ---
+
+    [Fact]
+    public async Task CheckConnectivityAsync_HeartbeatOnly_UsesHeartbeatUrl()
+    {
+        _handler.SetResponse(HttpStatusCode.OK);
+        var config = new Config { Username = "testuser", ConnectivityCheck = "heartbeat_only" };
+        var client = CreateClient(config);
+
+        var result = await client.CheckConnectivityAsync();
+
+        Assert.True(result);
+        Assert.Equal(config.HeartbeatUrl, _handler.LastRequest!.RequestUri!.ToString());
+    }
+
+    [Fact]
+    public async Task CheckConnectivityAsync_HeartbeatOnlyTimesOut_ReturnsFalse()
+    {
+        // What HttpClient throws when its own timeout fires, not the caller's token
+        _handler.SetException(new TaskCanceledException("The request was canceled due to the configured HttpClient.Timeout"));
+        var config = new Config { Username = "testuser", ConnectivityCheck = "heartbeat_only" };
+        var client = CreateClient(config);
+
+        var result = await client.CheckConnectivityAsync();
+
+        Assert.False(result);
+    }
+
+    [Fact]
+    public async Task CheckConnectivityAsync_GatewayPingWithoutGateway_ReturnsFalse()
+    {
+        var config = new Config { ConnectivityCheck = "gateway_ping" };
+        var client = CreateClient(config);
+
+        var result = await client.CheckConnectivityAsync();
+
+        Assert.False(result);
+        Assert.Null(_handler.LastRequest);
+    }
+}
+
+/// <summary>Answers only after <c>delay</c>, to run past the client timeout.</summary>
+public sealed class DelayingHttpHandler : HttpMessageHandler
+{
+    private readonly TimeSpan _delay;
+
+    public DelayingHttpHandler(TimeSpan delay)
+    {
+        _delay = delay;
+    }
+
+    protected override async Task<HttpResponseMessage> SendAsync(
+        HttpRequestMessage request, CancellationToken cancellationToken)
+    {
+        await Task.Delay(_delay, cancellationToken);
+        return new HttpResponseMessage(HttpStatusCode.OK);
+    }
 }
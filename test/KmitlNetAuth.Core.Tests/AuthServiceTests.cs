@@ -1,3 +1,4 @@
+using System.Net;
 using KmitlNetAuth.Core;
 using KmitlNetAuth.Core.Platform;
 using KmitlNetAuth.Core.Services;
@@ -8,46 +9,72 @@ namespace KmitlNetAuth.Core.Tests;
 
 public sealed class AuthServiceTests
 {
-    private readonly AuthClient _authClient;
-    private readonly Config _config;
+    private readonly Config _config = new()
+    {
+        Username = "testuser",
+        Password = "testpass",
+        AutoLogin = true,
+        Interval = 1,        // 1-second loop for fast tests
+        BackoffInterval = 1,
+        MaxAttempt = 2,
+    };
     private readonly INotificationService _notificationService = Substitute.For<INotificationService>();
 
-    public AuthServiceTests()
+    private static INetworkInfo CreateNetworkInfo()
     {
-        // Build a real AuthClient backed by mocked dependencies so NSubstitute
-        // can intercept the virtual-less methods via a wrapper approach.
-        // AuthClient is sealed and its methods are not virtual, so we cannot
-        // substitute it directly.  Instead we create a real instance whose
-        // HttpClient is controlled by a MockHttpHandler.
-        _config = new Config
-        {
-            Username = "testuser",
-            Password = "testpass",
-            AutoLogin = true,
-            Interval = 1,        // 1-second loop for fast tests
-            BackoffInterval = 1,
-            MaxAttempt = 2,
-        };
-
-        var handler = new MockHttpHandler();
-        handler.SetResponse(System.Net.HttpStatusCode.OK, "success");
-        var httpClient = new HttpClient(handler);
         var networkInfo = Substitute.For<INetworkInfo>();
         networkInfo.GetMacAddress().Returns("aabbccddeeff");
-        var credStore = Substitute.For<ICredentialStore>();
+        return networkInfo;
+    }
+
+    private static HttpResponseMessage Ok(string content = "success") =>
+        new(HttpStatusCode.OK) { Content = new StringContent(content) };
 
-        _authClient = new AuthClient(
-            httpClient, _config, networkInfo, credStore,
+    /// <summary>
+    /// AuthClient is sealed and its methods aren't virtual, so the service gets a real one whose requests
+    /// go to <paramref name="handler"/>: by default a portal that answers everything with "success", so
+    /// the connectivity check, heartbeats and logins all succeed.
+    /// </summary>
+    private AuthClient CreateClient(Config config, HttpMessageHandler? handler = null, INetworkInfo? networkInfo = null) =>
+        new(new HttpClient(handler ?? new StatefulHttpHandler(_ => Ok())), config, networkInfo ?? CreateNetworkInfo(), null,
             _notificationService, NullLogger<AuthClient>.Instance);
+
+    /// <summary>
+    /// A service over a fresh <see cref="CreateClient"/> for <paramref name="config"/> (default <see cref="_config"/>).
+    /// <paramref name="networkInfo"/> also goes to the service, which then waits while it reports no interface up.
+    /// </summary>
+    private AuthService CreateService(Config? config = null, HttpMessageHandler? handler = null, INetworkInfo? networkInfo = null,
+        INetworkChangeMonitor? networkChangeMonitor = null, IPowerStatus? powerStatus = null)
+    {
+        config ??= _config;
+        return CreateService(CreateClient(config, handler, networkInfo), config, networkInfo, networkChangeMonitor, powerStatus);
+    }
+
+    /// <summary>A service over <paramref name="client"/>, for tests that look at the client's stats afterwards.</summary>
+    private AuthService CreateService(AuthClient client, Config config, INetworkInfo? networkInfo = null,
+        INetworkChangeMonitor? networkChangeMonitor = null, IPowerStatus? powerStatus = null,
+        StatusFileWriter? statusFileWriter = null) =>
+        new(client, config, _notificationService, NullLogger<AuthService>.Instance, powerStatus, networkChangeMonitor,
+            statusFileWriter, networkInfo: networkInfo);
+
+    // Runs the loop until duration is up; the cancellation that ends it is the point, not a failure
+    private static async Task RunForAsync(AuthService service, TimeSpan duration)
+    {
+        using var cts = new CancellationTokenSource(duration);
+        try { await service.RunAsync(cts.Token); }
+        catch (OperationCanceledException) { }
     }
 
-    private AuthService CreateService(Config? config = null)
+    // Fails if the changes end (their token was cancelled) before the service gets there
+    private static async Task WaitForStatusAsync(IAsyncEnumerator<AuthStatusChangedEventArgs> changes, AuthStatus status)
     {
-        return new AuthService(
-            _authClient,
-            config ?? _config,
-            _notificationService,
-            NullLogger<AuthService>.Instance);
+        while (await changes.MoveNextAsync())
+        {
+            if (changes.Current.NewStatus == status)
+                return;
+        }
+
+        Assert.Fail($"The status never became {status}");
     }
 
     [Fact]
@@ -61,34 +88,116 @@ public sealed class AuthServiceTests
         // Need a separate AuthClient for this config since AuthService reads config.AutoLogin
         var handler = new MockHttpHandler();
         handler.SetResponse(System.Net.HttpStatusCode.OK, "success");
-        var httpClient = new HttpClient(handler);
-        var networkInfo = Substitute.For<INetworkInfo>();
-        networkInfo.GetMacAddress().Returns("000000000000");
-        var service = new AuthService(
-            new AuthClient(httpClient, config, networkInfo, null,
-                _notificationService, NullLogger<AuthClient>.Instance),
-            config,
-            _notificationService,
-            NullLogger<AuthService>.Instance);
-
-        using var cts = new CancellationTokenSource(TimeSpan.FromMilliseconds(200));
-        try { await service.RunAsync(cts.Token); }
-        catch (OperationCanceledException) { }
+        var service = CreateService(config, handler);
+
+        await RunForAsync(service, TimeSpan.FromMilliseconds(200));
 
         Assert.Equal(AuthStatus.Paused, service.CurrentStatus);
         // The handler should not have received any requests (no login/heartbeat/check)
         Assert.Null(handler.LastRequest);
     }
 
+    [Fact]
+    public void Pause_WithDuration_SetsResumeAt()
+    {
+        var service = CreateService();
+
+        service.Pause(TimeSpan.FromMinutes(30));
+
+        Assert.True(service.IsPaused);
+        Assert.True(_config.AutoLogin); // a pause isn't a config change
+        Assert.NotNull(service.ResumeAt);
+        Assert.InRange(service.ResumeAt.Value - DateTimeOffset.Now, TimeSpan.FromMinutes(29), TimeSpan.FromMinutes(30));
+    }
+
+    [Fact]
+    public void Pause_WithoutDuration_IsIndefinite()
+    {
+        var service = CreateService();
+
+        service.Pause();
+
+        Assert.True(service.IsPaused);
+        Assert.Null(service.ResumeAt);
+    }
+
+    [Fact]
+    public void Resume_ClearsTimedPause()
+    {
+        var service = CreateService();
+        service.Pause(TimeSpan.FromMinutes(30));
+
+        service.Resume();
+
+        Assert.False(service.IsPaused);
+        Assert.Null(service.ResumeAt);
+    }
+
+    [Fact]
+    public async Task RunAsync_TimedPauseExpires_ResumesAutoLogin()
+    {
+        var service = CreateService();
+        service.Pause(TimeSpan.FromMilliseconds(200));
+
+        await RunForAsync(service, TimeSpan.FromMilliseconds(800));
+
+        Assert.False(service.IsPaused);
+        Assert.Null(service.ResumeAt);
+    }
+
+    [Fact]
+    public async Task RunAsync_Paused_WakesAsSoonAsResumed()
+    {
+        var service = CreateService();
+        service.Pause();
+
+        using var cts = new CancellationTokenSource(TimeSpan.FromSeconds(5));
+        await using var changes = service.StatusChangesAsync(cts.Token).GetAsyncEnumerator();
+        var paused = WaitForStatusAsync(changes, AuthStatus.Paused); // subscribes before the service starts
+        var run = service.RunAsync(cts.Token);
+        await paused;
+
+        // Well before the fallback poll, so only the resume itself can wake the loop this soon
+        service.Resume();
+        await WaitForStatusAsync(changes, AuthStatus.Online);
+
+        cts.Cancel();
+        try { await run; }
+        catch (OperationCanceledException) { }
+    }
+
+    [Fact]
+    public async Task RunAsync_PausedInMonitorOnlyMode_KeepsMonitoring()
+    {
+        var config = new Config
+        {
+            Username = "testuser",
+            AutoLogin = false,
+            MonitorOnly = true,
+            Interval = 60,
+        };
+        var handler = new MockHttpHandler();
+        handler.SetResponse(System.Net.HttpStatusCode.OK, "success");
+        var service = CreateService(config, handler);
+
+        using var cts = new CancellationTokenSource(TimeSpan.FromSeconds(5));
+        await using var changes = service.StatusChangesAsync(cts.Token).GetAsyncEnumerator();
+        var online = WaitForStatusAsync(changes, AuthStatus.Online);
+        var run = service.RunAsync(cts.Token);
+        await online;
+
+        Assert.NotNull(handler.LastRequest);
+        cts.Cancel();
+        try { await run; }
+        catch (OperationCanceledException) { }
+    }
+
     [Fact]
     public async Task RunAsync_WhenOnline_CallsHeartbeat()
     {
         // Internet check returns "success", heartbeat returns 200
         var handler = new MockHttpHandler();
         handler.SetResponse(System.Net.HttpStatusCode.OK, "success");
-        var httpClient = new HttpClient(handler);
-        var networkInfo = Substitute.For<INetworkInfo>();
-        networkInfo.GetMacAddress().Returns("aabbccddeeff");
         var config = new Config
         {
             Username = "testuser",
@@ -96,14 +205,9 @@ public sealed class AuthServiceTests
             AutoLogin = true,
             Interval = 1,
         };
-        var client = new AuthClient(httpClient, config, networkInfo, null,
-            _notificationService, NullLogger<AuthClient>.Instance);
-        var service = new AuthService(client, config, _notificationService,
-            NullLogger<AuthService>.Instance);
+        var service = CreateService(config, handler);
 
-        using var cts = new CancellationTokenSource(TimeSpan.FromMilliseconds(500));
-        try { await service.RunAsync(cts.Token); }
-        catch (OperationCanceledException) { }
+        await RunForAsync(service, TimeSpan.FromMilliseconds(500));
 
         // After checking internet (GET) and heartbeat (POST), status should be Online
         Assert.Equal(AuthStatus.Online, service.CurrentStatus);
@@ -136,29 +240,238 @@ public sealed class AuthServiceTests
                 { Content = new StringContent("Login OK") };
         });
 
-        var httpClient = new HttpClient(handler);
-        var networkInfo = Substitute.For<INetworkInfo>();
-        networkInfo.GetMacAddress().Returns("aabbccddeeff");
         var config = new Config
         {
             Username = "testuser",
             Password = "testpass",
             AutoLogin = true,
             Interval = 1,
+            HeartbeatFailureThreshold = 1,
         };
-        var client = new AuthClient(httpClient, config, networkInfo, null,
-            _notificationService, NullLogger<AuthClient>.Instance);
-        var service = new AuthService(client, config, _notificationService,
-            NullLogger<AuthService>.Instance);
+        var service = CreateService(config, handler);
 
-        using var cts = new CancellationTokenSource(TimeSpan.FromMilliseconds(500));
-        try { await service.RunAsync(cts.Token); }
-        catch (OperationCanceledException) { }
+        await RunForAsync(service, TimeSpan.FromMilliseconds(500));
 
         // Should have made at least 3 requests: check internet, heartbeat, login
         Assert.True(callCount >= 3);
     }
 
+    [Theory]
+    [InlineData(2, 1)]
+    [InlineData(3, 0)]
+    public async Task RunAsync_HeartbeatFailures_LoginOnlyAtThreshold(int threshold, int expectedLogins)
+    {
+        var config = new Config
+        {
+            Username = "testuser",
+            Password = "testpass",
+            AutoLogin = true,
+            Interval = 1,
+            HeartbeatFailureThreshold = threshold,
+        };
+        var logins = 0;
+        var handler = new StatefulHttpHandler(request =>
+        {
+            var url = request.RequestUri!.ToString();
+            if (url == config.HeartbeatUrl)
+                return new HttpResponseMessage(System.Net.HttpStatusCode.InternalServerError);
+            if (url == config.PortalUrl)
+                Interlocked.Increment(ref logins);
+            return Ok();
+        });
+        var service = CreateService(config, handler);
+
+        // Two cycles: one heartbeat failure each
+        await RunForAsync(service, TimeSpan.FromMilliseconds(1600));
+
+        Assert.Equal(expectedLogins, logins);
+    }
+
+    [Fact]
+    public async Task RunAsync_IntervalZero_WaitsTheMinimumBetweenChecks()
+    {
+        var config = new Config
+        {
+            Username = "testuser",
+            Password = "testpass",
+            AutoLogin = true,
+            Interval = 0,
+            InternetCheckFallbackUrls = [],
+        };
+        var checks = 0;
+        var handler = new StatefulHttpHandler(request =>
+        {
+            if (request.Method == HttpMethod.Get)
+                Interlocked.Increment(ref checks);
+            return Ok();
+        });
+        var service = CreateService(config, handler);
+
+        await RunForAsync(service, AuthService.MinimumInterval * 2.5);
+
+        // A check about every MinimumInterval; a slow host can delay them, but without the floor there'd be hundreds
+        Assert.True(checks >= 2, $"expected checks to continue after the minimum interval, got {checks}");
+        Assert.True(checks <= 3, $"expected the minimum interval to be waited between checks, got {checks}");
+    }
+
+    [Theory]
+    [InlineData(true)]
+    [InlineData(false)]
+    public async Task RunAsync_LoginTimeout_RetriesAfterTheBackoffInsteadOfTheInterval(bool timedOut)
+    {
+        var config = new Config
+        {
+            Username = "testuser",
+            Password = "testpass",
+            AutoLogin = true,
+            Interval = 3600,
+            BackoffInterval = 1,
+            InternetCheckFallbackUrls = [],
+        };
+        var logins = 0;
+        var handler = new StatefulHttpHandler(request =>
+        {
+            if (request.Method == HttpMethod.Get)
+                return new HttpResponseMessage(System.Net.HttpStatusCode.ServiceUnavailable);
+
+            Interlocked.Increment(ref logins);
+            // HttpClient reports its own timeout as a cancellation the caller didn't ask for
+            if (timedOut)
+                throw new TaskCanceledException("The request was canceled due to the configured HttpClient.Timeout");
+            throw new HttpRequestException("Connection refused",
+                new System.Net.Sockets.SocketException((int)System.Net.Sockets.SocketError.ConnectionRefused));
+        });
+        var service = CreateService(config, handler);
+
+        // A timeout is retried after 1s, then 2s; a refused connection waits out the hour
+        await RunForAsync(service, TimeSpan.FromSeconds(2.5));
+
+        if (timedOut)
+            Assert.True(logins >= 2, $"expected a retry after the backoff, got {logins} login(s)");
+        else
+            Assert.Equal(1, logins);
+    }
+
+    [Fact]
+    public async Task RunAsync_FirstHeartbeatAfterLogin_WaitsGraceAndDoesNotCountFailure()
+    {
+        var config = new Config
+        {
+            Username = "testuser",
+            Password = "testpass",
+            AutoLogin = true,
+            Interval = 5,
+            HeartbeatFailureThreshold = 1,
+            LoginOnStart = true,
+            PostLoginGrace = 2,
+        };
+        var logins = 0;
+        DateTimeOffset? loggedInAt = null, heartbeatAt = null;
+        var handler = new StatefulHttpHandler(request =>
+        {
+            var url = request.RequestUri!.ToString();
+            if (url == config.HeartbeatUrl)
+            {
+                heartbeatAt ??= DateTimeOffset.Now;
+                return new HttpResponseMessage(System.Net.HttpStatusCode.InternalServerError);
+            }
+            if (url == config.PortalUrl)
+            {
+                Interlocked.Increment(ref logins);
+                loggedInAt ??= DateTimeOffset.Now;
+            }
+            return Ok();
+        });
+        var service = CreateService(config, handler);
+
+        // Login on start, then one cycle whose heartbeat fails; threshold 1 would normally re-login
+        await RunForAsync(service, TimeSpan.FromMilliseconds(3000));
+
+        Assert.Equal(1, logins);
+        Assert.NotNull(heartbeatAt);
+        Assert.True(heartbeatAt - loggedInAt >= TimeSpan.FromMilliseconds(1900));
+    }
+
+    [Fact]
+    public async Task StatusChangesAsync_YieldsChangesUntilCancelled()
+    {
+        var config = new Config { AutoLogin = false, Interval = 1 };
+        var service = CreateService(config, new MockHttpHandler());
+
+        using var cts = new CancellationTokenSource(TimeSpan.FromSeconds(5));
+        await using var changes = service.StatusChangesAsync(cts.Token).GetAsyncEnumerator();
+        var next = changes.MoveNextAsync(); // subscribes before the service starts
+        var run = service.RunAsync(cts.Token);
+
+        Assert.True(await next);
+        Assert.Equal(AuthStatus.Offline, changes.Current.OldStatus);
+        Assert.Equal(AuthStatus.Paused, changes.Current.NewStatus);
+
+        cts.Cancel();
+        Assert.False(await changes.MoveNextAsync());
+        try { await run; }
+        catch (OperationCanceledException) { }
+    }
+
+    [Fact]
+    public async Task RunSupervisedAsync_RestartsAfterCrash()
+    {
+        using var cts = new CancellationTokenSource(TimeSpan.FromSeconds(5));
+        var service = Substitute.For<IAuthService>();
+        service.RunAsync(Arg.Any<CancellationToken>()).Returns(
+            _ => Task.FromException(new InvalidOperationException("boom")),
+            _ =>
+            {
+                cts.Cancel();
+                return Task.CompletedTask;
+            });
+
+        await service.RunSupervisedAsync(NullLogger.Instance, cts.Token, restartDelay: TimeSpan.Zero);
+
+        await service.Received(2).RunAsync(Arg.Any<CancellationToken>());
+    }
+
+    [Fact]
+    public async Task RunSupervisedAsync_TooManyRestarts_Rethrows()
+    {
+        var service = Substitute.For<IAuthService>();
+        service.RunAsync(Arg.Any<CancellationToken>()).Returns(_ => Task.FromException(new InvalidOperationException("boom")));
+
+        var ex = await Assert.ThrowsAsync<InvalidOperationException>(() =>
+            service.RunSupervisedAsync(NullLogger.Instance, CancellationToken.None, restartDelay: TimeSpan.Zero, maxRestarts: 2));
+
+        Assert.Equal("boom", ex.Message);
+        await service.Received(3).RunAsync(Arg.Any<CancellationToken>());
+    }
+
+    [Theory]
+    [InlineData(true, 1)]
+    [InlineData(false, 0)]
+    public async Task RunAsync_LoginOnStart_LogsInExactlyOnceWhileOnline(bool loginOnStart, int expectedLogins)
+    {
+        var config = new Config
+        {
+            Username = "testuser",
+            Password = "testpass",
+            AutoLogin = true,
+            Interval = 1,
+            LoginOnStart = loginOnStart,
+        };
+        var logins = 0;
+        var handler = new StatefulHttpHandler(request =>
+        {
+            if (request.RequestUri!.ToString() == config.PortalUrl)
+                Interlocked.Increment(ref logins);
+            return Ok();
+        });
+        var service = CreateService(config, handler);
+
+        // Already online with working heartbeats, so the loop itself never logs in
+        await RunForAsync(service, TimeSpan.FromMilliseconds(2500));
+
+        Assert.Equal(expectedLogins, logins);
+    }
+
     [Fact]
     public async Task RunAsync_WhenOffline_AttemptsLogin()
     {
@@ -176,9 +489,6 @@ public sealed class AuthServiceTests
                 { Content = new StringContent("Login OK") };
         });
 
-        var httpClient = new HttpClient(handler);
-        var networkInfo = Substitute.For<INetworkInfo>();
-        networkInfo.GetMacAddress().Returns("aabbccddeeff");
         var config = new Config
         {
             Username = "testuser",
@@ -187,18 +497,183 @@ public sealed class AuthServiceTests
             Interval = 1,
             MaxAttempt = 5,
         };
-        var client = new AuthClient(httpClient, config, networkInfo, null,
-            _notificationService, NullLogger<AuthClient>.Instance);
-        var service = new AuthService(client, config, _notificationService,
-            NullLogger<AuthService>.Instance);
+        var service = CreateService(config, handler);
 
-        using var cts = new CancellationTokenSource(TimeSpan.FromMilliseconds(500));
-        try { await service.RunAsync(cts.Token); }
-        catch (OperationCanceledException) { }
+        await RunForAsync(service, TimeSpan.FromMilliseconds(500));
 
         Assert.True(loginAttempted);
     }
 
+    [Fact]
+    public async Task RunAsync_MonitorOnly_NeverLogsIn()
+    {
+        var postCount = 0;
+        var handler = new StatefulHttpHandler(request =>
+        {
+            if (request.Method == HttpMethod.Get)
+                return new HttpResponseMessage(System.Net.HttpStatusCode.OK)
+                    { Content = new StringContent("not connected") };
+
+            postCount++;
+            return new HttpResponseMessage(System.Net.HttpStatusCode.OK)
+                { Content = new StringContent("Login OK") };
+        });
+
+        var config = new Config
+        {
+            Username = "testuser",
+            Password = "testpass",
+            AutoLogin = true,
+            MonitorOnly = true,
+            Interval = 1,
+        };
+        var service = CreateService(config, handler);
+
+        await RunForAsync(service, TimeSpan.FromMilliseconds(500));
+
+        Assert.Equal(AuthStatus.Offline, service.CurrentStatus);
+        Assert.Equal(0, postCount);
+    }
+
+    [Fact]
+    public async Task RunAsync_LoginOkButStillOffline_CountsAsFailure()
+    {
+        var config = new Config
+        {
+            Username = "testuser",
+            Password = "testpass",
+            AutoLogin = true,
+            Interval = 60,
+        };
+        var checks = 0;
+        var logins = 0;
+        var handler = new StatefulHttpHandler(request =>
+        {
+            if (request.RequestUri!.GetLeftPart(UriPartial.Path) == config.InternetCheckUrl)
+                Interlocked.Increment(ref checks);
+            if (request.Method == HttpMethod.Post)
+                Interlocked.Increment(ref logins);
+            return new HttpResponseMessage(System.Net.HttpStatusCode.OK)
+                { Content = new StringContent(request.Method == HttpMethod.Post ? "Login OK" : "offline") };
+        });
+        var client = CreateClient(config, handler);
+        var service = CreateService(client, config);
+
+        await RunForAsync(service, TimeSpan.FromMilliseconds(1800));
+
+        Assert.Equal(1, logins);
+        Assert.Equal(2, checks); // the cycle's check, then the re-check after the login
+        Assert.Equal(1, client.Stats.LoginFailures);
+        Assert.Contains("still offline", client.Stats.LastError);
+        _notificationService.DidNotReceive().Show("Login Successful", Arg.Any<string>());
+    }
+
+    [Fact]
+    public async Task LoginAsync_Confirmed_NotifiesSuccess()
+    {
+        var service = CreateService();
+
+        var result = await service.LoginAsync();
+
+        Assert.True(result.Success);
+        _notificationService.Received(1).Show("Login Successful", "Logged in as testuser");
+    }
+
+    [Fact]
+    public async Task RunAsync_OnlineThenOffline_BanksConnectedTime()
+    {
+        var config = new Config
+        {
+            Username = "testuser",
+            Password = "testpass",
+            MonitorOnly = true,
+            Interval = 1,
+            InternetCheckFallbackUrls = [],
+        };
+        var checks = 0;
+        var handler = new StatefulHttpHandler(request =>
+        {
+            var online = request.Method != HttpMethod.Get || Interlocked.Increment(ref checks) == 1;
+            return new HttpResponseMessage(System.Net.HttpStatusCode.OK) { Content = new StringContent(online ? "success" : "offline") };
+        });
+        var client = CreateClient(config, handler);
+        var service = CreateService(client, config);
+
+        await RunForAsync(service, TimeSpan.FromMilliseconds(1600));
+
+        Assert.True(checks >= 2);
+        Assert.Null(client.Stats.OnlineSince);
+        Assert.Equal(TimeSpan.Zero, client.Stats.ConnectedFor);
+        Assert.True(client.Stats.TotalConnected >= TimeSpan.FromMilliseconds(900));
+    }
+
+    [Fact]
+    public async Task RunAsync_Online_SummarizesCycle()
+    {
+        var config = new Config
+        {
+            Username = "testuser",
+            Password = "testpass",
+            InternetCheckFallbackUrls = [],
+        };
+        var handler = new StatefulHttpHandler(_ => Ok());
+        var service = CreateService(config, handler);
+
+        await RunForAsync(service, TimeSpan.FromMilliseconds(500));
+
+        var tick = Assert.IsType<TickOutcome>(service.LastTick);
+        Assert.Equal(1, tick.Cycle);
+        Assert.True(tick.Online);
+        Assert.Equal(TickStep.Ok, tick.Heartbeat);
+        Assert.Equal(TickStep.Skipped, tick.Login);
+        Assert.StartsWith("cycle 1: online=true heartbeat=ok login=skipped latency=", tick.ToString());
+    }
+
+    [Fact]
+    public async Task RunAsync_AdditionalMacs_LogsInEachDeviceWhileOnline()
+    {
+        var config = new Config
+        {
+            Username = "testuser",
+            Password = "testpass",
+            AdditionalMacs = ["11:22:33:44:55:66", "AA-BB-CC-DD-EE-01"],
+            InternetCheckFallbackUrls = [],
+        };
+        var loginBodies = new List<string>();
+        var handler = new StatefulHttpHandler(request =>
+        {
+            if (request.RequestUri!.ToString() == config.PortalUrl)
+            {
+                lock (loginBodies)
+                    loginBodies.Add(request.Content!.ReadAsStringAsync().Result);
+            }
+
+            return Ok();
+        });
+        var client = CreateClient(config, handler);
+        var service = CreateService(client, config);
+
+        await RunForAsync(service, TimeSpan.FromMilliseconds(500));
+
+        Assert.Contains(loginBodies, body => body.Contains("umac=112233445566"));
+        Assert.Contains(loginBodies, body => body.Contains("umac=aabbccddee01"));
+        Assert.DoesNotContain(loginBodies, body => body.Contains("umac=aabbccddeeff"));
+        Assert.True(client.DeviceLogins["112233445566"].Result.Success);
+        Assert.Equal(2, client.DeviceLogins.Count);
+    }
+
+    [Fact]
+    public void EventHistory_KeepsOnlyTheNewestEvents()
+    {
+        var history = new EventHistory(capacity: 2);
+
+        history.Add("status", "Connecting");
+        history.Add("status", "Online");
+        history.Add("cycle", "cycle 1: online=true");
+
+        Assert.Equal(["Online", "cycle 1: online=true"], history.Snapshot().Select(e => e.Message));
+    }
+
     [Fact]
     public async Task RunAsync_MaxAttemptsReached_Backoff()
     {
@@ -214,9 +689,6 @@ public sealed class AuthServiceTests
                 { Content = new StringContent("Login OK") };
         });
 
-        var httpClient = new HttpClient(handler);
-        var networkInfo = Substitute.For<INetworkInfo>();
-        networkInfo.GetMacAddress().Returns("aabbccddeeff");
         var config = new Config
         {
             Username = "testuser",
@@ -226,15 +698,10 @@ public sealed class AuthServiceTests
             MaxAttempt = 2,
             BackoffInterval = 1,
         };
-        var client = new AuthClient(httpClient, config, networkInfo, null,
-            _notificationService, NullLogger<AuthClient>.Instance);
-        var service = new AuthService(client, config, _notificationService,
-            NullLogger<AuthService>.Instance);
+        var service = CreateService(config, handler);
 
         // Run long enough to hit max attempts and backoff
-        using var cts = new CancellationTokenSource(TimeSpan.FromSeconds(4));
-        try { await service.RunAsync(cts.Token); }
-        catch (OperationCanceledException) { }
+        await RunForAsync(service, TimeSpan.FromSeconds(4));
 
         // Should have made exactly MaxAttempt login calls before backing off,
         // then reset and possibly made more. At minimum, MaxAttempt logins happened.
@@ -248,9 +715,6 @@ public sealed class AuthServiceTests
 
         var handler = new MockHttpHandler();
         handler.SetResponse(System.Net.HttpStatusCode.OK, "success");
-        var httpClient = new HttpClient(handler);
-        var networkInfo = Substitute.For<INetworkInfo>();
-        networkInfo.GetMacAddress().Returns("aabbccddeeff");
         var config = new Config
         {
             Username = "testuser",
@@ -258,31 +722,142 @@ public sealed class AuthServiceTests
             AutoLogin = true,
             Interval = 1,
         };
-        var client = new AuthClient(httpClient, config, networkInfo, null,
-            _notificationService, NullLogger<AuthClient>.Instance);
-        var service = new AuthService(client, config, _notificationService,
-            NullLogger<AuthService>.Instance);
+        var service = CreateService(config, handler);
 
         service.StatusChanged += (_, e) =>
             transitions.Add((e.OldStatus, e.NewStatus));
 
-        using var cts = new CancellationTokenSource(TimeSpan.FromMilliseconds(500));
-        try { await service.RunAsync(cts.Token); }
-        catch (OperationCanceledException) { }
+        await RunForAsync(service, TimeSpan.FromMilliseconds(500));
 
         // Should have at least one transition (Offline -> Connecting)
         Assert.NotEmpty(transitions);
         Assert.Equal(AuthStatus.Connecting, transitions[0].New);
     }
 
+    [Fact]
+    public async Task RunAsync_WhenLoginRejected_NeedsAttentionWithReason()
+    {
+        var handler = new StatefulHttpHandler(request =>
+        {
+            if (request.Method == HttpMethod.Get)
+                return new HttpResponseMessage(System.Net.HttpStatusCode.OK)
+                    { Content = new StringContent("offline") };
+
+            return new HttpResponseMessage(System.Net.HttpStatusCode.OK)
+                { Content = new StringContent("""{"success": false, "message": "Wrong password"}""") };
+        });
+
+        var config = new Config
+        {
+            Username = "testuser",
+            Password = "wrongpass",
+            AutoLogin = true,
+            Interval = 1,
+            BackoffInterval = 1,
+        };
+        var service = CreateService(config, handler);
+
+        await RunForAsync(service, TimeSpan.FromMilliseconds(1500));
+
+        // Sticky across cycles: not overwritten by Connecting/Offline.
+        Assert.Equal(AuthStatus.NeedsAttention, service.CurrentStatus);
+        Assert.Equal("Wrong password", service.StatusReason);
+    }
+
+    [Fact]
+    public async Task RunAsync_WhenAccountLocked_WaitsOutLockout()
+    {
+        var loginCount = 0;
+        var handler = new StatefulHttpHandler(request =>
+        {
+            if (request.Method == HttpMethod.Get)
+                return new HttpResponseMessage(System.Net.HttpStatusCode.OK)
+                    { Content = new StringContent("offline") };
+
+            Interlocked.Increment(ref loginCount);
+            return new HttpResponseMessage(System.Net.HttpStatusCode.OK)
+            {
+                Content = new StringContent("""{"success": false, "message": "Account locked", "retryAfter": 3600}"""),
+            };
+        });
+
+        var config = new Config
+        {
+            Username = "testuser",
+            Password = "testpass",
+            AutoLogin = true,
+            Interval = 1,
+            BackoffInterval = 1,
+            MaxAttempt = 5,
+        };
+        var service = CreateService(config, handler);
+
+        await RunForAsync(service, TimeSpan.FromMilliseconds(2500));
+
+        Assert.Equal(1, loginCount);
+        Assert.Equal(AuthStatus.NeedsAttention, service.CurrentStatus);
+        Assert.StartsWith("Account locked", service.StatusReason);
+        Assert.True(service.NextCheckDelay > TimeSpan.FromMinutes(59));
+    }
+
+    [Theory]
+    [InlineData(10.0, 10.2, null)]     // timer jitter
+    [InlineData(10.0, 35.0, null)]     // within the threshold
+    [InlineData(10.0, 3610.0, 3600.0)] // an hour asleep
+    public void DetectSleep_OnlyReportsGapsBeyondThreshold(double expected, double actual, double? slept)
+    {
+        var gap = AuthService.DetectSleep(TimeSpan.FromSeconds(expected), TimeSpan.FromSeconds(actual));
+
+        Assert.Equal(slept, gap?.TotalSeconds);
+    }
+
+    [Theory]
+    [InlineData(true, 20, 1200)]   // on battery, below threshold -> multiplied
+    [InlineData(true, 80, 300)]    // on battery, above threshold
+    [InlineData(false, 20, 300)]   // on AC
+    public void GetEffectiveInterval_AppliesBatteryMultiplier(bool onBattery, int charge, int expectedSeconds)
+    {
+        var config = new Config { Interval = 300, BatteryIntervalMultiplier = 4, BatteryThresholdPercent = 50 };
+        var power = Substitute.For<IPowerStatus>();
+        power.GetPowerState().Returns(new PowerState(onBattery, charge));
+        var service = CreateService(config, powerStatus: power);
+
+        Assert.Equal(TimeSpan.FromSeconds(expectedSeconds), service.GetEffectiveInterval());
+    }
+
+    [Fact]
+    public void GetEffectiveInterval_PowerStatusUnavailable_UsesInterval()
+    {
+        var config = new Config { Interval = 300, BatteryIntervalMultiplier = 4 };
+        var power = Substitute.For<IPowerStatus>();
+        power.GetPowerState().Returns((PowerState?)null);
+        var service = CreateService(config, powerStatus: power);
+
+        Assert.Equal(TimeSpan.FromSeconds(300), service.GetEffectiveInterval());
+    }
+
+    [Fact]
+    public async Task RunAsync_NoInterfaceUp_WaitsWithoutSendingRequests()
+    {
+        var handler = new MockHttpHandler();
+        handler.SetResponse(System.Net.HttpStatusCode.OK, "success");
+        var networkInfo = CreateNetworkInfo();
+        networkInfo.IsNetworkUp(Arg.Any<string?>()).Returns(false);
+        var config = new Config { Username = "testuser", Password = "testpass", Interval = 1 };
+        var service = CreateService(config, handler, networkInfo);
+
+        await RunForAsync(service, TimeSpan.FromMilliseconds(300));
+
+        Assert.Null(handler.LastRequest);
+        Assert.Equal(AuthStatus.Offline, service.CurrentStatus);
+        Assert.Equal("Waiting for network", service.StatusReason);
+    }
+
     [Fact]
     public async Task RunAsync_CancellationToken_StopsGracefully()
     {
         var handler = new MockHttpHandler();
         handler.SetResponse(System.Net.HttpStatusCode.OK, "success");
-        var httpClient = new HttpClient(handler);
-        var networkInfo = Substitute.For<INetworkInfo>();
-        networkInfo.GetMacAddress().Returns("aabbccddeeff");
         var config = new Config
         {
             Username = "testuser",
@@ -290,10 +865,7 @@ public sealed class AuthServiceTests
             AutoLogin = true,
             Interval = 60, // long interval
         };
-        var client = new AuthClient(httpClient, config, networkInfo, null,
-            _notificationService, NullLogger<AuthClient>.Instance);
-        var service = new AuthService(client, config, _notificationService,
-            NullLogger<AuthService>.Instance);
+        var service = CreateService(config, handler);
 
         using var cts = new CancellationTokenSource(TimeSpan.FromMilliseconds(100));
 
@@ -301,6 +873,166 @@ public sealed class AuthServiceTests
         await Assert.ThrowsAnyAsync<OperationCanceledException>(
             () => service.RunAsync(cts.Token));
     }
+
+    [Theory]
+    [InlineData(true, true)]
+    [InlineData(false, false)]
+    public async Task RunAsync_OnShutdown_LogsOutOnlyWhenEnabled(bool logoutOnExit, bool expectLogout)
+    {
+        var config = new Config
+        {
+            Username = "testuser",
+            Password = "testpass",
+            AutoLogin = true,
+            Interval = 60,
+            LogoutUrl = "https://portal.example.com/logout",
+            LogoutOnExit = logoutOnExit,
+        };
+        var paths = new List<string>();
+        var handler = new StatefulHttpHandler(request =>
+        {
+            lock (paths)
+                paths.Add(request.RequestUri!.ToString());
+            return Ok();
+        });
+        var service = CreateService(config, handler);
+
+        await RunForAsync(service, TimeSpan.FromMilliseconds(200));
+
+        Assert.Equal(expectLogout, paths.Contains(config.LogoutUrl));
+    }
+
+    [Theory]
+    [InlineData("https://portal.example.com/logout", false)]
+    [InlineData("", null)]
+    public async Task ReloginAsync_LogsOutIfConfiguredThenInEvenIfLogoutFails(string logoutUrl, bool? expectedLoggedOut)
+    {
+        var config = new Config { Username = "testuser", Password = "testpass", LogoutUrl = logoutUrl };
+        var posts = new List<string>();
+        var handler = new StatefulHttpHandler(request =>
+        {
+            var url = request.RequestUri!.ToString();
+            if (request.Method == HttpMethod.Post)
+                lock (posts)
+                    posts.Add(url);
+            return url == config.LogoutUrl
+                ? new HttpResponseMessage(System.Net.HttpStatusCode.InternalServerError)
+                : Ok();
+        });
+        var service = CreateService(config, handler);
+
+        var result = await service.ReloginAsync();
+
+        Assert.Equal(expectedLoggedOut, result.LoggedOut);
+        Assert.True(result.Login.Success);
+        Assert.Equal(logoutUrl == "" ? new[] { config.PortalUrl } : new[] { config.LogoutUrl, config.PortalUrl }, posts);
+        _notificationService.Received(1).Show("Login Successful", "Logged in as testuser");
+    }
+
+    [Fact]
+    public async Task RunAsync_OnShutdown_WritesSummaryToStatusFile()
+    {
+        var statusFile = Path.Combine(Path.GetTempPath(), $"kmitl_shutdown_test_{Guid.NewGuid():N}.json");
+        var config = new Config
+        {
+            Username = "testuser",
+            Password = "testpass",
+            AutoLogin = true,
+            Interval = 60,
+            LogoutOnExit = true,
+            StatusFile = statusFile,
+        };
+        var client = CreateClient(config);
+        var writer = new StatusFileWriter(config, client, NullLogger<StatusFileWriter>.Instance);
+        var service = CreateService(client, config, statusFileWriter: writer);
+
+        try
+        {
+            await RunForAsync(service, TimeSpan.FromMilliseconds(200));
+
+            using var doc = System.Text.Json.JsonDocument.Parse(File.ReadAllText(statusFile));
+            var shutdown = doc.RootElement.GetProperty("shutdown");
+            Assert.True(shutdown.GetProperty("logged_out").GetBoolean());
+            Assert.True(shutdown.GetProperty("uptime_seconds").GetInt64() >= 0);
+        }
+        finally
+        {
+            File.Delete(statusFile);
+        }
+    }
+
+    [Theory]
+    [InlineData(true, 2)]
+    [InlineData(false, 1)]
+    public async Task RunAsync_OnNetworkChange_ChecksImmediatelyWhenEnabled(bool recheck, int expectedChecks)
+    {
+        var config = new Config
+        {
+            Username = "testuser",
+            Password = "testpass",
+            AutoLogin = true,
+            Interval = 60,
+            RecheckOnNetworkChange = recheck,
+        };
+        var checks = 0;
+        var handler = new StatefulHttpHandler(request =>
+        {
+            if (request.RequestUri!.GetLeftPart(UriPartial.Path) == config.InternetCheckUrl)
+                Interlocked.Increment(ref checks);
+            return Ok();
+        });
+        var monitor = Substitute.For<INetworkChangeMonitor>();
+        var service = CreateService(config, handler, networkChangeMonitor: monitor);
+
+        using var cts = new CancellationTokenSource(TimeSpan.FromSeconds(4));
+        var run = service.RunAsync(cts.Token);
+        await Task.Delay(500);
+        monitor.NetworkChanged += Raise.Event();
+        try { await run; }
+        catch (OperationCanceledException) { }
+
+        Assert.Equal(expectedChecks, checks);
+    }
+
+    [Fact]
+    public async Task RunAsync_IpAddressChange_ChecksImmediatelyWithoutLoggingInWhileOnline()
+    {
+        var config = new Config
+        {
+            Username = "testuser",
+            Password = "testpass",
+            AutoLogin = true,
+            Interval = 60,
+            RecheckOnNetworkChange = false,
+        };
+        var checks = 0;
+        var logins = 0;
+        var handler = new StatefulHttpHandler(request =>
+        {
+            var url = request.RequestUri!.GetLeftPart(UriPartial.Path);
+            if (url == config.InternetCheckUrl)
+                Interlocked.Increment(ref checks);
+            if (url == config.PortalUrl)
+                Interlocked.Increment(ref logins);
+            return Ok();
+        });
+        var networkInfo = CreateNetworkInfo();
+        networkInfo.IsNetworkUp(Arg.Any<string?>()).Returns(true);
+        networkInfo.GetIpAddress(Arg.Any<string?>()).Returns(IPAddress.Parse("10.0.0.5"), IPAddress.Parse("10.0.0.9"));
+        var monitor = Substitute.For<INetworkChangeMonitor>();
+        var service = CreateService(config, handler, networkInfo, monitor);
+
+        using var cts = new CancellationTokenSource(TimeSpan.FromSeconds(4));
+        var run = service.RunAsync(cts.Token);
+        await Task.Delay(500);
+        monitor.NetworkChanged += Raise.Event();
+        try { await run; }
+        catch (OperationCanceledException) { }
+
+        // Still online after the change, so the check and heartbeat are enough
+        Assert.Equal(2, checks);
+        Assert.Equal(0, logins);
+    }
 }
 
 /// <summary>
@@ -0,0 +1,112 @@
+using KmitlNetAuth.Core;
+
+namespace KmitlNetAuth.Core.Tests;
+
+public sealed class CircuitBreakerTests
+{
+    private sealed class ManualTimeProvider : TimeProvider
+    {
+        public DateTimeOffset Now { get; set; } = DateTimeOffset.UnixEpoch;
+        public override DateTimeOffset GetUtcNow() => Now;
+    }
+
+    private readonly ManualTimeProvider _time = new();
+
+    private CircuitBreaker Create(int threshold = 3) => new(threshold, TimeSpan.FromSeconds(60), _time);
+
+    [Fact]
+    public void Closed_UntilThresholdReached()
+    {
+        var breaker = Create();
+
+        Assert.False(breaker.RecordFailure());
+        Assert.False(breaker.RecordFailure());
+
+        Assert.Equal(CircuitState.Closed, breaker.State);
+        Assert.True(breaker.TryAcquire());
+    }
+
+    [Fact]
+    public void Opens_AfterThreshold_AndRejectsCalls()
+    {
+        var breaker = Create();
+
+        breaker.RecordFailure();
+        breaker.RecordFailure();
+        var opened = breaker.RecordFailure();
+
+        Assert.True(opened);
+        Assert.Equal(CircuitState.Open, breaker.State);
+        Assert.False(breaker.TryAcquire());
+    }
+
+    [Fact]
+    public void SuccessResetsFailureCount()
+    {
+        var breaker = Create();
+
+        breaker.RecordFailure();
+        breaker.RecordFailure();
+        breaker.RecordSuccess();
+        breaker.RecordFailure();
+
+        Assert.Equal(CircuitState.Closed, breaker.State);
+    }
+
+    [Fact]
+    public void HalfOpen_AfterCooldown_AllowsSingleProbe()
+    {
+        var breaker = Create(threshold: 1);
+        breaker.RecordFailure();
+
+        _time.Now += TimeSpan.FromSeconds(60);
+
+        Assert.Equal(CircuitState.HalfOpen, breaker.State);
+        Assert.True(breaker.TryAcquire());
+        Assert.False(breaker.TryAcquire());
+    }
+
+    [Fact]
+    public void HalfOpen_ProbeSuccess_Closes()
+    {
+        var breaker = Create(threshold: 1);
+        breaker.RecordFailure();
+        _time.Now += TimeSpan.FromSeconds(60);
+        breaker.TryAcquire();
+
+        breaker.RecordSuccess();
+
+        Assert.Equal(CircuitState.Closed, breaker.State);
+        Assert.True(breaker.TryAcquire());
+    }
+
+    [Fact]
+    public void HalfOpen_ProbeFailure_ReopensForFullCooldown_WithoutNewOpenEvent()
+    {
+        var breaker = Create(threshold: 1);
+        breaker.RecordFailure();
+        _time.Now += TimeSpan.FromSeconds(60);
+        breaker.TryAcquire();
+
+        var openedAgain = breaker.RecordFailure();
+
+        Assert.False(openedAgain);
+        Assert.Equal(CircuitState.Open, breaker.State);
+
+        _time.Now += TimeSpan.FromSeconds(59);
+        Assert.Equal(CircuitState.Open, breaker.State);
+        _time.Now += TimeSpan.FromSeconds(1);
+        Assert.Equal(CircuitState.HalfOpen, breaker.State);
+    }
+
+    [Fact]
+    public void ZeroThreshold_NeverOpens()
+    {
+        var breaker = Create(threshold: 0);
+
+        for (var i = 0; i < 10; i++)
+            breaker.RecordFailure();
+
+        Assert.Equal(CircuitState.Closed, breaker.State);
+    }
+}
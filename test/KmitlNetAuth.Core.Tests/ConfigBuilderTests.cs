@@ -0,0 +1,60 @@
+using KmitlNetAuth.Core;
+using KmitlNetAuth.Core.Exceptions;
+
+namespace KmitlNetAuth.Core.Tests;
+
+public sealed class ConfigBuilderTests
+{
+    [Fact]
+    public void Build_UnsetValues_KeepDefaults()
+    {
+        var config = new ConfigBuilder()
+            .WithCredentials("670xxxxx", "secret")
+            .WithInterval(TimeSpan.FromMinutes(2))
+            .Build();
+
+        Assert.Equal("670xxxxx", config.Username);
+        Assert.Equal("secret", config.Password);
+        Assert.Equal(120UL, config.Interval);
+        Assert.Equal(new Config().PortalUrl, config.PortalUrl);
+        Assert.Equal(20U, config.MaxAttempt);
+    }
+
+    [Fact]
+    public void Build_InvalidValues_ThrowsWithAllErrors()
+    {
+        var builder = new ConfigBuilder()
+            .WithEndpoints(portalUrl: "not a url")
+            .WithTimeout(TimeSpan.Zero);
+
+        var e = Assert.Throws<ConfigException>(() => builder.Build());
+
+        Assert.Contains("portal_url", e.Message);
+        Assert.Contains("timeout", e.Message);
+    }
+
+    [Fact]
+    public void TryBuild_InvalidValues_ReturnsErrors()
+    {
+        var ok = new ConfigBuilder().WithConnectivityCheck("carrier_pigeon").TryBuild(out var config, out var errors);
+
+        Assert.False(ok);
+        Assert.Null(config);
+        Assert.Single(errors);
+    }
+
+    [Fact]
+    public void Build_ReturnsIndependentCopies()
+    {
+        var baseConfig = new Config { Username = "base" };
+        var builder = new ConfigBuilder(baseConfig);
+
+        var first = builder.Build();
+        builder.Configure(c => c.Username = "changed");
+        var second = builder.Build();
+
+        Assert.Equal("base", baseConfig.Username);
+        Assert.Equal("base", first.Username);
+        Assert.Equal("changed", second.Username);
+    }
+}
@@ -23,4 +23,82 @@ public sealed class ConfigPathsTests
         Assert.Contains("kmitlnetauth", result);
         Assert.EndsWith("config.toml", result);
     }
+
+    [Fact]
+    public void Resolve_EnvVar_UsedWhenNoExplicitPath_ExplicitWins()
+    {
+        var previous = Environment.GetEnvironmentVariable("KMITL_CONFIG");
+        Environment.SetEnvironmentVariable("KMITL_CONFIG", "/env/config.toml");
+        try
+        {
+            Assert.Equal("/env/config.toml", ConfigPaths.Resolve());
+            Assert.Equal("/cli/config.toml", ConfigPaths.Resolve("/cli/config.toml"));
+        }
+        finally
+        {
+            Environment.SetEnvironmentVariable("KMITL_CONFIG", previous);
+        }
+    }
+
+    [Fact]
+    public void GetLayers_NonDefaultPath_IsReadOnItsOwn()
+    {
+        var path = Path.Combine(Path.GetTempPath(), $"kmitl_paths_{Guid.NewGuid():N}.toml");
+
+        Assert.Equal([path], ConfigPaths.GetLayers(path));
+    }
+
+    [Fact]
+    public void IsEnvironmentOnly_TrueUntilTheFileExists()
+    {
+        var path = Path.Combine(Path.GetTempPath(), $"kmitl_paths_{Guid.NewGuid():N}.toml");
+        Assert.True(ConfigPaths.IsEnvironmentOnly(path));
+
+        File.WriteAllText(path, "");
+        try
+        {
+            Assert.False(ConfigPaths.IsEnvironmentOnly(path));
+        }
+        finally
+        {
+            File.Delete(path);
+        }
+    }
+
+    [Fact]
+    public void IsEnvironmentOnly_LegacyYamlCountsAsAFile()
+    {
+        var path = Path.Combine(Path.GetTempPath(), $"kmitl_paths_{Guid.NewGuid():N}.toml");
+        var yaml = Path.ChangeExtension(path, ".yaml");
+        File.WriteAllText(yaml, "");
+        try
+        {
+            Assert.False(ConfigPaths.IsEnvironmentOnly(path));
+        }
+        finally
+        {
+            File.Delete(yaml);
+        }
+    }
+
+    [SkipOnWindowsFact]
+    public void DataFiles_UseXdgDataHome_IgnoringRelativeValues()
+    {
+        var previous = Environment.GetEnvironmentVariable("XDG_DATA_HOME");
+        try
+        {
+            Environment.SetEnvironmentVariable("XDG_DATA_HOME", "/xdg/data");
+            Assert.Equal("/xdg/data/kmitlnetauth/status.json", ConfigPaths.GetStatusFilePath());
+            Assert.Equal("/xdg/data/kmitlnetauth/certificates.json", ConfigPaths.GetCertificateStatePath());
+            Assert.Equal("/xdg/data/kmitlnetauth/logs", ConfigPaths.GetLogDirectory());
+
+            Environment.SetEnvironmentVariable("XDG_DATA_HOME", "relative/data");
+            Assert.DoesNotContain("relative", ConfigPaths.GetStatusFilePath());
+            Assert.True(Path.IsPathRooted(ConfigPaths.GetStatusFilePath()));
+        }
+        finally
+        {
+            Environment.SetEnvironmentVariable("XDG_DATA_HOME", previous);
+        }
+    }
 }
@@ -0,0 +1,235 @@
+using System.Net;
+using System.Net.Http.Headers;
+using System.Net.Sockets;
+using System.Text.Json;
+using KmitlNetAuth.Core;
+using KmitlNetAuth.Core.Platform;
+using KmitlNetAuth.Core.Services;
+using Microsoft.Extensions.Logging.Abstractions;
+using NSubstitute;
+using NSubstitute.ExceptionExtensions;
+
+namespace KmitlNetAuth.Core.Tests;
+
+public class ControlApiServerTests
+{
+    private const string Token = "s3cret-token";
+
+    private static int FreePort()
+    {
+        var listener = new TcpListener(IPAddress.Loopback, 0);
+        listener.Start();
+        var port = ((IPEndPoint)listener.LocalEndpoint).Port;
+        listener.Stop();
+        return port;
+    }
+
+    private static (ControlApiServer Server, MockHttpHandler Portal) Create(int port, ILogLevelSwitch? logLevelSwitch = null)
+    {
+        var config = new Config
+        {
+            Username = "testuser",
+            Password = "testpass",
+            ApiPort = port,
+            ApiToken = Token,
+        };
+        var portal = new MockHttpHandler();
+        portal.SetResponse(HttpStatusCode.OK, "Login OK");
+        var networkInfo = Substitute.For<INetworkInfo>();
+        networkInfo.GetMacAddress().Returns("aabbccddeeff");
+        var client = new AuthClient(new HttpClient(portal), config, networkInfo, null,
+            new NullNotificationService(), NullLogger<AuthClient>.Instance);
+        var statusFileWriter = new StatusFileWriter(config, client, NullLogger<StatusFileWriter>.Instance);
+        return (new ControlApiServer(config, client, statusFileWriter, NullLogger<ControlApiServer>.Instance, logLevelSwitch), portal);
+    }
+
+    // The listener starts on a background task; retry until it accepts connections
+    private static async Task<HttpResponseMessage> SendAsync(HttpClient http, HttpMethod method, string url, string? token)
+    {
+        for (var attempt = 0; ; attempt++)
+        {
+            var request = new HttpRequestMessage(method, url);
+            if (token != null)
+                request.Headers.Authorization = new AuthenticationHeaderValue("Bearer", token);
+            try
+            {
+                return await http.SendAsync(request);
+            }
+            catch (HttpRequestException) when (attempt < 50)
+            {
+                await Task.Delay(100);
+            }
+        }
+    }
+
+    [Fact]
+    public async Task Request_WithoutToken_IsUnauthorized()
+    {
+        var port = FreePort();
+        var (server, _) = Create(port);
+        var service = Substitute.For<IAuthService>();
+        using var cts = new CancellationTokenSource();
+        var run = server.RunAsync(service, cts.Token);
+        using var http = new HttpClient();
+
+        using var missing = await SendAsync(http, HttpMethod.Get, $"http://127.0.0.1:{port}/status", null);
+        using var wrong = await SendAsync(http, HttpMethod.Get, $"http://127.0.0.1:{port}/status", "wrong");
+
+        Assert.Equal(HttpStatusCode.Unauthorized, missing.StatusCode);
+        Assert.Equal(HttpStatusCode.Unauthorized, wrong.StatusCode);
+        cts.Cancel();
+        await run;
+    }
+
+    [Fact]
+    public async Task Status_WithToken_ReturnsSnapshotJson()
+    {
+        var port = FreePort();
+        var (server, _) = Create(port);
+        var service = Substitute.For<IAuthService>();
+        service.CurrentStatus.Returns(AuthStatus.Online);
+        using var cts = new CancellationTokenSource();
+        var run = server.RunAsync(service, cts.Token);
+        using var http = new HttpClient();
+
+        using var response = await SendAsync(http, HttpMethod.Get, $"http://127.0.0.1:{port}/status", Token);
+
+        Assert.Equal(HttpStatusCode.OK, response.StatusCode);
+        Assert.Equal("application/json", response.Content.Headers.ContentType?.MediaType);
+        using var doc = JsonDocument.Parse(await response.Content.ReadAsStringAsync());
+        Assert.Equal("Online", doc.RootElement.GetProperty("status").GetString());
+        cts.Cancel();
+        await run;
+    }
+
+    [Fact]
+    public async Task Events_WithToken_ReturnsRecentHistory()
+    {
+        var port = FreePort();
+        var (server, _) = Create(port);
+        var service = Substitute.For<IAuthService>();
+        service.RecentEvents.Returns(new List<ServiceEvent> { new(DateTimeOffset.Now, "status", "Online") });
+        using var cts = new CancellationTokenSource();
+        var run = server.RunAsync(service, cts.Token);
+        using var http = new HttpClient();
+
+        using var response = await SendAsync(http, HttpMethod.Get, $"http://127.0.0.1:{port}/events", Token);
+
+        Assert.Equal(HttpStatusCode.OK, response.StatusCode);
+        using var doc = JsonDocument.Parse(await response.Content.ReadAsStringAsync());
+        var entry = Assert.Single(doc.RootElement.GetProperty("events").EnumerateArray());
+        Assert.Equal("status", entry.GetProperty("kind").GetString());
+        Assert.Equal("Online", entry.GetProperty("message").GetString());
+        cts.Cancel();
+        await run;
+    }
+
+    [Fact]
+    public async Task Login_WithToken_LogsInThroughTheService()
+    {
+        var port = FreePort();
+        var (server, _) = Create(port);
+        var service = Substitute.For<IAuthService>();
+        service.LoginAsync(Arg.Any<CancellationToken>()).Returns(LoginResult.Succeeded());
+        using var cts = new CancellationTokenSource();
+        var run = server.RunAsync(service, cts.Token);
+        using var http = new HttpClient();
+
+        using var wrongMethod = await SendAsync(http, HttpMethod.Get, $"http://127.0.0.1:{port}/login", Token);
+        using var response = await SendAsync(http, HttpMethod.Post, $"http://127.0.0.1:{port}/login", Token);
+
+        Assert.Equal(HttpStatusCode.MethodNotAllowed, wrongMethod.StatusCode);
+        using var doc = JsonDocument.Parse(await response.Content.ReadAsStringAsync());
+        Assert.True(doc.RootElement.GetProperty("success").GetBoolean());
+        await service.Received(1).LoginAsync(Arg.Any<CancellationToken>());
+        cts.Cancel();
+        await run;
+    }
+
+    [Fact]
+    public async Task Request_ServiceThrows_AnswersWithServerError()
+    {
+        var port = FreePort();
+        var (server, _) = Create(port);
+        var service = Substitute.For<IAuthService>();
+        service.LoginAsync(Arg.Any<CancellationToken>()).ThrowsAsync(new InvalidOperationException("boom"));
+        using var cts = new CancellationTokenSource();
+        var run = server.RunAsync(service, cts.Token);
+        using var http = new HttpClient();
+
+        using var response = await SendAsync(http, HttpMethod.Post, $"http://127.0.0.1:{port}/login", Token);
+
+        Assert.Equal(HttpStatusCode.InternalServerError, response.StatusCode);
+        cts.Cancel();
+        await run;
+    }
+
+    [Theory]
+    [InlineData("127.0.0.1", "http://127.0.0.1:8787/")]
+    [InlineData("0.0.0.0", "http://+:8787/")]
+    [InlineData("::", "http://+:8787/")]
+    [InlineData("::1", "http://[::1]:8787/")]
+    [InlineData("[::1]", "http://[::1]:8787/")]
+    [InlineData("localhost", "http://localhost:8787/")]
+    public void Prefix_MapsTheBindAddressForHttpListener(string bind, string expected)
+    {
+        var config = new Config { ApiBind = bind, ApiPort = 8787 };
+        var networkInfo = Substitute.For<INetworkInfo>();
+        networkInfo.GetMacAddress().Returns("aabbccddeeff");
+        var client = new AuthClient(new HttpClient(new MockHttpHandler()), config, networkInfo, null,
+            new NullNotificationService(), NullLogger<AuthClient>.Instance);
+        var server = new ControlApiServer(config, client,
+            new StatusFileWriter(config, client, NullLogger<StatusFileWriter>.Instance), NullLogger<ControlApiServer>.Instance);
+
+        Assert.Equal(expected, server.Prefix);
+    }
+
+    [Fact]
+    public async Task LogLevel_SetsKnownLevelsAndRejectsOthers()
+    {
+        var port = FreePort();
+        var levelSwitch = Substitute.For<ILogLevelSwitch>();
+        levelSwitch.Level.Returns("Debug");
+        var (server, _) = Create(port, levelSwitch);
+        using var cts = new CancellationTokenSource();
+        var run = server.RunAsync(Substitute.For<IAuthService>(), cts.Token);
+        using var http = new HttpClient();
+
+        using var set = await SendAsync(http, HttpMethod.Post, $"http://127.0.0.1:{port}/log-level?level=debug", Token);
+        using var unknown = await SendAsync(http, HttpMethod.Post, $"http://127.0.0.1:{port}/log-level?level=loud", Token);
+        using var get = await SendAsync(http, HttpMethod.Get, $"http://127.0.0.1:{port}/log-level", Token);
+
+        Assert.Equal(HttpStatusCode.OK, set.StatusCode);
+        Assert.Equal(HttpStatusCode.BadRequest, unknown.StatusCode);
+        using var doc = JsonDocument.Parse(await get.Content.ReadAsStringAsync());
+        Assert.Equal("Debug", doc.RootElement.GetProperty("level").GetString());
+        levelSwitch.Received(1).SetLevel("debug");
+        levelSwitch.DidNotReceive().SetLevel("loud");
+        cts.Cancel();
+        await run;
+    }
+
+    [Fact]
+    public async Task LogLevel_WithoutSwitch_IsNotImplemented()
+    {
+        var port = FreePort();
+        var (server, _) = Create(port);
+        using var cts = new CancellationTokenSource();
+        var run = server.RunAsync(Substitute.For<IAuthService>(), cts.Token);
+        using var http = new HttpClient();
+
+        using var response = await SendAsync(http, HttpMethod.Post, $"http://127.0.0.1:{port}/log-level?level=debug", Token);
+
+        Assert.Equal(HttpStatusCode.NotImplemented, response.StatusCode);
+        cts.Cancel();
+        await run;
+    }
+
+    [Fact]
+    public async Task RunAsync_PortZero_ReturnsImmediately()
+    {
+        var (server, _) = Create(0);
+
+        await server.RunAsync(Substitute.For<IAuthService>(), CancellationToken.None);
+    }
+}
@@ -0,0 +1,23 @@
+using KmitlNetAuth.Core.DependencyInjection;
+using KmitlNetAuth.Core.Platform;
+using Microsoft.Extensions.DependencyInjection;
+using NSubstitute;
+
+namespace KmitlNetAuth.Core.Tests;
+
+public sealed class CoreServiceCollectionExtensionsTests
+{
+    [Fact]
+    public void AddKmitlNetAuth_HostDesktopNotifier_ReplacesTheBuiltInOne()
+    {
+        var desktop = Substitute.For<INotificationService>();
+        var services = new ServiceCollection();
+        services.AddKeyedSingleton(CoreServiceCollectionExtensions.DesktopNotificationKey, desktop);
+        services.AddKmitlNetAuth(new Config());
+        using var provider = services.BuildServiceProvider();
+
+        provider.GetRequiredService<INotificationService>().Show("Title", "Body");
+
+        desktop.Received(1).Show("Title", "Body");
+    }
+}
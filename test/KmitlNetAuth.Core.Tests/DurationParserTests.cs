@@ -0,0 +1,30 @@
+namespace KmitlNetAuth.Core.Tests;
+
+public sealed class DurationParserTests
+{
+    [Theory]
+    [InlineData("300", 300)]
+    [InlineData("30s", 30)]
+    [InlineData("5m", 300)]
+    [InlineData("1h", 3600)]
+    [InlineData("1h30m", 5400)]
+    [InlineData(" 2M ", 120)]
+    [InlineData("1d", 86400)]
+    public void TryParseSeconds_ValidInput(string input, int expected)
+    {
+        Assert.True(DurationParser.TryParseSeconds(input, out var seconds));
+        Assert.Equal(expected, seconds);
+    }
+
+    [Theory]
+    [InlineData("")]
+    [InlineData("5 minutes")]
+    [InlineData("m5")]
+    [InlineData("-5m")]
+    [InlineData("5m junk")]
+    [InlineData("99999999999h")]
+    public void TryParseSeconds_InvalidInput(string input)
+    {
+        Assert.False(DurationParser.TryParseSeconds(input, out _));
+    }
+}
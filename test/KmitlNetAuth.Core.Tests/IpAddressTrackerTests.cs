@@ -0,0 +1,52 @@
+using System.Net;
+using KmitlNetAuth.Core;
+
+namespace KmitlNetAuth.Core.Tests;
+
+public class IpAddressTrackerTests
+{
+    [Fact]
+    public void Update_FirstAddress_IsNotAChange()
+    {
+        var tracker = new IpAddressTracker();
+
+        Assert.False(tracker.Update(IPAddress.Parse("10.0.0.5"), out var previous));
+        Assert.Null(previous);
+        Assert.Equal(IPAddress.Parse("10.0.0.5"), tracker.Current);
+    }
+
+    [Fact]
+    public void Update_NewAddress_ReportsThePreviousOne()
+    {
+        var tracker = new IpAddressTracker();
+        tracker.Update(IPAddress.Parse("10.0.0.5"), out _);
+
+        Assert.False(tracker.Update(IPAddress.Parse("10.0.0.5"), out _));
+        Assert.True(tracker.Update(IPAddress.Parse("10.0.0.9"), out var previous));
+        Assert.Equal(IPAddress.Parse("10.0.0.5"), previous);
+        Assert.Equal(IPAddress.Parse("10.0.0.9"), tracker.Current);
+    }
+
+    [Fact]
+    public void Update_GapWithoutAddress_KeepsTheLastOne()
+    {
+        var tracker = new IpAddressTracker();
+        tracker.Update(IPAddress.Parse("10.0.0.5"), out _);
+
+        Assert.False(tracker.Update(null, out _));
+        Assert.False(tracker.Update(IPAddress.Parse("10.0.0.5"), out _));
+        Assert.True(tracker.Update(IPAddress.Parse("10.0.0.7"), out _));
+    }
+
+    [Fact]
+    public void IsChange_DoesNotRecordTheAddress()
+    {
+        var tracker = new IpAddressTracker();
+        Assert.False(tracker.IsChange(IPAddress.Parse("10.0.0.5")));
+        tracker.Update(IPAddress.Parse("10.0.0.5"), out _);
+
+        Assert.True(tracker.IsChange(IPAddress.Parse("10.0.0.9")));
+        Assert.False(tracker.IsChange(null));
+        Assert.Equal(IPAddress.Parse("10.0.0.5"), tracker.Current);
+    }
+}
@@ -0,0 +1,26 @@
+using KmitlNetAuth.Core.Services;
+
+namespace KmitlNetAuth.Core.Tests;
+
+public class LatencySummaryTests
+{
+    [Fact]
+    public void FromSamples_NoSamples_ReturnsNull()
+    {
+        Assert.Null(LatencySummary.FromSamples([]));
+    }
+
+    [Fact]
+    public void FromSamples_ComputesMinAverageMaxAndNearestRankP95()
+    {
+        var samples = Enumerable.Range(1, 20).Select(ms => TimeSpan.FromMilliseconds(ms)).Reverse().ToArray();
+
+        var summary = LatencySummary.FromSamples(samples)!;
+
+        Assert.Equal(20, summary.Samples);
+        Assert.Equal(TimeSpan.FromMilliseconds(1), summary.Min);
+        Assert.Equal(TimeSpan.FromMilliseconds(10.5), summary.Average);
+        Assert.Equal(TimeSpan.FromMilliseconds(20), summary.Max);
+        Assert.Equal(TimeSpan.FromMilliseconds(19), summary.P95);
+    }
+}
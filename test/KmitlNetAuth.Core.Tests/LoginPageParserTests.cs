@@ -0,0 +1,62 @@
+namespace KmitlNetAuth.Core.Tests;
+
+public sealed class LoginPageParserTests
+{
+    [Fact]
+    public void GetHiddenFields_ReturnsOnlyNamedHiddenInputs()
+    {
+        const string html = """
+            <form method="post">
+              <input type="hidden" name="_token" value="a&amp;b">
+              <INPUT TYPE='HIDDEN' NAME='nonce' VALUE='42' />
+              <input type="hidden" value="no-name">
+              <input type="text" name="userName" value="">
+            </form>
+            """;
+
+        var fields = LoginPageParser.GetHiddenFields(html);
+
+        Assert.Equal(2, fields.Count);
+        Assert.Equal("a&b", fields["_token"]);
+        Assert.Equal("42", fields["nonce"]);
+    }
+
+    [Fact]
+    public void GetHiddenFields_NoForm_ReturnsEmpty()
+    {
+        Assert.Empty(LoginPageParser.GetHiddenFields("Login OK"));
+    }
+
+    [Theory]
+    [InlineData("Invalid CSRF token", true)]
+    [InlineData("nonce expired", true)]
+    [InlineData("Invalid password", false)]
+    public void MentionsFormToken_DetectsTokenErrors(string message, bool expected)
+    {
+        Assert.Equal(expected, LoginPageParser.MentionsFormToken(message));
+    }
+
+    [Theory]
+    [InlineData("https://portal.kmitl.ac.th/?wlanuserip=10.0.0.5&wlanacip=10.252.99.1&ssid=KMITL", "10.252.99.1")]
+    [InlineData("http://portal/login?acip=10.252.13.10", "10.252.13.10")]
+    [InlineData("http://portal/login?AC_IP=10.252.20.2&mac=aa", "10.252.20.2")]
+    [InlineData("http://portal/login?ac%2Dip=10.252.20.3", "10.252.20.3")]
+    [InlineData("http://portal/login?acip=not-an-ip", null)]
+    [InlineData("http://portal/login?userip=10.0.0.5", null)]
+    [InlineData("http://portal/login", null)]
+    public void GetAcipFromRedirect_ReadsControllerAddress(string url, string? expected)
+    {
+        Assert.Equal(expected, LoginPageParser.GetAcipFromRedirect(new Uri(url)));
+    }
+
+    [Theory]
+    [InlineData("https://portal.kmitl.ac.th:19008/portalauth/login?wlanuserip=10.0.0.5", true)]
+    [InlineData("https://PORTAL.kmitl.ac.th:19008/portalauth/login?a=1", true)]
+    [InlineData("https://portal.kmitl.ac.th:19008/portalauth/login", false)]
+    [InlineData("https://portal.kmitl.ac.th/?wlanuserip=10.0.0.5", false)]
+    [InlineData("https://portal.kmitl.ac.th:19008/portalauth/other?a=1", false)]
+    public void IsLoginRedirect_MatchesLoginUrlWithQuery(string url, bool expected)
+    {
+        Assert.Equal(expected, LoginPageParser.IsLoginRedirect(new Uri(url), "https://portal.kmitl.ac.th:19008/portalauth/login"));
+    }
+}
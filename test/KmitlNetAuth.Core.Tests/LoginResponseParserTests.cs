@@ -0,0 +1,102 @@
+using KmitlNetAuth.Core;
+
+namespace KmitlNetAuth.Core.Tests;
+
+public sealed class LoginResponseParserTests
+{
+    [Theory]
+    [InlineData("""{"success": false, "message": "Invalid password"}""", "Invalid password")]
+    [InlineData("""{"result": "fail", "msg": "User not found"}""", "User not found")]
+    [InlineData("""{"success": false}""", "Login rejected by portal")]
+    public void TryGetRejection_FailureBody_ReturnsReason(string body, string expected)
+    {
+        Assert.True(LoginResponseParser.TryGetRejection(body, out var reason));
+        Assert.Equal(expected, reason);
+    }
+
+    [Theory]
+    [InlineData("Login OK")]
+    [InlineData("")]
+    [InlineData("""{"success": true}""")]
+    [InlineData("{not json")]
+    public void TryGetRejection_NonRejectionBody_ReturnsFalse(string body)
+    {
+        Assert.False(LoginResponseParser.TryGetRejection(body, out _));
+    }
+
+    [Theory]
+    [InlineData("Login SUCCESSFUL", "success", true)]
+    [InlineData("""{"result": 1}""", @"""result""\s*:\s*1", true)]
+    [InlineData("Login failed", "success", false)]
+    public void MatchesSuccessPattern_MatchesCaseInsensitively(string body, string pattern, bool expected)
+    {
+        Assert.Equal(expected, LoginResponseParser.MatchesSuccessPattern(body, pattern));
+    }
+
+    [Theory]
+    [InlineData("success", true)]
+    [InlineData("(unclosed", false)]
+    public void IsValidPattern_RejectsMalformedRegex(string pattern, bool expected)
+    {
+        Assert.Equal(expected, LoginResponseParser.IsValidPattern(pattern, out var error));
+        Assert.Equal(expected, error == null);
+    }
+
+    [Theory]
+    [InlineData("""{"success": false, "message": "Account locked", "retryAfter": 600}""", "Account locked", 600)]
+    [InlineData("""{"success": false}""", "Too many failed attempts, try again in 15 minutes", 900)]
+    [InlineData("""{"success": false}""", "บัญชีถูกล็อก กรุณารอ 5 นาที", 300)]
+    public void IsLockout_LockoutMessage_ParsesRetryAfter(string body, string reason, int expectedSeconds)
+    {
+        Assert.True(LoginResponseParser.IsLockout(body, reason, out var retryAfter));
+        Assert.Equal(TimeSpan.FromSeconds(expectedSeconds), retryAfter);
+    }
+
+    [Fact]
+    public void IsLockout_NoWaitGiven_ReturnsNullRetryAfter()
+    {
+        Assert.True(LoginResponseParser.IsLockout("{}", "Your account is locked", out var retryAfter));
+        Assert.Null(retryAfter);
+    }
+
+    [Theory]
+    [InlineData("Invalid password")]
+    [InlineData("MAC address blocked")]
+    [InlineData("Too many sessions")]
+    public void IsLockout_OtherRejection_ReturnsFalse(string reason)
+    {
+        Assert.False(LoginResponseParser.IsLockout("{}", reason, out _));
+    }
+
+    [Theory]
+    [InlineData("Session limit reached", true)]
+    [InlineData("User is already logged in on another device", true)]
+    [InlineData("Maximum number of devices exceeded", true)]
+    [InlineData("ผู้ใช้นี้กำลังใช้งานอยู่", true)]
+    [InlineData("Too many sessions", true)]
+    [InlineData("Too many login attempts", false)]
+    [InlineData("Invalid password", false)]
+    public void IsSessionLimit_DetectsConcurrentSessionMessages(string reason, bool expected)
+    {
+        Assert.Equal(expected, LoginResponseParser.IsSessionLimit(reason));
+    }
+
+    [Theory]
+    [InlineData("  <!DOCTYPE html><html></html>", true)]
+    [InlineData("<HTML><HEAD><TITLE>Access Denied</TITLE></HEAD></HTML>", true)]
+    [InlineData("""{"success": true, "message": "<html>"}""", false)]
+    [InlineData("Login OK", false)]
+    [InlineData("<b>not a page", false)]
+    public void IsHtml_DetectsByMarkup(string body, bool expected)
+    {
+        Assert.Equal(expected, LoginResponseParser.IsHtml(body));
+    }
+
+    [Fact]
+    public void Summarize_PrefersTitle_AndTruncates()
+    {
+        Assert.Equal("502 Bad Gateway",
+            LoginResponseParser.Summarize("<html><head><title>\n 502 Bad Gateway </title></head><body>...</body></html>"));
+        Assert.Equal(new string('x', 10) + "...", LoginResponseParser.Summarize(new string('x', 50), maxLength: 10));
+    }
+}
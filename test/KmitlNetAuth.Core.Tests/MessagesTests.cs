@@ -0,0 +1,23 @@
+namespace KmitlNetAuth.Core.Tests;
+
+public sealed class MessagesTests
+{
+    [Theory]
+    [InlineData("en", "Logged in as 67012345")]
+    [InlineData("TH", "เข้าสู่ระบบในชื่อ 67012345")]
+    [InlineData("fr", "Logged in as 67012345")]
+    public void Get_FormatsInLanguageWithEnglishFallback(string language, string expected)
+    {
+        Assert.Equal(expected, Messages.Get(language, MessageKey.LoginSuccessBody, "67012345"));
+    }
+
+    [Fact]
+    public void Get_EveryKeyHasATranslation()
+    {
+        foreach (var language in Messages.Languages)
+        {
+            foreach (var key in Enum.GetValues<MessageKey>())
+                Assert.False(string.IsNullOrWhiteSpace(Messages.Get(language, key)), $"{language}: {key}");
+        }
+    }
+}
@@ -0,0 +1,117 @@
+using System.Collections.Concurrent;
+using System.Net;
+using System.Net.Sockets;
+using System.Text;
+using KmitlNetAuth.Core.Platform;
+using Microsoft.Extensions.Logging.Abstractions;
+using NSubstitute;
+
+namespace KmitlNetAuth.Core.Tests;
+
+/// <summary>
+/// Minimal local stand-in for the KMITL portal, serving the <c>Config.Mock*Path</c> endpoints
+/// over real HTTP so tests exercise the whole client stack.
+/// </summary>
+public sealed class MockPortalServer : IDisposable
+{
+    private readonly HttpListener _listener = new();
+    private readonly CancellationTokenSource _cts = new();
+
+    public string BaseUrl { get; }
+    public ConcurrentQueue<(string Path, string Body)> Requests { get; } = new();
+    public string LoginResponse { get; set; } = """{"success": true}""";
+
+    public MockPortalServer()
+    {
+        BaseUrl = $"http://localhost:{GetFreePort()}";
+        _listener.Prefixes.Add(BaseUrl + "/");
+        _listener.Start();
+        _ = ServeAsync();
+    }
+
+    private async Task ServeAsync()
+    {
+        while (!_cts.IsCancellationRequested)
+        {
+            HttpListenerContext context;
+            try
+            {
+                context = await _listener.GetContextAsync();
+            }
+            catch (Exception) when (_cts.IsCancellationRequested)
+            {
+                return;
+            }
+
+            using var reader = new StreamReader(context.Request.InputStream);
+            var path = context.Request.Url!.AbsolutePath;
+            Requests.Enqueue((path, await reader.ReadToEndAsync()));
+
+            var body = path switch
+            {
+                Config.MockLoginPath => LoginResponse,
+                Config.MockHeartbeatPath => "{}",
+                Config.MockInternetCheckPath => "success",
+                _ => null,
+            };
+
+            context.Response.StatusCode = body == null ? 404 : 200;
+            var bytes = Encoding.UTF8.GetBytes(body ?? "");
+            await context.Response.OutputStream.WriteAsync(bytes);
+            context.Response.Close();
+        }
+    }
+
+    private static int GetFreePort()
+    {
+        using var socket = new TcpListener(IPAddress.Loopback, 0);
+        socket.Start();
+        return ((IPEndPoint)socket.LocalEndpoint).Port;
+    }
+
+    public void Dispose()
+    {
+        _cts.Cancel();
+        _listener.Close();
+    }
+}
+
+public sealed class MockPortalTests : IDisposable
+{
+    private readonly MockPortalServer _portal = new();
+
+    public void Dispose() => _portal.Dispose();
+
+    private AuthClient CreateClient(Config config) => new(
+        new HttpClient(),
+        config,
+        Substitute.For<INetworkInfo>(),
+        null,
+        Substitute.For<INotificationService>(),
+        NullLogger<AuthClient>.Instance);
+
+    [Fact]
+    public async Task ForMockPortal_FullCycle_HitsLocalEndpoints()
+    {
+        var client = CreateClient(Config.ForMockPortal(_portal.BaseUrl));
+
+        Assert.True(await client.LoginAsync());
+        Assert.True(await client.HeartbeatAsync());
+        Assert.True(await client.CheckInternetAsync());
+
+        var paths = _portal.Requests.Select(r => r.Path).ToList();
+        Assert.Equal(new[] { Config.MockLoginPath, Config.MockHeartbeatPath, Config.MockInternetCheckPath }, paths);
+        Assert.Contains("userName=mockuser", _portal.Requests.First().Body);
+    }
+
+    [Fact]
+    public async Task ForMockPortal_Rejection_IsReported()
+    {
+        _portal.LoginResponse = """{"success": false, "message": "Invalid password"}""";
+        var client = CreateClient(Config.ForMockPortal(_portal.BaseUrl));
+
+        var result = await client.AttemptLoginAsync();
+
+        Assert.Equal(LoginFailure.Rejected, result.Failure);
+    }
+}
@@ -28,4 +28,33 @@ public sealed class NetworkInfoTests
         // if we have a real NIC. On CI this may fall back, so we just check it's 12 chars.
         Assert.Equal(12, mac.Length);
     }
+
+    [Theory]
+    [InlineData("AA:BB:CC:DD:EE:FF", "aabbccddeeff")]
+    [InlineData("aa-bb-cc-dd-ee-ff", "aabbccddeeff")]
+    [InlineData("aabb.ccdd.eeff", "aabbccddeeff")]
+    [InlineData("not-a-mac", null)]
+    [InlineData("aabbccddee", null)]
+    public void NormalizeMacAddress_AcceptsCommonFormats(string input, string? expected)
+    {
+        Assert.Equal(expected, NetworkInfo.NormalizeMacAddress(input));
+    }
+
+    [Theory]
+    [InlineData("001122334455", false)]
+    [InlineData("da1122334455", true)]
+    [InlineData("3e1122334455", true)]
+    [InlineData(NetworkInfo.FallbackMacAddress, false)]
+    public void IsLocallyAdministered_ChecksSecondBitOfFirstOctet(string mac, bool expected)
+    {
+        Assert.Equal(expected, NetworkInfo.IsLocallyAdministered(mac));
+    }
+
+    [Fact]
+    public void GetMacAddress_UnknownInterface_ReturnsFallback()
+    {
+        var info = new NetworkInfo();
+
+        Assert.Equal(NetworkInfo.FallbackMacAddress, info.GetMacAddress("no-such-interface-xyz"));
+    }
 }
@@ -0,0 +1,34 @@
+using System.Security.Cryptography;
+using KmitlNetAuth.Core;
+
+namespace KmitlNetAuth.Core.Tests;
+
+public sealed class PasswordCipherTests
+{
+    [Fact]
+    public void EncryptDecrypt_RoundTrip_ReturnsPlaintext()
+    {
+        var encrypted = PasswordCipher.Encrypt("secretpass123", "correct horse");
+
+        Assert.NotEqual("secretpass123", encrypted);
+        Assert.Equal("secretpass123", PasswordCipher.Decrypt(encrypted, "correct horse"));
+    }
+
+    [Fact]
+    public void Encrypt_SameInput_ProducesDifferentOutput()
+    {
+        // Random salt + nonce per call
+        var a = PasswordCipher.Encrypt("secret", "phrase");
+        var b = PasswordCipher.Encrypt("secret", "phrase");
+
+        Assert.NotEqual(a, b);
+    }
+
+    [Fact]
+    public void Decrypt_WrongPassphrase_Throws()
+    {
+        var encrypted = PasswordCipher.Encrypt("secret", "right");
+
+        Assert.ThrowsAny<CryptographicException>(() => PasswordCipher.Decrypt(encrypted, "wrong"));
+    }
+}
@@ -0,0 +1,92 @@
+using System.Security.Cryptography;
+using System.Security.Cryptography.X509Certificates;
+using KmitlNetAuth.Core;
+using KmitlNetAuth.Core.Platform;
+using Microsoft.Extensions.Logging.Abstractions;
+using NSubstitute;
+
+namespace KmitlNetAuth.Core.Tests;
+
+public sealed class PortalCertificateWatcherTests : IDisposable
+{
+    private readonly string _statePath = Path.Combine(Path.GetTempPath(), $"kmitl_cert_test_{Guid.NewGuid():N}", "certificates.json");
+    private readonly INotificationService _notificationService = Substitute.For<INotificationService>();
+    private readonly Config _config = new() { PortalUrl = "https://portal.example.com:19008/login" };
+
+    public void Dispose()
+    {
+        if (Path.GetDirectoryName(_statePath) is { } dir && Directory.Exists(dir))
+            Directory.Delete(dir, true);
+    }
+
+    private PortalCertificateWatcher Create() =>
+        new(_config, _notificationService, NullLogger<PortalCertificateWatcher>.Instance, _statePath);
+
+    private static X509Certificate2 CreateCertificate(string subject)
+    {
+        using var key = RSA.Create(2048);
+        var request = new CertificateRequest($"CN={subject}", key, HashAlgorithmName.SHA256, RSASignaturePadding.Pkcs1);
+        return request.CreateSelfSigned(DateTimeOffset.Now.AddDays(-1), DateTimeOffset.Now.AddDays(30));
+    }
+
+    [Fact]
+    public void Observe_SameCertificateAcrossRuns_DoesNotWarn()
+    {
+        using var certificate = CreateCertificate("portal");
+
+        Create().Observe("portal.example.com", certificate);
+        Create().Observe("portal.example.com", certificate);
+
+        Assert.True(File.Exists(_statePath));
+        _notificationService.DidNotReceiveWithAnyArgs().Show(default!, default!, default!);
+    }
+
+    [Fact]
+    public void Observe_ChangedCertificate_NotifiesOnceAndKeepsTheOldPin()
+    {
+        using var original = CreateCertificate("portal");
+        using var replaced = CreateCertificate("attacker");
+        Create().Observe("portal.example.com", original);
+
+        var watcher = Create();
+        watcher.Observe("portal.example.com", replaced);
+        watcher.Observe("portal.example.com", replaced);
+        watcher.Observe("portal.example.com", original);
+
+        _notificationService.Received(1).Show(Arg.Any<string>(), Arg.Is<string>(body => body.Contains("portal.example.com")),
+            Arg.Is<NotificationOptions>(options => options.Urgency == NotificationUrgency.Critical));
+        Assert.Equal(replaced.GetCertHashString(HashAlgorithmName.SHA256), Assert.Single(watcher.GetPending()).Value);
+    }
+
+    [Fact]
+    public void AcceptPending_PinsTheNewCertificateForOtherInstances()
+    {
+        using var original = CreateCertificate("portal");
+        using var renewed = CreateCertificate("renewed");
+        var service = Create();
+        service.Observe("portal.example.com", original);
+        service.Observe("portal.example.com", renewed);
+
+        Assert.True(Create().AcceptPending("portal.example.com"));
+        service.Observe("portal.example.com", renewed);
+        Create().Observe("portal.example.com", renewed);
+
+        Assert.Empty(service.GetPending());
+        Assert.False(Create().AcceptPending("portal.example.com"));
+        _notificationService.Received(1).Show(Arg.Any<string>(), Arg.Any<string>(), Arg.Any<NotificationOptions>());
+    }
+
+    [Theory]
+    [InlineData(true, "www.example.org")]
+    [InlineData(false, "portal.example.com")]
+    public void Observe_OtherHostOrDisabled_IsIgnored(bool watch, string host)
+    {
+        _config.WatchCertificate = watch;
+
+        Create().Observe(host, CreateCertificate("one"));
+        Create().Observe(host, CreateCertificate("two"));
+
+        Assert.False(File.Exists(_statePath));
+        _notificationService.DidNotReceiveWithAnyArgs().Show(default!, default!, default!);
+    }
+}
@@ -0,0 +1,54 @@
+using System.Net;
+using System.Net.Sockets;
+using KmitlNetAuth.Core;
+
+namespace KmitlNetAuth.Core.Tests;
+
+public class PortalDiagnosticsTests
+{
+    private static readonly TimeSpan Timeout = TimeSpan.FromSeconds(5);
+
+    [Fact]
+    public async Task DiagnoseAsync_UnresolvableHost_FailsAtDns()
+    {
+        var diagnostics = new PortalDiagnostics((_, _) => throw new SocketException((int)SocketError.HostNotFound));
+
+        var diagnosis = await diagnostics.DiagnoseAsync(new Uri("https://portal.example.com:19008/login"), Timeout, false);
+
+        Assert.Equal(ReachabilityStage.Dns, diagnosis.FailedStage);
+        Assert.Contains("portal.example.com", diagnosis.Summary);
+    }
+
+    [Fact]
+    public async Task DiagnoseAsync_ClosedPort_FailsAtTcp()
+    {
+        var listener = new TcpListener(IPAddress.Loopback, 0);
+        listener.Start();
+        var port = ((IPEndPoint)listener.LocalEndpoint).Port;
+        listener.Stop();
+
+        var diagnosis = await new PortalDiagnostics().DiagnoseAsync(new Uri($"http://127.0.0.1:{port}/"), Timeout, false);
+
+        Assert.Equal(ReachabilityStage.Tcp, diagnosis.FailedStage);
+        Assert.Contains($"127.0.0.1:{port}", diagnosis.Summary);
+    }
+
+    [Fact]
+    public async Task DiagnoseAsync_ListeningHttpPort_IsReachable()
+    {
+        var listener = new TcpListener(IPAddress.Loopback, 0);
+        listener.Start();
+        try
+        {
+            var port = ((IPEndPoint)listener.LocalEndpoint).Port;
+
+            var diagnosis = await new PortalDiagnostics().DiagnoseAsync(new Uri($"http://127.0.0.1:{port}/"), Timeout, false);
+
+            Assert.True(diagnosis.Reachable);
+        }
+        finally
+        {
+            listener.Stop();
+        }
+    }
+}
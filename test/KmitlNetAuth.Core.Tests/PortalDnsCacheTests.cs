@@ -0,0 +1,53 @@
+using System.Net;
+using System.Net.Sockets;
+using KmitlNetAuth.Core;
+using Microsoft.Extensions.Logging.Abstractions;
+
+namespace KmitlNetAuth.Core.Tests;
+
+public class PortalDnsCacheTests
+{
+    [Fact]
+    public async Task WarmUp_ResolvesEachPortalHostOnce_PreferringIpv4()
+    {
+        var lookups = new List<string>();
+        var cache = new PortalDnsCache(NullLogger<PortalDnsCache>.Instance, (host, _) =>
+        {
+            lookups.Add(host);
+            return Task.FromResult(new[] { IPAddress.Parse("2001:db8::1"), IPAddress.Parse("10.252.1.1") });
+        });
+        var config = new Config
+        {
+            PortalUrl = "https://portal.example.com:19008/login",
+            HeartbeatUrl = "https://heartbeat.example.com/api",
+            LogoutUrl = "https://PORTAL.example.com:19008/logout",
+        };
+
+        await cache.WarmUpAsync(config);
+
+        Assert.Equal(new[] { "portal.example.com", "heartbeat.example.com" }, lookups);
+        Assert.Equal(new[] { IPAddress.Parse("10.252.1.1") }, cache.GetCached("portal.example.com"));
+    }
+
+    [Fact]
+    public async Task WarmUp_SkipsIpLiteralsAndSurvivesResolveFailures()
+    {
+        var lookups = new List<string>();
+        var cache = new PortalDnsCache(NullLogger<PortalDnsCache>.Instance, (host, _) =>
+        {
+            lookups.Add(host);
+            throw new SocketException((int)SocketError.HostNotFound);
+        });
+        var config = new Config
+        {
+            PortalUrl = "http://127.0.0.1:8080/login",
+            HeartbeatUrl = "https://heartbeat.example.com/api",
+            LogoutUrl = "http://127.0.0.1:8080/logout",
+        };
+
+        await cache.WarmUpAsync(config);
+
+        Assert.Equal(new[] { "heartbeat.example.com" }, lookups);
+        Assert.Null(cache.GetCached("heartbeat.example.com"));
+    }
+}
@@ -0,0 +1,56 @@
+using System.Net.Http.Headers;
+using System.Text;
+using KmitlNetAuth.Core;
+
+namespace KmitlNetAuth.Core.Tests;
+
+public class PortalResponseDecoderTests
+{
+    // "สำเร็จ" (success) in TIS-620
+    private static readonly byte[] ThaiSuccessTis620 = [0xCA, 0xD3, 0xE0, 0xC3, 0xE7, 0xA8];
+
+    [Theory]
+    [InlineData("TIS-620")]
+    [InlineData("windows-874")]
+    [InlineData("iso-8859-11")]
+    public void Decode_ThaiCharsetFromHeader_DecodesThai(string charset)
+    {
+        Assert.Equal("สำเร็จ", PortalResponseDecoder.Decode(ThaiSuccessTis620, charset));
+    }
+
+    [Fact]
+    public void Decode_CharsetOnlyInMetaTag_UsesIt()
+    {
+        var body = Encoding.ASCII.GetBytes("<html><head><meta http-equiv=\"Content-Type\" content=\"text/html; charset=tis-620\"></head><body>")
+            .Concat(ThaiSuccessTis620).Concat(Encoding.ASCII.GetBytes("</body></html>")).ToArray();
+
+        Assert.Contains("<body>สำเร็จ</body>", PortalResponseDecoder.Decode(body, null));
+    }
+
+    [Theory]
+    [InlineData(null)]
+    [InlineData("no-such-charset")]
+    public void Decode_NoOrUnknownCharset_FallsBackToUtf8(string? charset)
+    {
+        var body = Encoding.UTF8.GetBytes("{\"message\": \"เข้าสู่ระบบสำเร็จ\"}");
+
+        Assert.Equal("{\"message\": \"เข้าสู่ระบบสำเร็จ\"}", PortalResponseDecoder.Decode(body, charset));
+    }
+
+    [Fact]
+    public void Decode_Utf8Bom_IsStrippedAndWinsOverHeader()
+    {
+        var body = Encoding.UTF8.GetPreamble().Concat(Encoding.UTF8.GetBytes("success")).ToArray();
+
+        Assert.Equal("success", PortalResponseDecoder.Decode(body, "tis-620"));
+    }
+
+    [Fact]
+    public async Task ReadAsync_UsesContentTypeCharset()
+    {
+        var content = new ByteArrayContent(ThaiSuccessTis620);
+        content.Headers.ContentType = new MediaTypeHeaderValue("text/plain") { CharSet = "tis-620" };
+
+        Assert.Equal("สำเร็จ", await PortalResponseDecoder.ReadAsync(content));
+    }
+}
@@ -0,0 +1,25 @@
+namespace KmitlNetAuth.Core.Tests;
+
+public sealed class PortalResponseTests
+{
+    [Theory]
+    [InlineData("""{"success": true, "token": "abc123"}""", """{"success": true, "token": "<redacted>"}""")]
+    [InlineData("userName=frank&userPass=hunter2&x=1", "userName=frank&userPass=<redacted>&x=1")]
+    [InlineData("echo: s3cret!", "echo: <redacted>")]
+    [InlineData("<html>ok</html>", "<html>ok</html>")]
+    public void Capture_RedactsSecrets(string body, string expected)
+    {
+        var response = PortalResponse.Capture("Login", 200, "application/json", body, password: "s3cret!");
+
+        Assert.Equal(expected, response.Body);
+    }
+
+    [Fact]
+    public void Capture_TruncatesLongBodies()
+    {
+        var response = PortalResponse.Capture("Heartbeat", 200, "text/html", new string('x', PortalResponse.MaxBodyLength + 10));
+
+        Assert.StartsWith(new string('x', PortalResponse.MaxBodyLength), response.Body);
+        Assert.EndsWith("(10 more characters truncated)", response.Body);
+    }
+}
@@ -0,0 +1,66 @@
+using System.Net;
+using System.Text.Json;
+using KmitlNetAuth.Core;
+using KmitlNetAuth.Core.Platform;
+using KmitlNetAuth.Core.Services;
+using Microsoft.Extensions.Logging.Abstractions;
+using NSubstitute;
+
+namespace KmitlNetAuth.Core.Tests;
+
+public sealed class StatusFileWriterTests : IDisposable
+{
+    private readonly string _tempDir = Path.Combine(Path.GetTempPath(), $"kmitl_status_test_{Guid.NewGuid():N}");
+
+    public void Dispose()
+    {
+        if (Directory.Exists(_tempDir))
+            Directory.Delete(_tempDir, true);
+    }
+
+    private (StatusFileWriter Writer, AuthClient Client, Config Config) Create()
+    {
+        var config = new Config
+        {
+            Username = "testuser",
+            Password = "testpass",
+            StatusFile = Path.Combine(_tempDir, "nested", "status.json"),
+        };
+        var handler = new MockHttpHandler();
+        handler.SetResponse(HttpStatusCode.OK, "Login OK");
+        var networkInfo = Substitute.For<INetworkInfo>();
+        networkInfo.GetMacAddress().Returns("aabbccddeeff");
+        var client = new AuthClient(new HttpClient(handler), config, networkInfo, null,
+            new NullNotificationService(), NullLogger<AuthClient>.Instance);
+        return (new StatusFileWriter(config, client, NullLogger<StatusFileWriter>.Instance), client, config);
+    }
+
+    [Fact]
+    public async Task Write_CreatesJsonWithStatusAndCounters()
+    {
+        var (writer, client, config) = Create();
+        await client.LoginAsync();
+        var service = Substitute.For<IAuthService>();
+        service.CurrentStatus.Returns(AuthStatus.Online);
+
+        writer.Write(service);
+
+        using var doc = JsonDocument.Parse(File.ReadAllText(config.StatusFile!));
+        Assert.Equal("Online", doc.RootElement.GetProperty("status").GetString());
+        Assert.Equal("testuser", doc.RootElement.GetProperty("username").GetString());
+        Assert.Equal(1, doc.RootElement.GetProperty("counters").GetProperty("logins").GetInt64());
+        Assert.NotEqual(JsonValueKind.Null, doc.RootElement.GetProperty("last_login_at").ValueKind);
+        Assert.False(File.Exists(config.StatusFile + ".tmp"));
+    }
+
+    [Fact]
+    public async Task RunAsync_IntervalZero_WritesNothing()
+    {
+        var (writer, _, config) = Create();
+        config.StatusFileInterval = 0;
+
+        await writer.RunAsync(Substitute.For<IAuthService>(), CancellationToken.None);
+
+        Assert.False(File.Exists(config.StatusFile));
+    }
+}
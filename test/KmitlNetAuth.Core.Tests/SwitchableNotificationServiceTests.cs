@@ -0,0 +1,23 @@
+using KmitlNetAuth.Core.Platform;
+using NSubstitute;
+
+namespace KmitlNetAuth.Core.Tests;
+
+public sealed class SwitchableNotificationServiceTests
+{
+    [Fact]
+    public void Show_FollowsTheSwitchOnEveryNotification()
+    {
+        var inner = Substitute.For<INotificationService>();
+        var config = new Config { NotificationsEnabled = false };
+        var service = new SwitchableNotificationService(inner, () => config.NotificationsEnabled);
+        var options = new NotificationOptions(NotificationUrgency.Critical);
+
+        service.Show("Off", "body", options);
+        config.NotificationsEnabled = true;
+        service.Show("On", "body", options);
+
+        inner.DidNotReceive().Show("Off", Arg.Any<string>(), Arg.Any<NotificationOptions>());
+        inner.Received(1).Show("On", "body", options);
+    }
+}
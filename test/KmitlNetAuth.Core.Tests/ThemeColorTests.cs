@@ -0,0 +1,48 @@
+using KmitlNetAuth.Core;
+
+namespace KmitlNetAuth.Core.Tests;
+
+public sealed class ThemeColorTests
+{
+    [Theory]
+    [InlineData("#2ECC71", 0x2E, 0xCC, 0x71)]
+    [InlineData("ff8800", 0xFF, 0x88, 0x00)]
+    [InlineData(" Green ", 0x2E, 0xCC, 0x71)]
+    [InlineData("grey", 0x95, 0xA5, 0xA6)]
+    public void TryParse_HexOrName_ReturnsColor(string value, byte r, byte g, byte b)
+    {
+        Assert.True(ThemeColor.TryParse(value, out var color));
+        Assert.Equal(new ThemeColor(r, g, b), color);
+    }
+
+    [Theory]
+    [InlineData(null)]
+    [InlineData("")]
+    [InlineData("#12345")]
+    [InlineData("#GGGGGG")]
+    [InlineData("chartreuse")]
+    public void TryParse_Invalid_ReturnsFalse(string? value)
+    {
+        Assert.False(ThemeColor.TryParse(value, out _));
+    }
+
+    [Fact]
+    public void Formatting_HexAndAnsi()
+    {
+        var color = new ThemeColor(0x0A, 0xFF, 0x00);
+
+        Assert.Equal("#0AFF00", color.ToString());
+        Assert.Equal("\x1b[38;2;10;255;0m", color.ToAnsi());
+    }
+
+    [Fact]
+    public void Config_InvalidThemeColor_IsReported()
+    {
+        var config = new Config { ThemeOnline = "blue", ThemeLogError = "not-a-colour" };
+
+        var errors = config.Validate();
+
+        Assert.Single(errors, e => e.StartsWith("[theme]"));
+        Assert.Contains(errors, e => e.Contains("log_error"));
+    }
+}
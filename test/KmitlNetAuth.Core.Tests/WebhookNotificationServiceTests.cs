@@ -0,0 +1,51 @@
+using System.Net;
+using KmitlNetAuth.Core.Platform;
+using Microsoft.Extensions.Logging.Abstractions;
+
+namespace KmitlNetAuth.Core.Tests;
+
+public sealed class WebhookNotificationServiceTests
+{
+    private readonly MockHttpHandler _handler = new();
+
+    private WebhookNotificationService Create(string url) =>
+        new(new HttpClient(_handler), url, NullLogger<WebhookNotificationService>.Instance);
+
+    [Fact]
+    public async Task SendAsync_Discord_PostsContentJson()
+    {
+        await Create("https://discord.com/api/webhooks/1/abc").SendAsync("Disconnected", "Session dropped");
+
+        Assert.Equal(HttpMethod.Post, _handler.LastRequest!.Method);
+        Assert.Equal("application/json", _handler.LastRequest.Content!.Headers.ContentType!.MediaType);
+        Assert.Equal("{\"content\":\"**Disconnected**\\nSession dropped\"}", _handler.LastRequestContent);
+    }
+
+    [Fact]
+    public async Task SendAsync_Slack_PostsTextJson()
+    {
+        await Create("https://hooks.slack.com/services/T/B/x").SendAsync("Connected", "Online");
+
+        Assert.Equal("{\"text\":\"*Connected*\\nOnline\"}", _handler.LastRequestContent);
+    }
+
+    [Fact]
+    public async Task SendAsync_OtherUrl_PostsPlainTextWithTitleHeader()
+    {
+        await Create("https://ntfy.sh/my-lab-pc").SendAsync("Connected", "Online");
+
+        Assert.Equal("Online", _handler.LastRequestContent);
+        Assert.Equal("Connected", Assert.Single(_handler.LastRequest!.Headers.GetValues("Title")));
+    }
+
+    [Fact]
+    public async Task SendAsync_Failure_DoesNotThrow()
+    {
+        _handler.SetException(new HttpRequestException("Connection refused"));
+
+        await Create("https://ntfy.sh/my-lab-pc").SendAsync("Connected", "Online");
+
+        _handler.SetResponse(HttpStatusCode.InternalServerError);
+        await Create("https://ntfy.sh/my-lab-pc").SendAsync("Connected", "Online");
+    }
+}